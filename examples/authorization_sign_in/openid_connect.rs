@@ -1,6 +1,7 @@
 use graph_rs_sdk::error::IdentityResult;
 use graph_rs_sdk::identity::{
-    ConfidentialClientApplication, OpenIdCredential, Prompt, ResponseMode, ResponseType,
+    AuthorizationResponse, ConfidentialClientApplication, OpenIdCredential, Prompt, ResponseMode,
+    ResponseType,
 };
 use graph_rs_sdk::GraphClient;
 use url::Url;
@@ -50,6 +51,7 @@ fn map_to_credential(
     state: &str,
     scope: Vec<&str>,
     client_secret: &str,
+    authorization_response: AuthorizationResponse,
 ) -> IdentityResult<()> {
     let auth_url_builder = OpenIdCredential::authorization_url_builder(client_id)
         .with_tenant(tenant)
@@ -66,11 +68,10 @@ fn map_to_credential(
     // returned in the POST to the redirect uri.
     let _url = auth_url_builder.url().unwrap();
 
-    // Code returned on redirect uri.
-    let authorization_code = "...";
-
-    // Use the authorization url builder to create the credential builder.
-    let mut credential_builder = auth_url_builder.into_credential(authorization_code);
+    // The authorization_response is the form data posted to the redirect uri.
+    // Use the authorization url builder to create the credential builder. The state,
+    // nonce and id token of the response are validated before the code is redeemed.
+    let mut credential_builder = auth_url_builder.into_credential(authorization_response);
     let mut confidential_client = credential_builder.with_client_secret(client_secret).build();
 
     let _graph_client = GraphClient::from(&confidential_client);
@@ -0,0 +1,122 @@
+use std::fmt::{Display, Formatter};
+
+/// Modules of the sdk that are used by the api clients of every version.
+static SHARED_MODULES: [&str; 2] = ["api_default_imports", "client"];
+
+/// Version of the Microsoft Graph Api that code is generated for.
+///
+/// The v1.0 Api is written to `src/` and the beta Api is written to `src/beta/`
+/// so that both can be compiled into the sdk as separate types.
+#[derive(
+    Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+pub enum ApiVersion {
+    #[default]
+    V1,
+    Beta,
+}
+
+impl ApiVersion {
+    /// Directory that the api clients are written to.
+    pub fn src_dir(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "./src",
+            ApiVersion::Beta => "./src/beta",
+        }
+    }
+
+    /// Module path of the api clients in the sdk crate.
+    pub fn crate_path(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "crate",
+            ApiVersion::Beta => "crate::beta",
+        }
+    }
+
+    /// Changes a path of a file written for the v1.0 Api, such as `./src/users/mod.rs`,
+    /// to the path of the same file for this version.
+    pub fn file_path(&self, file: &str) -> String {
+        file.replacen(ApiVersion::V1.src_dir(), self.src_dir(), 1)
+    }
+
+    /// Changes an import of a v1.0 api client, such as `crate::users::*`, to the
+    /// import of the same module for this version. Imports of modules shared by
+    /// all versions, such as `crate::api_default_imports::*`, are not changed.
+    pub fn import(&self, import: &str) -> String {
+        match import.strip_prefix("crate::") {
+            Some(module) if !SHARED_MODULES.iter().any(|m| module.starts_with(m)) => {
+                format!("{}::{}", self.crate_path(), module)
+            }
+            _ => import.to_string(),
+        }
+    }
+
+    /// Changes the `use` declarations of the source of an api client generated
+    /// for the v1.0 Api to the modules of this version.
+    pub fn imports_source(&self, source: &str) -> String {
+        source
+            .split_inclusive('\n')
+            .map(|line| match line.strip_prefix("use ") {
+                Some(import) => format!("use {}", self.import(import)),
+                None => line.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl Display for ApiVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiVersion::V1 => write!(f, "v1.0"),
+            ApiVersion::Beta => write!(f, "beta"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api_types::{ModFile, WriteConfiguration};
+    use graph_core::resource::ResourceIdentity;
+
+    #[test]
+    fn beta_paths() {
+        assert_eq!(
+            ApiVersion::Beta.file_path("./src/users/mod.rs"),
+            "./src/beta/users/mod.rs"
+        );
+        assert_eq!(
+            ApiVersion::V1.file_path("./src/users/mod.rs"),
+            "./src/users/mod.rs"
+        );
+        assert_eq!(
+            ApiVersion::Beta.imports_source(
+                "// GENERATED CODE\n\nuse crate::api_default_imports::*;\nuse crate::users::*;\n"
+            ),
+            "// GENERATED CODE\n\nuse crate::api_default_imports::*;\nuse crate::beta::users::*;\n"
+        );
+    }
+
+    #[test]
+    fn beta_write_configuration() {
+        let write_configuration =
+            WriteConfiguration::from(ResourceIdentity::Users).for_api_version(ApiVersion::Beta);
+        assert_eq!(write_configuration.api_version, ApiVersion::Beta);
+        assert_eq!(
+            write_configuration.mod_file,
+            Some(ModFile::Declarations {
+                file: "./src/beta/users/mod.rs".into(),
+                declarations: vec!["request".into()],
+            })
+        );
+
+        let write_configuration = write_configuration.for_api_version(ApiVersion::Beta);
+        assert_eq!(
+            write_configuration.mod_file,
+            Some(ModFile::Declarations {
+                file: "./src/beta/users/mod.rs".into(),
+                declarations: vec!["request".into()],
+            })
+        );
+    }
+}
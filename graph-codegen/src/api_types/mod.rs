@@ -1,17 +1,23 @@
+mod api_version;
 mod metadata;
 mod metadata_modifier;
 mod method_macro;
 mod mod_file_writer;
+mod operation_report;
 mod request_client;
 mod request_metadata;
 mod request_task;
+mod sdk_features;
 mod write_configuration;
 
+pub use api_version::*;
 pub use metadata::*;
 pub use metadata_modifier::*;
 pub use method_macro::*;
 pub use mod_file_writer::*;
+pub use operation_report::*;
 pub use request_client::*;
 pub use request_metadata::*;
 pub use request_task::*;
+pub use sdk_features::*;
 pub use write_configuration::*;
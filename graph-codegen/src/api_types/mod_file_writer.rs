@@ -4,7 +4,7 @@ use graph_core::resource::ResourceIdentity;
 use graph_error::{GraphFailure, GraphResult};
 use graph_http::io_tools::create_dir;
 use inflector::Inflector;
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 use std::fs::OpenOptions;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
@@ -95,14 +95,14 @@ impl ModFile {
 #[builder(setter(into, strip_option), default)]
 pub struct ModFileWriter {
     file: PathBuf,
-    declarations: HashSet<String>,
+    declarations: BTreeSet<String>,
 }
 
 impl ModFileWriter {
     pub fn new(file: impl AsRef<Path>, declarations: Vec<String>) -> ModFileWriter {
         ModFileWriter {
             file: file.as_ref().to_path_buf(),
-            declarations: HashSet::from_iter(declarations.iter().map(|s| s.to_string())),
+            declarations: BTreeSet::from_iter(declarations.iter().map(|s| s.to_string())),
         }
     }
 
@@ -0,0 +1,352 @@
+use crate::api_types::ApiVersion;
+use crate::inflector::Inflector;
+use anyhow::anyhow;
+use from_as::*;
+use graph_core::resource::ResourceIdentity;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+lazy_static! {
+    static ref API_CLIENT_REGEX: Regex =
+        Regex::new(r"api_client!\([^;]*?ResourceIdentity::(\w+)\s*\)").unwrap();
+    static ref METHOD_MACRO_REGEX: Regex = Regex::new(
+        r#"\b(get|post|put|patch|delete)!\(\s*(?:doc:\s*"(?:[^"\\]|\\.)*",\s*)?name:\s*(\w+),\s*path:\s*"([^"]*)""#
+    )
+    .unwrap();
+}
+
+/// An api method of a generated api client.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct GeneratedOperation {
+    pub http_method: String,
+    pub path: String,
+    pub name: String,
+}
+
+impl Display for GeneratedOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.http_method, self.name, self.path)
+    }
+}
+
+/// An api method that has the same http method and path but a different method name.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct RenamedOperation {
+    pub http_method: String,
+    pub path: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl Display for RenamedOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} -> {} {}",
+            self.http_method, self.from, self.to, self.path
+        )
+    }
+}
+
+/// The api methods of the generated api clients grouped by the [`ResourceIdentity`]
+/// of the api client, read from the `request.rs` files of the sdk.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedOperations {
+    pub operations: BTreeMap<ResourceIdentity, BTreeSet<GeneratedOperation>>,
+}
+
+impl GeneratedOperations {
+    /// Reads the api methods of every api client written for the api version. Api clients
+    /// of other versions that are written to a sub directory, such as `src/beta`, are skipped.
+    pub fn read(api_version: ApiVersion) -> anyhow::Result<GeneratedOperations> {
+        let skip: Vec<PathBuf> = [ApiVersion::V1, ApiVersion::Beta]
+            .iter()
+            .filter(|version| api_version.ne(version))
+            .map(|version| PathBuf::from(version.src_dir()))
+            .collect();
+
+        let mut generated_operations = GeneratedOperations::default();
+        generated_operations.read_dir(api_version.src_dir(), &skip)?;
+        Ok(generated_operations)
+    }
+
+    /// Parses the api methods of the source of a generated `request.rs` file.
+    pub fn from_source(source: &str) -> anyhow::Result<GeneratedOperations> {
+        let mut generated_operations = GeneratedOperations::default();
+        generated_operations.extend_source(source)?;
+        Ok(generated_operations)
+    }
+
+    fn read_dir<P: AsRef<Path>>(&mut self, dir: P, skip: &[PathBuf]) -> anyhow::Result<()> {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(dir.as_ref())?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                if !skip.contains(&path) {
+                    self.read_dir(&path, skip)?;
+                }
+            } else if path.file_name().and_then(|name| name.to_str()) == Some("request.rs") {
+                let source = std::fs::read_to_string(&path)?;
+                self.extend_source(&source)
+                    .map_err(|err| anyhow!("{}: {err}", path.display()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn extend_source(&mut self, source: &str) -> anyhow::Result<()> {
+        let mut api_clients = Vec::new();
+        for captures in API_CLIENT_REGEX.captures_iter(source) {
+            let variant = &captures[1];
+            let resource_identity = ResourceIdentity::from_str(&variant.to_camel_case())
+                .map_err(|_| anyhow!("Unable to find variant for ResourceIdentity::{variant}"))?;
+            api_clients.push((captures.get(0).unwrap().start(), resource_identity));
+        }
+
+        for captures in METHOD_MACRO_REGEX.captures_iter(source) {
+            let start = captures.get(0).unwrap().start();
+            let resource_identity = api_clients
+                .iter()
+                .rev()
+                .find(|(api_client_start, _)| *api_client_start < start)
+                .map(|(_, resource_identity)| *resource_identity)
+                .ok_or_else(|| anyhow!("Api method {} has no api client", &captures[2]))?;
+
+            self.operations
+                .entry(resource_identity)
+                .or_default()
+                .insert(GeneratedOperation {
+                    http_method: captures[1].to_uppercase(),
+                    path: captures[3].to_string(),
+                    name: captures[2].to_string(),
+                });
+        }
+
+        Ok(())
+    }
+
+    /// Compares these api methods to the api methods after regenerating the api clients.
+    pub fn diff(&self, regenerated: &GeneratedOperations) -> OperationReport {
+        let empty = BTreeSet::new();
+        let resource_identities: BTreeSet<&ResourceIdentity> = self
+            .operations
+            .keys()
+            .chain(regenerated.operations.keys())
+            .collect();
+
+        let mut report = OperationReport::default();
+        for resource_identity in resource_identities {
+            let previous = self.operations.get(resource_identity).unwrap_or(&empty);
+            let current = regenerated
+                .operations
+                .get(resource_identity)
+                .unwrap_or(&empty);
+            let resource_report = ResourceOperationReport::new(previous, current);
+            if !resource_report.is_empty() {
+                report.resources.insert(*resource_identity, resource_report);
+            }
+        }
+        report
+    }
+}
+
+/// Api methods added, removed, or renamed for a single [`ResourceIdentity`].
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ResourceOperationReport {
+    pub added: BTreeSet<GeneratedOperation>,
+    pub removed: BTreeSet<GeneratedOperation>,
+    pub renamed: BTreeSet<RenamedOperation>,
+}
+
+impl ResourceOperationReport {
+    fn new(
+        previous: &BTreeSet<GeneratedOperation>,
+        current: &BTreeSet<GeneratedOperation>,
+    ) -> ResourceOperationReport {
+        let mut added: BTreeSet<GeneratedOperation> =
+            current.difference(previous).cloned().collect();
+        let mut removed: BTreeSet<GeneratedOperation> =
+            previous.difference(current).cloned().collect();
+        let mut renamed = BTreeSet::new();
+
+        for operation in removed.clone() {
+            let renamed_operation = added
+                .iter()
+                .find(|op| op.http_method == operation.http_method && op.path == operation.path)
+                .cloned();
+
+            if let Some(renamed_operation) = renamed_operation {
+                added.remove(&renamed_operation);
+                removed.remove(&operation);
+                renamed.insert(RenamedOperation {
+                    http_method: operation.http_method,
+                    path: operation.path,
+                    from: operation.name,
+                    to: renamed_operation.name,
+                });
+            }
+        }
+
+        ResourceOperationReport {
+            added,
+            removed,
+            renamed,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+    }
+}
+
+/// Report of the api methods that changed per [`ResourceIdentity`] when regenerating
+/// the api clients. Only resources with changes are included.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize, FromFile, AsFile)]
+pub struct OperationReport {
+    pub resources: BTreeMap<ResourceIdentity, ResourceOperationReport>,
+}
+
+impl OperationReport {
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+}
+
+impl Display for OperationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No operations changed");
+        }
+
+        for (resource_identity, report) in self.resources.iter() {
+            writeln!(
+                f,
+                "{resource_identity:?}: {} added, {} removed, {} renamed",
+                report.added.len(),
+                report.removed.len(),
+                report.renamed.len()
+            )?;
+            for operation in report.added.iter() {
+                writeln!(f, "  + {operation}")?;
+            }
+            for operation in report.removed.iter() {
+                writeln!(f, "  - {operation}")?;
+            }
+            for operation in report.renamed.iter() {
+                writeln!(f, "  ~ {operation}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static SOURCE: &str = r#"// GENERATED CODE
+
+use crate::api_default_imports::*;
+
+api_client!(UsersApiClient, UsersIdApiClient, ResourceIdentity::Users);
+
+impl UsersApiClient {
+    get!(
+        doc: "List users",
+        name: list_user,
+        path: "/users"
+    );
+}
+
+impl UsersIdApiClient {
+    get!(
+        doc: "Get entity from users by key",
+        name: get_user,
+        path: "/users/{{RID}}"
+    );
+    delete!(
+        name: delete_user,
+        path: "/users/{{RID}}"
+    );
+}
+"#;
+
+    #[test]
+    fn operations_from_source() {
+        let generated_operations = GeneratedOperations::from_source(SOURCE).unwrap();
+        let operations = generated_operations
+            .operations
+            .get(&ResourceIdentity::Users)
+            .unwrap();
+        assert_eq!(3, operations.len());
+        assert!(operations.contains(&GeneratedOperation {
+            http_method: "DELETE".into(),
+            path: "/users/{{RID}}".into(),
+            name: "delete_user".into(),
+        }));
+    }
+
+    #[test]
+    fn operations_diff() {
+        let previous = GeneratedOperations::from_source(SOURCE).unwrap();
+        let regenerated = GeneratedOperations::from_source(
+            &SOURCE.replace("name: get_user", "name: get_users").replace(
+                "name: delete_user,\n        path: \"/users/{{RID}}\"",
+                "name: delta,\n        path: \"/users/delta()\"",
+            ),
+        )
+        .unwrap();
+
+        let report = previous.diff(&regenerated);
+        let users = report.resources.get(&ResourceIdentity::Users).unwrap();
+        assert_eq!(
+            vec!["delta"],
+            users
+                .added
+                .iter()
+                .map(|op| op.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["delete_user"],
+            users
+                .removed
+                .iter()
+                .map(|op| op.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![("get_user", "get_users")],
+            users
+                .renamed
+                .iter()
+                .map(|op| (op.from.as_str(), op.to.as_str()))
+                .collect::<Vec<_>>()
+        );
+
+        assert!(previous.diff(&previous).is_empty());
+    }
+
+    #[test]
+    fn read_sdk_operations() {
+        let mut generated_operations = GeneratedOperations::default();
+        generated_operations
+            .read_dir("../src", &[PathBuf::from("../src/beta")])
+            .unwrap();
+
+        assert!(generated_operations
+            .operations
+            .get(&ResourceIdentity::Users)
+            .unwrap()
+            .iter()
+            .any(|op| op.name.eq("list_user")));
+    }
+}
@@ -9,6 +9,7 @@ use crate::parser::HttpMethod;
 use crate::traits::{FilterMetadata, RequestParser, INTERNAL_PATH_ID};
 use from_as::*;
 use graph_core::resource::ResourceIdentity;
+use graph_error::GraphFailure;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::io::{Read, Write};
 use std::str::FromStr;
@@ -698,9 +699,11 @@ impl From<(WriteConfiguration, &OpenApi)> for PathMetadataQueue {
     }
 }
 
-impl From<WriteConfiguration> for PathMetadataQueue {
-    fn from(resource_parsing_info: WriteConfiguration) -> Self {
-        let open_api = OpenApi::default();
+impl TryFrom<WriteConfiguration> for PathMetadataQueue {
+    type Error = GraphFailure;
+
+    fn try_from(resource_parsing_info: WriteConfiguration) -> Result<Self, Self::Error> {
+        let open_api = OpenApi::try_from(resource_parsing_info.api_version)?;
         let requests = {
             if let Some(trim_pat) = resource_parsing_info.trim_path_start.as_ref() {
                 open_api
@@ -753,6 +756,6 @@ impl From<WriteConfiguration> for PathMetadataQueue {
             metadata_queue.format_path_parameters();
             metadata_queue.transform_id_metadata(path_filter.as_str());
         }
-        metadata_queue
+        Ok(metadata_queue)
     }
 }
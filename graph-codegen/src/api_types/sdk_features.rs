@@ -0,0 +1,319 @@
+use crate::api_types::ApiVersion;
+use anyhow::anyhow;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Modules declared in `src/lib.rs` that are not api client modules or that have
+/// their own feature.
+static NON_RESOURCE_MODULES: [&str; 1] = ["beta"];
+
+static FEATURES_START: &str =
+    "# Api client features. Generated by graph-codegen, changes are overwritten.";
+static FEATURES_END: &str = "# End of api client features.";
+
+lazy_static! {
+    static ref MOD_REGEX: Regex = Regex::new(r"^pub mod (\w+);$").unwrap();
+    static ref CRATE_PATH_REGEX: Regex = Regex::new(r"\bcrate::(\w+)").unwrap();
+    static ref USE_CRATE_REGEX: Regex = Regex::new(r"^use crate::(\w+)::").unwrap();
+    static ref API_CLIENT_REGEX: Regex = Regex::new(r"\b(\w+ApiClient)\b").unwrap();
+    static ref CFG_FEATURE_REGEX: Regex =
+        Regex::new(r#"^#\[cfg\(feature = "[\w-]+"\)\]$"#).unwrap();
+}
+
+/// The cargo features of the sdk. Every top level api client module in `src/` is gated
+/// by a feature and the feature depends on the features of the modules that the api
+/// clients of the module use. The `full` feature enables every api client module.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SdkFeatures {
+    /// Module name to the names of the other modules it uses.
+    pub modules: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SdkFeatures {
+    /// Reads the api client modules declared in `lib.rs` of the source directory and the
+    /// modules that each one uses.
+    pub fn read<P: AsRef<Path>>(src_dir: P) -> anyhow::Result<SdkFeatures> {
+        let src_dir = src_dir.as_ref();
+        let lib = std::fs::read_to_string(src_dir.join("lib.rs"))?;
+
+        let mut modules: BTreeMap<String, BTreeSet<String>> = lib
+            .lines()
+            .filter_map(|line| MOD_REGEX.captures(line.trim()))
+            .map(|captures| captures[1].to_string())
+            .filter(|module| !NON_RESOURCE_MODULES.contains(&module.as_str()))
+            .map(|module| (module, BTreeSet::new()))
+            .collect();
+
+        let names: BTreeSet<String> = modules.keys().cloned().collect();
+        for (module, dependencies) in modules.iter_mut() {
+            let mut sources = Vec::new();
+            SdkFeatures::read_sources(src_dir.join(module), &mut sources)?;
+            for source in sources.iter() {
+                dependencies.extend(
+                    CRATE_PATH_REGEX
+                        .captures_iter(source)
+                        .map(|captures| captures[1].to_string())
+                        .filter(|dependency| dependency.ne(module) && names.contains(dependency)),
+                );
+            }
+        }
+
+        Ok(SdkFeatures { modules })
+    }
+
+    fn read_sources(dir: PathBuf, sources: &mut Vec<String>) -> anyhow::Result<()> {
+        if !dir.is_dir() {
+            return Err(anyhow!("Missing module directory {}", dir.display()));
+        }
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                SdkFeatures::read_sources(path, sources)?;
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some("rs") {
+                sources.push(std::fs::read_to_string(path)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Name of the cargo feature of a module, such as `device-management` for the
+    /// `device_management` module.
+    pub fn feature_name(module: &str) -> String {
+        module.replace('_', "-")
+    }
+
+    /// The `[features]` entries of the api client modules.
+    pub fn cargo_features(&self) -> String {
+        let mut features = String::new();
+        features.push_str("full = [\n");
+        for module in self.modules.keys() {
+            let _ = writeln!(features, "    \"{}\",", SdkFeatures::feature_name(module));
+        }
+        features.push_str("]\n");
+
+        for (module, dependencies) in self.modules.iter() {
+            let dependencies: Vec<String> = dependencies
+                .iter()
+                .map(|dependency| format!("\"{}\"", SdkFeatures::feature_name(dependency)))
+                .collect();
+            let _ = writeln!(
+                features,
+                "{} = [{}]",
+                SdkFeatures::feature_name(module),
+                dependencies.join(", ")
+            );
+        }
+        features
+    }
+
+    /// Replaces the api client features in the `[features]` table of the Cargo.toml.
+    /// The features are appended to the end of the table if they have not been written before.
+    pub fn write_cargo_features<P: AsRef<Path>>(&self, cargo_toml: P) -> anyhow::Result<()> {
+        let manifest = std::fs::read_to_string(cargo_toml.as_ref())?;
+        let features = format!(
+            "{FEATURES_START}\n{}{FEATURES_END}\n",
+            self.cargo_features()
+        );
+
+        let manifest = match (manifest.find(FEATURES_START), manifest.find(FEATURES_END)) {
+            (Some(start), Some(end)) => {
+                let end = end + FEATURES_END.len() + 1;
+                format!("{}{features}{}", &manifest[..start], &manifest[end..])
+            }
+            _ => {
+                let table = manifest
+                    .find("[features]\n")
+                    .ok_or_else(|| anyhow!("Missing [features] table"))?;
+                let end = manifest[table..]
+                    .find("\n\n")
+                    .map(|end| table + end + 2)
+                    .unwrap_or(manifest.len());
+                format!("{}{features}\n{}", &manifest[..end], &manifest[end..])
+            }
+        };
+
+        std::fs::write(cargo_toml, manifest)?;
+        Ok(())
+    }
+
+    /// Adds the `#[cfg(feature = "...")]` attribute to the declaration of each api
+    /// client module in `lib.rs`.
+    pub fn lib_source(&self, lib: &str) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        for line in lib.lines() {
+            if let Some(captures) = MOD_REGEX.captures(line.trim()) {
+                if self.modules.contains_key(&captures[1]) {
+                    if lines
+                        .last()
+                        .map(|last| last.starts_with("#[cfg(feature = "))
+                        .unwrap_or_default()
+                    {
+                        lines.pop();
+                    }
+                    lines.push(format!(
+                        "#[cfg(feature = \"{}\")]",
+                        SdkFeatures::feature_name(&captures[1])
+                    ));
+                }
+            }
+            lines.push(line.to_string());
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Writes the feature attributes of the api client modules to `lib.rs`.
+    pub fn write_lib<P: AsRef<Path>>(&self, lib_rs: P) -> anyhow::Result<()> {
+        let lib = std::fs::read_to_string(lib_rs.as_ref())?;
+        std::fs::write(lib_rs, self.lib_source(&lib))?;
+        Ok(())
+    }
+
+    /// Adds the `#[cfg(feature = "...")]` attribute to the imports of the api clients in
+    /// `client/graph.rs` and to the methods of `GraphClient` that return an api client.
+    ///
+    /// The feature of a method is the feature of the module that the first api client
+    /// type used by the method is imported from.
+    pub fn graph_client_source(&self, source: &str) -> String {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut api_client_modules: BTreeMap<String, String> = BTreeMap::new();
+        let mut items: Vec<(usize, usize, Option<String>)> = Vec::new();
+
+        let mut index = 0;
+        while index < lines.len() {
+            let line = lines[index];
+            let trimmed = line.trim_start();
+            let indent = &line[..line.len() - trimmed.len()];
+            let end = if trimmed.starts_with("use crate::") {
+                lines[index..].iter().position(|line| line.ends_with(';'))
+            } else if trimmed.starts_with("api_client_impl") {
+                lines[index..].iter().position(|line| line.ends_with(");"))
+            } else if trimmed.starts_with("pub fn ") {
+                let closing = format!("{indent}}}");
+                lines[index..].iter().position(|line| line.eq(&closing))
+            } else {
+                None
+            };
+
+            let Some(end) = end.map(|end| index + end) else {
+                index += 1;
+                continue;
+            };
+
+            let item = lines[index..=end].join("\n");
+            let module = USE_CRATE_REGEX
+                .captures(trimmed)
+                .map(|captures| captures[1].to_string());
+            if let Some(module) = module.as_ref() {
+                for captures in API_CLIENT_REGEX.captures_iter(&item) {
+                    api_client_modules.insert(captures[1].to_string(), module.clone());
+                }
+            }
+            items.push((index, end, module));
+            index = end + 1;
+        }
+
+        let mut features: BTreeMap<usize, String> = BTreeMap::new();
+        for (start, end, module) in items {
+            let module = module.or_else(|| {
+                let item = lines[start..=end].join("\n");
+                API_CLIENT_REGEX
+                    .captures(&item)
+                    .and_then(|captures| api_client_modules.get(&captures[1]).cloned())
+            });
+            if let Some(module) = module.filter(|module| self.modules.contains_key(module)) {
+                features.insert(start, SdkFeatures::feature_name(&module));
+            }
+        }
+
+        let mut output: Vec<String> = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            if let Some(feature) = features.get(&index) {
+                if output
+                    .last()
+                    .map(|last| CFG_FEATURE_REGEX.is_match(last.trim()))
+                    .unwrap_or_default()
+                {
+                    output.pop();
+                }
+                let indent = &line[..line.len() - line.trim_start().len()];
+                output.push(format!("{indent}#[cfg(feature = \"{feature}\")]"));
+            }
+            output.push(line.to_string());
+        }
+        output.join("\n") + "\n"
+    }
+
+    /// Writes the feature attributes of the api clients to `client/graph.rs`.
+    pub fn write_graph_client<P: AsRef<Path>>(&self, graph_rs: P) -> anyhow::Result<()> {
+        let source = std::fs::read_to_string(graph_rs.as_ref())?;
+        std::fs::write(graph_rs, self.graph_client_source(&source))?;
+        Ok(())
+    }
+
+    /// Updates the Cargo.toml, lib.rs and client/graph.rs of the sdk. Only the v1.0 api
+    /// clients have their own features.
+    pub fn write(api_version: ApiVersion) -> anyhow::Result<()> {
+        if api_version != ApiVersion::V1 {
+            return Ok(());
+        }
+
+        let sdk_features = SdkFeatures::read(api_version.src_dir())?;
+        sdk_features.write_cargo_features("./Cargo.toml")?;
+        sdk_features.write_lib(format!("{}/lib.rs", api_version.src_dir()))?;
+        sdk_features.write_graph_client(format!("{}/client/graph.rs", api_version.src_dir()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lib_source() {
+        let mut sdk_features = SdkFeatures::default();
+        sdk_features
+            .modules
+            .insert("device_management".into(), BTreeSet::new());
+        sdk_features.modules.insert("users".into(), BTreeSet::new());
+
+        let lib = "pub mod batch;\n#[cfg(feature = \"beta\")]\npub mod beta;\npub mod device_management;\n#[cfg(feature = \"users\")]\npub mod users;\n";
+        let expected = "pub mod batch;\n#[cfg(feature = \"beta\")]\npub mod beta;\n#[cfg(feature = \"device-management\")]\npub mod device_management;\n#[cfg(feature = \"users\")]\npub mod users;\n";
+        assert_eq!(expected, sdk_features.lib_source(lib));
+        assert_eq!(expected, sdk_features.lib_source(expected));
+    }
+
+    #[test]
+    fn graph_client_source() {
+        let mut sdk_features = SdkFeatures::default();
+        sdk_features.modules.insert("batch".into(), BTreeSet::new());
+        sdk_features.modules.insert("users".into(), BTreeSet::new());
+
+        let source = "use crate::api_default_imports::*;\nuse crate::batch::BatchApiClient;\n#[cfg(feature = \"beta\")]\nuse crate::beta::BetaGraphClient;\n#[cfg(feature = \"users\")]\nuse crate::users::{\n    UsersApiClient, UsersIdApiClient,\n};\n\nimpl GraphClient {\n    api_client_impl!(users, UsersApiClient, user, UsersIdApiClient);\n\n    pub fn custom(&self) -> RequestHandler {\n        todo!()\n    }\n\n    pub fn batch(&self) -> RequestHandler {\n        BatchApiClient::new().batch()\n    }\n}\n";
+        let expected = "use crate::api_default_imports::*;\n#[cfg(feature = \"batch\")]\nuse crate::batch::BatchApiClient;\n#[cfg(feature = \"beta\")]\nuse crate::beta::BetaGraphClient;\n#[cfg(feature = \"users\")]\nuse crate::users::{\n    UsersApiClient, UsersIdApiClient,\n};\n\nimpl GraphClient {\n    #[cfg(feature = \"users\")]\n    api_client_impl!(users, UsersApiClient, user, UsersIdApiClient);\n\n    pub fn custom(&self) -> RequestHandler {\n        todo!()\n    }\n\n    #[cfg(feature = \"batch\")]\n    pub fn batch(&self) -> RequestHandler {\n        BatchApiClient::new().batch()\n    }\n}\n";
+        assert_eq!(expected, sdk_features.graph_client_source(source));
+        assert_eq!(expected, sdk_features.graph_client_source(expected));
+    }
+
+    #[test]
+    fn sdk_features_are_up_to_date() {
+        let sdk_features = SdkFeatures::read("../src").unwrap();
+        let me = sdk_features.modules.get("me").unwrap();
+        assert!(me.contains("users"));
+        assert!(!sdk_features.modules.contains_key("beta"));
+
+        let manifest = std::fs::read_to_string("../Cargo.toml").unwrap();
+        assert!(manifest.contains(&sdk_features.cargo_features()));
+
+        let lib = std::fs::read_to_string("../src/lib.rs").unwrap();
+        assert_eq!(lib, sdk_features.lib_source(&lib));
+
+        let graph_client = std::fs::read_to_string("../src/client/graph.rs").unwrap();
+        assert_eq!(
+            graph_client,
+            sdk_features.graph_client_source(&graph_client)
+        );
+    }
+}
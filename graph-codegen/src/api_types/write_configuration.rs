@@ -1,4 +1,4 @@
-use crate::api_types::ModFile;
+use crate::api_types::{ApiVersion, ModFile};
 use crate::settings::ApiClientLinkSettings;
 use from_as::*;
 use graph_core::resource::ResourceIdentity;
@@ -66,6 +66,11 @@ pub struct WriteConfiguration {
     /// This is commonly used for APIs that have a manual_request.rs file that implements
     /// API methods for Microsoft Graph that are not in the OpenAPI metadata file.
     pub custom_modules: Vec<&'static str>,
+
+    /// The version of the Api to generate. Beta clients are written to `src/beta`
+    /// from the beta OpenApi metadata. Use [`WriteConfiguration::for_api_version`]
+    /// to change the version of an existing configuration.
+    pub api_version: ApiVersion,
 }
 
 impl WriteConfiguration {
@@ -96,6 +101,33 @@ impl WriteConfiguration {
         builder
     }
 
+    /// Returns the configuration for generating the same resource from another version
+    /// of the Api. The mod files of the resource and its children are moved to the
+    /// directory of the version.
+    pub fn for_api_version(mut self, api_version: ApiVersion) -> WriteConfiguration {
+        let mod_file = self
+            .mod_file
+            .take()
+            .unwrap_or_else(|| ModFile::base_mod_from_ri(self.resource_identity));
+        self.mod_file = Some(match mod_file {
+            ModFile::Declarations { file, declarations } => {
+                let v1_file =
+                    file.replacen(self.api_version.src_dir(), ApiVersion::V1.src_dir(), 1);
+                ModFile::Declarations {
+                    file: api_version.file_path(&v1_file),
+                    declarations,
+                }
+            }
+        });
+        self.children = self
+            .children
+            .into_iter()
+            .map(|child| child.for_api_version(api_version))
+            .collect();
+        self.api_version = api_version;
+        self
+    }
+
     pub fn implement_children_mods(&mut self) {
         if !self.children.is_empty() {
             if let Some(mod_file) = self.mod_file.as_mut() {
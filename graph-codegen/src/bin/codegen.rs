@@ -0,0 +1,105 @@
+//! Regenerates the api clients of the sdk from a local OpenApi metadata file and prints
+//! a report of the api methods that were added, removed, or renamed per resource.
+//!
+//! Run from the root of the repository:
+//!
+//! ```bash
+//! cargo run -p graph-codegen --bin codegen -- ./openapi.yaml
+//! cargo run -p graph-codegen --bin codegen -- ./openapi-beta.yaml --beta --report report.json
+//! ```
+//!
+//! After regenerating the v1.0 api clients the cargo feature of each api client module is
+//! written to Cargo.toml, `src/lib.rs` and `src/client/graph.rs`. Use `--features` to only
+//! update the features.
+//!
+//! The metadata can be a yaml or json file. Because the metadata is not downloaded,
+//! regenerating the api clients from the same file always writes the same code.
+//! Run `cargo fmt` after regenerating the api clients.
+use from_as::*;
+use graph_codegen::api_types::{ApiVersion, GeneratedOperations, SdkFeatures};
+use graph_codegen::macros::OpenApiParser;
+use graph_codegen::openapi::OpenApi;
+use graph_codegen::settings::{get_generated_resource_identities, get_write_configuration};
+use std::path::{Path, PathBuf};
+
+static USAGE: &str =
+    "usage: codegen <openapi.yaml | openapi.json> [--beta] [--report <report.json>]\n       codegen --features";
+
+struct Args {
+    open_api_file: PathBuf,
+    api_version: ApiVersion,
+    report_file: Option<PathBuf>,
+    features_only: bool,
+}
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut open_api_file = None;
+    let mut api_version = ApiVersion::V1;
+    let mut report_file = None;
+    let mut features_only = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--beta" => api_version = ApiVersion::Beta,
+            "--features" => features_only = true,
+            "--report" => {
+                report_file =
+                    Some(PathBuf::from(args.next().ok_or_else(|| {
+                        anyhow::anyhow!("--report requires a file\n{USAGE}")
+                    })?))
+            }
+            _ if open_api_file.is_none() && !arg.starts_with("--") => {
+                open_api_file = Some(PathBuf::from(arg))
+            }
+            _ => return Err(anyhow::anyhow!("unexpected argument {arg}\n{USAGE}")),
+        }
+    }
+
+    if open_api_file.is_none() && !features_only {
+        return Err(anyhow::anyhow!(USAGE));
+    }
+
+    Ok(Args {
+        open_api_file: open_api_file.unwrap_or_default(),
+        api_version,
+        report_file,
+        features_only,
+    })
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = parse_args()?;
+
+    if !Path::new("./src/lib.rs").exists() {
+        return Err(anyhow::anyhow!(
+            "codegen must be run from the root of the graph-rs-sdk repository"
+        ));
+    }
+
+    if args.features_only {
+        return SdkFeatures::write(ApiVersion::V1);
+    }
+
+    let open_api = OpenApi::from_file(&args.open_api_file)?;
+    let previous = GeneratedOperations::read(args.api_version)?;
+
+    for resource_identity in get_generated_resource_identities(args.api_version) {
+        let write_configuration = match args.api_version {
+            ApiVersion::V1 => get_write_configuration(resource_identity),
+            api_version => get_write_configuration(resource_identity).for_api_version(api_version),
+        };
+        OpenApi::write_from(write_configuration, &open_api);
+    }
+
+    SdkFeatures::write(args.api_version)?;
+
+    let report = previous.diff(&GeneratedOperations::read(args.api_version)?);
+    println!("{report}");
+
+    if let Some(report_file) = args.report_file.as_ref() {
+        report.as_file_pretty(report_file)?;
+    }
+
+    Ok(())
+}
@@ -1,8 +1,8 @@
+use crate::api_types::{ApiVersion, ModWriteConfiguration, WriteConfiguration};
 use crate::api_types::{
     Metadata, MethodMacro, ModFile, PathMetadataQueue, RequestClientList, RequestMetadata,
     RequestTask,
 };
-use crate::api_types::{ModWriteConfiguration, WriteConfiguration};
 use crate::inflector::Inflector;
 use crate::openapi::OpenApi;
 use crate::settings::{get_method_macro_modifiers, ResourceSettings};
@@ -11,7 +11,8 @@ use bytes::{BufMut, BytesMut};
 use from_as::*;
 use graph_core::resource::ResourceIdentity;
 use graph_http::io_tools::create_dir;
-use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::fmt::Debug;
 use std::fmt::Write as _;
 use std::fs::{File, OpenOptions};
@@ -342,7 +343,7 @@ pub trait MacroImplWriter {
     fn get_impl_bytes(&self) -> anyhow::Result<BytesMut> {
         let mut v = BTreeSet::new();
         let mut buf = BytesMut::new();
-        let mut imports: HashSet<String> = HashSet::new();
+        let mut imports: BTreeSet<String> = BTreeSet::new();
         let path_metadata_map = self.path_metadata_map();
         let keys: Vec<_> = path_metadata_map.keys().cloned().collect();
         let values: Vec<_> = path_metadata_map.values().cloned().collect();
@@ -455,7 +456,7 @@ pub trait MacroImplWriter {
             let api_client_name = format!("{name}ApiClient");
             buf.put(format!("\nimpl {api_client_name} {{").as_bytes());
 
-            let mut set = HashSet::new();
+            let mut set = BTreeSet::new();
             for setting in settings.iter() {
                 for link in setting.api_client_links.iter() {
                     if let Some(api_client) = link.0.as_ref() {
@@ -494,8 +495,10 @@ pub trait MacroImplWriter {
         Ok(buf)
     }
 
-    fn get_impl_metadata(resource_parsing_info: WriteConfiguration) -> PathMetadataQueue {
-        PathMetadataQueue::from(resource_parsing_info)
+    fn get_impl_metadata(
+        resource_parsing_info: WriteConfiguration,
+    ) -> anyhow::Result<PathMetadataQueue> {
+        Ok(PathMetadataQueue::try_from(resource_parsing_info)?)
     }
 
     fn get_metadata_method_macros(
@@ -532,7 +535,7 @@ pub trait MacroImplWriter {
         }
     }
 
-    fn write_mod_file(&self, mod_file: &ModFile) {
+    fn write_mod_file(&self, mod_file: &ModFile, api_version: ApiVersion) {
         mod_file.write();
 
         match mod_file {
@@ -540,7 +543,9 @@ pub trait MacroImplWriter {
                 file,
                 declarations: _,
             } => {
-                if let Ok(mut buf) = self.get_impl_bytes() {
+                if let Ok(buf) = self.get_impl_bytes() {
+                    let source = std::str::from_utf8(buf.as_ref()).unwrap();
+                    let mut buf = BytesMut::from(api_version.imports_source(source).as_bytes());
                     let request_file = file.replace("mod.rs", "request.rs");
                     let mut f = OpenOptions::new()
                         .write(true)
@@ -558,7 +563,15 @@ pub trait MacroImplWriter {
 }
 
 pub trait OpenApiParser {
-    fn write(mut write_configuration: WriteConfiguration) {
+    fn write(write_configuration: WriteConfiguration) -> anyhow::Result<()> {
+        let open_api = OpenApi::try_from(write_configuration.api_version)?;
+        OpenApi::write_from(write_configuration, &open_api);
+        Ok(())
+    }
+
+    /// Same as [`write`](OpenApiParser::write) but uses the given metadata instead of
+    /// downloading the metadata for the api version of the write configuration.
+    fn write_from(mut write_configuration: WriteConfiguration, open_api: &OpenApi) {
         let name = {
             if let Some(name) = write_configuration.modifier_name.as_ref() {
                 name.to_string()
@@ -569,8 +582,6 @@ pub trait OpenApiParser {
 
         write_configuration.implement_children_mods();
 
-        let open_api = OpenApi::default();
-
         dbg!(&write_configuration);
 
         for write_config in write_configuration.children.iter() {
@@ -589,7 +600,7 @@ pub trait OpenApiParser {
             OpenApi::write_using(write_config.clone(), &open_api2);
         }
 
-        let mut open_api2 = open_api;
+        let mut open_api2 = open_api.clone();
         open_api2.paths = open_api2.filter_path_contains(&write_configuration.path);
         let metadata_queue = PathMetadataQueue::from((write_configuration.clone(), &open_api2));
 
@@ -597,7 +608,7 @@ pub trait OpenApiParser {
         // dbg!(&metadata_queue);
 
         if let Some(mod_file) = write_configuration.mod_file.as_ref() {
-            metadata_queue.write_mod_file(mod_file);
+            metadata_queue.write_mod_file(mod_file, write_configuration.api_version);
         } else {
             metadata_queue.write_impl(name.as_str());
         }
@@ -641,7 +652,7 @@ pub trait OpenApiParser {
         // dbg!(&metadata_queue);
 
         if let Some(mod_file) = write_configuration.mod_file.as_ref() {
-            metadata_queue.write_mod_file(mod_file);
+            metadata_queue.write_mod_file(mod_file, write_configuration.api_version);
         } else {
             metadata_queue.write_impl(name.as_str());
         }
@@ -667,8 +678,28 @@ pub trait OpenApiParser {
          */
     }
 
-    fn write_all(write_configurations: Vec<WriteConfiguration>) {
-        let open_api = OpenApi::default();
+    fn write_all(write_configurations: Vec<WriteConfiguration>) -> anyhow::Result<()> {
+        let mut open_api_versions: BTreeMap<ApiVersion, OpenApi> = BTreeMap::new();
+        for write_configuration in write_configurations {
+            let open_api = match open_api_versions.entry(write_configuration.api_version) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(OpenApi::try_from(write_configuration.api_version)?)
+                }
+            };
+            let mut open_api2 = open_api.clone();
+            if let Some(start_path) = write_configuration.trim_path_start.as_ref() {
+                open_api2.paths = open_api2.filter_path(start_path);
+                open_api2.paths = open_api2.filter_path_contains(&write_configuration.path);
+            }
+            OpenApi::write_using(write_configuration, &open_api2);
+        }
+        Ok(())
+    }
+
+    /// Same as [`write_all`](OpenApiParser::write_all) but uses the given metadata for
+    /// every write configuration.
+    fn write_all_from(write_configurations: Vec<WriteConfiguration>, open_api: &OpenApi) {
         for write_configuration in write_configurations {
             let mut open_api2 = open_api.clone();
             if let Some(start_path) = write_configuration.trim_path_start.as_ref() {
@@ -682,17 +713,17 @@ pub trait OpenApiParser {
     fn write_metadata<P: AsRef<Path>>(
         resource_parsing_info: WriteConfiguration,
         path: &P,
-    ) -> Result<(), FromAsError> {
-        let metadata_queue = PathMetadataQueue::from(resource_parsing_info);
+    ) -> anyhow::Result<()> {
+        let metadata_queue = PathMetadataQueue::try_from(resource_parsing_info)?;
         metadata_queue.debug_print();
         let path_buf = path.as_ref().to_path_buf();
-        metadata_queue.as_file_pretty(path_buf)
+        Ok(metadata_queue.as_file_pretty(path_buf)?)
     }
 
     fn get_metadata_method_macros(
         resource_parsing_info: WriteConfiguration,
-    ) -> BTreeSet<MethodMacro> {
-        let metadata_queue = PathMetadataQueue::from(resource_parsing_info.clone());
-        metadata_queue.get_metadata_method_macros(resource_parsing_info)
+    ) -> anyhow::Result<BTreeSet<MethodMacro>> {
+        let metadata_queue = PathMetadataQueue::try_from(resource_parsing_info.clone())?;
+        Ok(metadata_queue.get_metadata_method_macros(resource_parsing_info))
     }
 }
@@ -56,7 +56,7 @@ pub use server_variable::*;
 pub use tag::*;
 pub use xml::*;
 
-use crate::api_types::{PathMetadata, WriteConfiguration};
+use crate::api_types::{ApiVersion, PathMetadata, WriteConfiguration};
 use crate::macros::OpenApiParser;
 use crate::traits::RequestParser;
 use from_as::*;
@@ -74,7 +74,7 @@ use std::{
 };
 
 static MS_GRAPH_METADATA_URL: &str = "https://raw.githubusercontent.com/microsoftgraph/msgraph-metadata/master/openapi/v1.0/openapi.yaml";
-// static MS_GRAPH_BETA_METADATA_URL: &str = "https://raw.githubusercontent.com/microsoftgraph/msgraph-metadata/master/openapi/beta/openapi.yaml";
+static MS_GRAPH_BETA_METADATA_URL: &str = "https://raw.githubusercontent.com/microsoftgraph/msgraph-metadata/master/openapi/beta/openapi.yaml";
 
 /// [OpenAPI Object](https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md#oasObject)
 #[derive(Debug, Clone, Serialize, Deserialize, FromFile, AsFile)]
@@ -381,6 +381,18 @@ impl Default for OpenApi {
     }
 }
 
+/// Downloads the metadata of the api version.
+impl TryFrom<ApiVersion> for OpenApi {
+    type Error = GraphFailure;
+
+    fn try_from(api_version: ApiVersion) -> Result<Self, Self::Error> {
+        match api_version {
+            ApiVersion::V1 => OpenApi::try_from(GraphUrl::parse(MS_GRAPH_METADATA_URL)?),
+            ApiVersion::Beta => OpenApi::try_from(GraphUrl::parse(MS_GRAPH_BETA_METADATA_URL)?),
+        }
+    }
+}
+
 impl TryFrom<reqwest::Url> for OpenApi {
     type Error = GraphFailure;
 
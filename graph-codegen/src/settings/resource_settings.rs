@@ -1,4 +1,4 @@
-use crate::api_types::{WriteConfiguration, WriteConfigurationBuilder};
+use crate::api_types::{ApiVersion, WriteConfiguration, WriteConfigurationBuilder};
 use crate::settings::{ApiClientLink, ApiClientLinkSettings};
 use from_as::*;
 use graph_core::resource::ResourceIdentity;
@@ -1733,6 +1733,75 @@ pub fn map_write_config(ris: Vec<ResourceIdentity>) -> Vec<WriteConfiguration> {
     ris.iter().map(|ri| get_write_configuration(*ri)).collect()
 }
 
+/// Write configurations for generating the beta api clients in `src/beta`.
+pub fn map_beta_write_config(ris: Vec<ResourceIdentity>) -> Vec<WriteConfiguration> {
+    ris.iter()
+        .map(|ri| get_write_configuration(*ri).for_api_version(ApiVersion::Beta))
+        .collect()
+}
+
+/// Resources of the generated top level modules in `src/`. The write configuration of each
+/// resource also writes the api clients of its child modules.
+///
+/// `ResourceIdentity::Identity` is not included because its api clients are written to
+/// `src/identity` and the module in the sdk is `src/identity_access`.
+pub fn get_generated_resource_identities(api_version: ApiVersion) -> Vec<ResourceIdentity> {
+    match api_version {
+        ApiVersion::V1 => vec![
+            ResourceIdentity::Admin,
+            ResourceIdentity::AgreementAcceptances,
+            ResourceIdentity::Agreements,
+            ResourceIdentity::AppCatalogs,
+            ResourceIdentity::Applications,
+            ResourceIdentity::AuditLogs,
+            ResourceIdentity::AuthenticationMethodConfigurations,
+            ResourceIdentity::AuthenticationMethodsPolicy,
+            ResourceIdentity::Branding,
+            ResourceIdentity::CertificateBasedAuthConfiguration,
+            ResourceIdentity::Chats,
+            ResourceIdentity::Communications,
+            ResourceIdentity::Contracts,
+            ResourceIdentity::DataPolicyOperations,
+            ResourceIdentity::DeviceAppManagement,
+            ResourceIdentity::DeviceManagement,
+            ResourceIdentity::Devices,
+            ResourceIdentity::Directory,
+            ResourceIdentity::DirectoryObjects,
+            ResourceIdentity::DirectoryRoleTemplates,
+            ResourceIdentity::DirectoryRoles,
+            ResourceIdentity::DomainDnsRecords,
+            ResourceIdentity::Domains,
+            ResourceIdentity::Drives,
+            ResourceIdentity::Education,
+            ResourceIdentity::ExtendedProperties,
+            ResourceIdentity::GroupLifecyclePolicies,
+            ResourceIdentity::Groups,
+            ResourceIdentity::IdentityGovernance,
+            ResourceIdentity::IdentityProviders,
+            ResourceIdentity::Invitations,
+            ResourceIdentity::Me,
+            ResourceIdentity::Oauth2PermissionGrants,
+            ResourceIdentity::Organization,
+            ResourceIdentity::PermissionGrants,
+            ResourceIdentity::Places,
+            ResourceIdentity::Planner,
+            ResourceIdentity::Policies,
+            ResourceIdentity::Reports,
+            ResourceIdentity::SchemaExtensions,
+            ResourceIdentity::ServicePrincipals,
+            ResourceIdentity::Sites,
+            ResourceIdentity::Solutions,
+            ResourceIdentity::SubscribedSkus,
+            ResourceIdentity::Subscriptions,
+            ResourceIdentity::Teams,
+            ResourceIdentity::TeamsTemplates,
+            ResourceIdentity::Teamwork,
+            ResourceIdentity::Users,
+        ],
+        ApiVersion::Beta => vec![ResourceIdentity::IdentityProtection, ResourceIdentity::Users],
+    }
+}
+
 pub fn get_write_configuration(resource_identity: ResourceIdentity) -> WriteConfiguration {
     match resource_identity {
 		ResourceIdentity::AuthenticationMethodsPolicy => WriteConfiguration::builder(resource_identity)
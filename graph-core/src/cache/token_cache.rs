@@ -27,4 +27,8 @@ pub trait TokenCache {
     async fn get_token_silent_async(&mut self) -> Result<Self::Token, AuthExecutionError>;
 
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh);
+
+    /// Use the given claims in the next token request and ignore any tokens in cache
+    /// for that request.
+    fn with_claims_challenge(&mut self, _claims: String) {}
 }
@@ -1,6 +1,8 @@
 mod pkce;
+mod pop;
 
 pub use pkce::*;
+pub use pop::*;
 
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
@@ -0,0 +1,187 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use graph_error::{AuthExecutionResult, IdentityResult, AF};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+use std::fmt::{Debug, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// Key pair used for Proof-of-Possession (PoP) also known as Signed Http Request (SHR)
+/// tokens.
+///
+/// The public key is sent to the identity platform during token acquisition through the
+/// `req_cnf` parameter and the returned access token is bound to this key. Every request
+/// made with the access token must then be signed with the private key and sent using the
+/// `PoP` authorization scheme.
+///
+/// The key is an ECDSA P-256 key pair generated using the Rust ring crypto library.
+#[derive(Clone)]
+pub struct ProofOfPossessionKey {
+    pkcs8: Vec<u8>,
+    x: String,
+    y: String,
+    kid: String,
+}
+
+impl ProofOfPossessionKey {
+    /// Generate a new ECDSA P-256 key pair.
+    pub fn generate() -> IdentityResult<ProofOfPossessionKey> {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            .map_err(|_| AF::msg_err("pop_key", "Unable to generate ECDSA P-256 key pair"))?;
+        ProofOfPossessionKey::from_pkcs8(pkcs8.as_ref())
+    }
+
+    /// Use an existing ECDSA P-256 private key in PKCS#8 DER format.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> IdentityResult<ProofOfPossessionKey> {
+        let rng = ring::rand::SystemRandom::new();
+        let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8, &rng)
+            .map_err(|err| AF::msg_err("pop_key", err.to_string().as_str()))?;
+
+        // Uncompressed point: 0x04 || X || Y
+        let public_key = key_pair.public_key().as_ref();
+        if public_key.len() != 65 {
+            return AF::msg_result("pop_key", "Invalid ECDSA P-256 public key");
+        }
+
+        let x = URL_SAFE_NO_PAD.encode(&public_key[1..33]);
+        let y = URL_SAFE_NO_PAD.encode(&public_key[33..65]);
+        let kid = ProofOfPossessionKey::thumbprint(&x, &y);
+
+        Ok(ProofOfPossessionKey {
+            pkcs8: pkcs8.to_vec(),
+            x,
+            y,
+            kid,
+        })
+    }
+
+    /// JWK thumbprint of the public key as defined in
+    /// [RFC 7638](https://datatracker.ietf.org/doc/html/rfc7638).
+    fn thumbprint(x: &str, y: &str) -> String {
+        // Required members in lexicographic order with no whitespace.
+        let canonical = format!(r#"{{"crv":"P-256","kty":"EC","x":"{x}","y":"{y}"}}"#);
+        let digest = ring::digest::digest(&ring::digest::SHA256, canonical.as_bytes());
+        URL_SAFE_NO_PAD.encode(digest.as_ref())
+    }
+
+    /// The key id which is the JWK thumbprint of the public key.
+    pub fn kid(&self) -> &str {
+        self.kid.as_str()
+    }
+
+    /// The public key as a JSON Web Key.
+    pub fn jwk(&self) -> serde_json::Value {
+        serde_json::json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": self.x,
+            "y": self.y,
+        })
+    }
+
+    /// The `req_cnf` parameter sent in the token request which binds the requested
+    /// access token to this key.
+    pub fn req_cnf(&self) -> String {
+        let cnf = serde_json::json!({ "kid": self.kid });
+        URL_SAFE_NO_PAD.encode(cnf.to_string())
+    }
+
+    /// Create the signed http request for the given access token, http method and url.
+    /// A new random nonce is generated for every signed request.
+    ///
+    /// The returned value is used with the `PoP` authorization scheme:
+    /// `Authorization: PoP <signed http request>`
+    pub fn sign_request(
+        &self,
+        access_token: &str,
+        method: &http::Method,
+        url: &Url,
+    ) -> AuthExecutionResult<String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let mut host = url.host_str().unwrap_or_default().to_string();
+        if let Some(port) = url.port() {
+            host = format!("{host}:{port}");
+        }
+
+        let claims = serde_json::json!({
+            "at": access_token,
+            "ts": timestamp,
+            "m": method.as_str(),
+            "u": host,
+            "p": url.path(),
+            "nonce": crate::crypto::secure_random_32(),
+            "cnf": {
+                "jwk": self.jwk()
+            }
+        });
+
+        let mut header = Header::new(Algorithm::ES256);
+        header.typ = Some("pop".into());
+        header.kid = Some(self.kid.clone());
+
+        Ok(jsonwebtoken::encode(
+            &header,
+            &claims,
+            &EncodingKey::from_ec_der(&self.pkcs8),
+        )?)
+    }
+}
+
+impl PartialEq for ProofOfPossessionKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.kid == other.kid
+    }
+}
+
+impl Debug for ProofOfPossessionKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProofOfPossessionKey")
+            .field("kid", &self.kid)
+            .field("jwk", &self.jwk())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jsonwebtoken::{DecodingKey, Validation};
+
+    #[test]
+    fn req_cnf_contains_kid() {
+        let key = ProofOfPossessionKey::generate().unwrap();
+        let req_cnf = URL_SAFE_NO_PAD.decode(key.req_cnf()).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&req_cnf).unwrap();
+        assert_eq!(value["kid"].as_str(), Some(key.kid()));
+    }
+
+    #[test]
+    fn signed_request_verifies_with_public_key() {
+        let key = ProofOfPossessionKey::generate().unwrap();
+        let url = Url::parse("https://graph.microsoft.com/v1.0/me?$select=id").unwrap();
+        let signed = key
+            .sign_request("access_token", &http::Method::GET, &url)
+            .unwrap();
+
+        let decoding_key = DecodingKey::from_ec_components(&key.x, &key.y).unwrap();
+        let mut validation = Validation::new(Algorithm::ES256);
+        validation.required_spec_claims.clear();
+        validation.validate_exp = false;
+
+        let token_data =
+            jsonwebtoken::decode::<serde_json::Value>(&signed, &decoding_key, &validation).unwrap();
+
+        assert_eq!(token_data.header.typ.as_deref(), Some("pop"));
+        assert_eq!(token_data.header.kid.as_deref(), Some(key.kid()));
+        assert_eq!(token_data.claims["at"], "access_token");
+        assert_eq!(token_data.claims["m"], "GET");
+        assert_eq!(token_data.claims["u"], "graph.microsoft.com");
+        assert_eq!(token_data.claims["p"], "/v1.0/me");
+    }
+}
@@ -0,0 +1,51 @@
+use crate::crypto::ProofOfPossessionKey;
+use graph_error::{AuthExecutionResult, IdentityResult};
+use url::Url;
+
+/// The scheme used in the Authorization header when calling a resource
+/// with an access token.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <access_token>`
+    #[default]
+    Bearer,
+    /// Proof-of-Possession (PoP) also known as Signed Http Request (SHR) tokens.
+    /// Access tokens are bound to the key and each request is signed using
+    /// the key.
+    ///
+    /// `Authorization: PoP <signed http request>`
+    ProofOfPossession(ProofOfPossessionKey),
+}
+
+impl AuthScheme {
+    /// Generate a new key pair and use the Proof-of-Possession scheme.
+    pub fn proof_of_possession() -> IdentityResult<AuthScheme> {
+        Ok(AuthScheme::ProofOfPossession(
+            ProofOfPossessionKey::generate()?,
+        ))
+    }
+
+    /// The token type requested from the token endpoint.
+    pub fn token_type(&self) -> &'static str {
+        match self {
+            AuthScheme::Bearer => "Bearer",
+            AuthScheme::ProofOfPossession(_) => "pop",
+        }
+    }
+
+    /// The value of the Authorization header for the given access token, http method, and url.
+    pub fn authorization_header(
+        &self,
+        access_token: &str,
+        method: &http::Method,
+        url: &Url,
+    ) -> AuthExecutionResult<String> {
+        match self {
+            AuthScheme::Bearer => Ok(format!("Bearer {access_token}")),
+            AuthScheme::ProofOfPossession(key) => Ok(format!(
+                "PoP {}",
+                key.sign_request(access_token, method, url)?
+            )),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use base64::engine::general_purpose::{STANDARD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use http::header::WWW_AUTHENTICATE;
+use http::{HeaderMap, StatusCode};
+
+/// Claims challenge returned by a resource, such as Microsoft Graph, that supports
+/// Continuous Access Evaluation (CAE).
+///
+/// When a resource rejects an access token it returns a `401 Unauthorized` with a
+/// `WWW-Authenticate` header that includes the claims that the next token must satisfy:
+///
+/// `WWW-Authenticate: Bearer error="insufficient_claims", claims="eyJhY2Nlc3NfdG9rZW4iOnsi..."`
+///
+/// See [Claims challenges, claims requests, and client capabilities](https://learn.microsoft.com/en-us/entra/identity-platform/claims-challenge)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimsChallenge {
+    /// The value of the error parameter, typically `insufficient_claims`.
+    pub error: Option<String>,
+    /// The base64 decoded claims JSON that should be passed in the claims
+    /// parameter of the next token request.
+    pub claims: String,
+}
+
+impl ClaimsChallenge {
+    /// Parse a claims challenge from the headers of a `401 Unauthorized` response.
+    /// Returns None if the status is not 401 or if there is no claims challenge.
+    pub fn from_response_parts(status: StatusCode, headers: &HeaderMap) -> Option<ClaimsChallenge> {
+        if status != StatusCode::UNAUTHORIZED {
+            return None;
+        }
+
+        headers
+            .get_all(WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(ClaimsChallenge::from_www_authenticate)
+    }
+
+    /// Parse a claims challenge from the value of a `WWW-Authenticate` header.
+    pub fn from_www_authenticate(value: &str) -> Option<ClaimsChallenge> {
+        let (_scheme, parameters) = value.trim().split_once(' ')?;
+        let mut error = None;
+        let mut claims = None;
+
+        for parameter in parameters.split(',') {
+            if let Some((key, value)) = parameter.trim().split_once('=') {
+                let value = value.trim().trim_matches('"');
+                match key.trim() {
+                    "error" => error = Some(value.to_owned()),
+                    "claims" => claims = ClaimsChallenge::decode(value),
+                    _ => {}
+                }
+            }
+        }
+
+        Some(ClaimsChallenge {
+            error,
+            claims: claims?,
+        })
+    }
+
+    fn decode(value: &str) -> Option<String> {
+        let bytes = STANDARD
+            .decode(value)
+            .or_else(|_| URL_SAFE.decode(value))
+            .or_else(|_| URL_SAFE_NO_PAD.decode(value.trim_end_matches('=')))
+            .ok()?;
+        String::from_utf8(bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn parse_insufficient_claims() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            WWW_AUTHENTICATE,
+            HeaderValue::from_static("Bearer authorization_uri=\"https://login.microsoftonline.com/common/oauth2/authorize\", error=\"insufficient_claims\", claims=\"eyJhY2Nlc3NfdG9rZW4iOnsibmJmIjp7ImVzc2VudGlhbCI6dHJ1ZSwgInZhbHVlIjoiMTYwNDEwNjY1MSJ9fX0=\""),
+        );
+
+        let challenge =
+            ClaimsChallenge::from_response_parts(StatusCode::UNAUTHORIZED, &headers).unwrap();
+        assert_eq!(challenge.error.as_deref(), Some("insufficient_claims"));
+        assert_eq!(
+            challenge.claims,
+            r#"{"access_token":{"nbf":{"essential":true, "value":"1604106651"}}}"#
+        );
+    }
+
+    #[test]
+    fn no_challenge_without_claims() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            WWW_AUTHENTICATE,
+            HeaderValue::from_static("Bearer error=\"invalid_token\""),
+        );
+
+        assert!(ClaimsChallenge::from_response_parts(StatusCode::UNAUTHORIZED, &headers).is_none());
+        assert!(ClaimsChallenge::from_response_parts(StatusCode::OK, &headers).is_none());
+    }
+}
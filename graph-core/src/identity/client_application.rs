@@ -1,6 +1,8 @@
+use crate::identity::{AuthScheme, CloudEnvironment};
 use async_trait::async_trait;
 use dyn_clone::DynClone;
 use graph_error::AuthExecutionResult;
+use url::Url;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum ForceTokenRefresh {
@@ -26,6 +28,36 @@ pub trait ClientApplication: DynClone + Send + Sync {
     async fn get_token_silent_async(&mut self) -> AuthExecutionResult<String>;
 
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh);
+
+    /// The scheme used in the Authorization header of requests made with
+    /// tokens from this client application. Defaults to [AuthScheme::Bearer]
+    fn auth_scheme(&self) -> AuthScheme {
+        AuthScheme::Bearer
+    }
+
+    /// Use the claims from a [ClaimsChallenge](crate::identity::ClaimsChallenge) in the next
+    /// token request. The next token request ignores any tokens in cache in the same way as
+    /// [ForceTokenRefresh::Once].
+    ///
+    /// Client applications that cannot request new tokens, such as static access tokens,
+    /// ignore the claims challenge.
+    fn with_claims_challenge(&mut self, _claims: String) {}
+
+    /// The host of the authority that issues tokens for this client application, such as
+    /// https://login.microsoftonline.com. Used to check that the client application and
+    /// the [CloudEnvironment](crate::identity::CloudEnvironment) of a client match.
+    ///
+    /// Client applications that do not request tokens, such as static access tokens,
+    /// return `None`.
+    fn authority_host(&self) -> Option<Url> {
+        None
+    }
+
+    /// The cloud environment that was given to the client application, if any. Clients
+    /// built with the client application use the environment unless they set their own.
+    fn cloud_environment(&self) -> Option<CloudEnvironment> {
+        None
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,189 @@
+use url::Url;
+
+/// A custom cloud environment. See [`CloudEnvironment::custom`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CustomCloudEnvironment {
+    authority_host: Url,
+    graph_host: Url,
+    default_scope: String,
+}
+
+/// The national cloud deployment of Microsoft Graph and the identity platform that a client
+/// uses. The environment configures the authority host of credentials, the Graph endpoint of
+/// the client, the default `.default` scope, and the hosts that are allowed as the endpoint.
+///
+/// The client and the credential must use the same environment. Tokens issued by the authority
+/// of one cloud are rejected by the Graph endpoint of another cloud.
+///
+/// See [National cloud deployments](https://learn.microsoft.com/en-us/graph/deployments)
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum CloudEnvironment {
+    /// Microsoft Graph global service. Uses https://graph.microsoft.com and
+    /// https://login.microsoftonline.com
+    #[default]
+    Public,
+    /// Microsoft Graph for US Government L4. Uses https://graph.microsoft.us and
+    /// https://login.microsoftonline.us
+    USGov,
+    /// Microsoft Graph for US Government L5 (DOD). Uses https://dod-graph.microsoft.us and
+    /// https://login.microsoftonline.us
+    USGovDoD,
+    /// Microsoft Graph China operated by 21Vianet. Uses https://microsoftgraph.chinacloudapi.cn
+    /// and https://login.chinacloudapi.cn
+    China,
+    /// A custom authority host and Graph host.
+    Custom(CustomCloudEnvironment),
+}
+
+impl CloudEnvironment {
+    /// A cloud environment with a custom authority host and Graph host, such as a Graph
+    /// endpoint behind an API gateway. Only the scheme, host, and port of the urls are used.
+    ///
+    /// Credentials request tokens from the authority hosts of the Azure cloud instances
+    /// so the authority host should be the host of one of the Azure cloud instances.
+    ///
+    /// The `default_scope` is the `.default` scope of the resource that the tokens are
+    /// issued for. A gateway in front of Microsoft Graph usually expects tokens for Graph
+    /// itself, such as `https://graph.microsoft.com/.default`, and not for the host of
+    /// the gateway.
+    pub fn custom<T: ToString>(
+        authority_host: Url,
+        graph_host: Url,
+        default_scope: T,
+    ) -> CloudEnvironment {
+        CloudEnvironment::Custom(CustomCloudEnvironment {
+            authority_host: origin(&authority_host),
+            graph_host: origin(&graph_host),
+            default_scope: default_scope.to_string(),
+        })
+    }
+
+    /// The host of the identity platform that issues tokens, such as
+    /// https://login.microsoftonline.com
+    pub fn authority_host(&self) -> Url {
+        match self {
+            CloudEnvironment::Public => parse("https://login.microsoftonline.com"),
+            CloudEnvironment::USGov | CloudEnvironment::USGovDoD => {
+                parse("https://login.microsoftonline.us")
+            }
+            CloudEnvironment::China => parse("https://login.chinacloudapi.cn"),
+            CloudEnvironment::Custom(custom) => custom.authority_host.clone(),
+        }
+    }
+
+    /// The host of Microsoft Graph, such as https://graph.microsoft.com
+    pub fn graph_host(&self) -> Url {
+        match self {
+            CloudEnvironment::Public => parse("https://graph.microsoft.com"),
+            CloudEnvironment::USGov => parse("https://graph.microsoft.us"),
+            CloudEnvironment::USGovDoD => parse("https://dod-graph.microsoft.us"),
+            CloudEnvironment::China => parse("https://microsoftgraph.chinacloudapi.cn"),
+            CloudEnvironment::Custom(custom) => custom.graph_host.clone(),
+        }
+    }
+
+    /// The v1.0 endpoint of Microsoft Graph, such as https://graph.microsoft.com/v1.0
+    pub fn graph_endpoint(&self) -> Url {
+        self.endpoint("v1.0")
+    }
+
+    /// The beta endpoint of Microsoft Graph, such as https://graph.microsoft.com/beta
+    pub fn graph_beta_endpoint(&self) -> Url {
+        self.endpoint("beta")
+    }
+
+    /// The scope that requests the application permissions of Microsoft Graph, such as
+    /// https://graph.microsoft.com/.default
+    pub fn default_scope(&self) -> String {
+        match self {
+            CloudEnvironment::Custom(custom) => custom.default_scope.clone(),
+            _ => format!("{}.default", self.graph_host()),
+        }
+    }
+
+    /// The hosts that are allowed as the endpoint of a client using this environment.
+    pub fn allowed_hosts(&self) -> Vec<Url> {
+        match self {
+            CloudEnvironment::Public => vec![
+                parse("https://graph.microsoft.com"),
+                parse("https://canary.graph.microsoft.com"),
+            ],
+            _ => vec![self.graph_host()],
+        }
+    }
+
+    /// Returns true if the authority host of the url is the authority host of this environment.
+    pub fn is_authority_host(&self, url: &Url) -> bool {
+        origin(url) == self.authority_host()
+    }
+
+    fn endpoint(&self, version: &str) -> Url {
+        let mut url = self.graph_host();
+        url.set_path(version);
+        url
+    }
+}
+
+fn parse(url: &str) -> Url {
+    Url::parse(url).expect("Unable to parse cloud environment url")
+}
+
+fn origin(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_path("");
+    url.set_query(None);
+    url.set_fragment(None);
+    url
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cloud_environment_endpoints() {
+        let environment = CloudEnvironment::USGovDoD;
+        assert_eq!(
+            "https://dod-graph.microsoft.us/v1.0",
+            environment.graph_endpoint().as_str()
+        );
+        assert_eq!(
+            "https://dod-graph.microsoft.us/beta",
+            environment.graph_beta_endpoint().as_str()
+        );
+        assert_eq!(
+            "https://dod-graph.microsoft.us/.default",
+            environment.default_scope()
+        );
+        assert_eq!(
+            "https://login.microsoftonline.us/",
+            environment.authority_host().as_str()
+        );
+        assert_eq!(
+            "https://graph.microsoft.com/.default",
+            CloudEnvironment::Public.default_scope()
+        );
+    }
+
+    #[test]
+    fn custom_cloud_environment() {
+        let environment = CloudEnvironment::custom(
+            Url::parse("https://login.microsoftonline.com/common").unwrap(),
+            Url::parse("https://graph.corp.internal:8443/graph?query").unwrap(),
+            "https://graph.microsoft.com/.default",
+        );
+        assert_eq!(
+            "https://graph.corp.internal:8443/v1.0",
+            environment.graph_endpoint().as_str()
+        );
+        assert_eq!(
+            "https://graph.microsoft.com/.default",
+            environment.default_scope()
+        );
+        assert!(environment.is_authority_host(
+            &Url::parse("https://login.microsoftonline.com/tenant/oauth2/v2.0/token").unwrap()
+        ));
+        assert!(!CloudEnvironment::China
+            .is_authority_host(&Url::parse("https://login.microsoftonline.com").unwrap()));
+    }
+}
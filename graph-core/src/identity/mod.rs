@@ -1,7 +1,13 @@
+mod auth_scheme;
+mod claims_challenge;
 mod client_application;
+mod cloud_environment;
 mod jwk;
 mod jwks;
 
+pub use auth_scheme::*;
+pub use claims_challenge::*;
 pub use client_application::*;
+pub use cloud_environment::*;
 pub use jwk::*;
 pub use jwks::*;
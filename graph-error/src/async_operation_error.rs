@@ -0,0 +1,26 @@
+use crate::ErrorStatus;
+use std::time::Duration;
+
+/// Errors of a long running operation that is polled with the monitor url of a
+/// `202 Accepted` response, such as copying a drive item or creating a team.
+#[derive(Debug, Clone, thiserror::Error)]
+#[allow(clippy::large_enum_variant)]
+pub enum AsyncOperationError {
+    #[error("the response does not have a Location header with the url of the operation status")]
+    MissingMonitorUrl,
+
+    #[error("long running operation failed with status {status}: {error:?}")]
+    Failed {
+        /// The status reported by the operation such as `failed`.
+        status: String,
+        /// The error of the operation, if the status included one.
+        error: Option<ErrorStatus>,
+        monitor_url: url::Url,
+    },
+
+    #[error("long running operation did not complete within {0:?}")]
+    TimedOut(Duration),
+
+    #[error("long running operation was cancelled")]
+    Cancelled,
+}
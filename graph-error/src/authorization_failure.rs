@@ -1,4 +1,6 @@
-use crate::{ErrorMessage, IdentityResult, WebViewDeviceCodeError};
+use crate::{
+    ConfigurationError, ErrorMessage, IdentityError, IdentityResult, WebViewDeviceCodeError,
+};
 use std::error::Error;
 use tokio::sync::mpsc::error::SendTimeoutError;
 use url::ParseError;
@@ -109,14 +111,27 @@ pub enum AuthExecutionError {
         response: http::Response<Result<serde_json::Value, ErrorMessage>>,
     },
 
+    /// The revocation endpoint did not revoke the refresh token.
+    #[error("token revocation failed, response: {0:#?}", response)]
+    Revocation {
+        response: http::Response<Result<serde_json::Value, ErrorMessage>>,
+    },
+
     #[error("{0:#?}")]
     JsonWebToken(#[from] jsonwebtoken::errors::Error),
 
+    /// The http client for the request could not be built from the configuration.
+    #[error("{0}")]
+    Configuration(#[from] ConfigurationError),
+
     #[error("{0:#?}")]
     Other(#[from] Box<dyn Error + Send + Sync>),
 }
 
 impl AuthExecutionError {
+    /// Error for a failed token request. The response is kept so that the status and
+    /// headers are available. Use [`AuthExecutionError::identity_error`] for the typed
+    /// identity platform error of the response body.
     pub fn silent_token_auth(
         response: http::Response<Result<serde_json::Value, ErrorMessage>>,
     ) -> AuthExecutionError {
@@ -125,6 +140,32 @@ impl AuthExecutionError {
             response,
         }
     }
+
+    /// Error for a failed refresh token revocation request.
+    pub fn revocation(
+        response: http::Response<Result<serde_json::Value, ErrorMessage>>,
+    ) -> AuthExecutionError {
+        AuthExecutionError::Revocation { response }
+    }
+
+    /// The typed identity platform error of a [`AuthExecutionError::SilentTokenAuth`]
+    /// or [`AuthExecutionError::Revocation`] error. Returns `None` when the response body is not an identity platform error
+    /// response.
+    pub fn identity_error(&self) -> Option<IdentityError> {
+        match self {
+            AuthExecutionError::SilentTokenAuth { response, .. }
+            | AuthExecutionError::Revocation { response } => IdentityError::from_response(response),
+            _ => None,
+        }
+    }
+
+    /// Returns true if a token cannot be acquired silently and the user must go
+    /// through an interactive sign in. See [`IdentityError::requires_user_interaction`]
+    pub fn requires_user_interaction(&self) -> bool {
+        self.identity_error()
+            .map(|identity_error| identity_error.requires_user_interaction())
+            .unwrap_or_default()
+    }
 }
 
 impl From<serde_json::error::Error> for AuthExecutionError {
@@ -149,6 +190,7 @@ impl From<url::ParseError> for AuthExecutionError {
 }
 
 #[derive(Debug, thiserror::Error)]
+#[allow(clippy::large_enum_variant)]
 pub enum AuthTaskExecutionError<R> {
     #[error("{0:#?}")]
     AuthExecutionError(#[from] AuthExecutionError),
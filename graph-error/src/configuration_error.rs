@@ -0,0 +1,52 @@
+/// Errors from an invalid client configuration, returned when a client is built.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ConfigurationError {
+    /// The authority of the credential is not the authority of the cloud environment of
+    /// the client. Tokens issued by the authority of one cloud are rejected by the Graph
+    /// endpoint of another cloud.
+    #[error(
+        "the credential uses the cloud environment host {found} but the client uses {expected}"
+    )]
+    CloudEnvironmentMismatch {
+        /// The authority host or Graph host of the cloud environment of the client.
+        expected: String,
+        /// The authority host or Graph host of the credential.
+        found: String,
+    },
+
+    /// No access token or client application was given to the client.
+    #[error("an access token or client application is required to build the client")]
+    MissingCredentials,
+
+    /// The endpoint is not a valid url.
+    #[error("invalid endpoint {endpoint}: {source}")]
+    InvalidEndpoint {
+        endpoint: String,
+        source: url::ParseError,
+    },
+
+    /// The endpoint has a query. Only the scheme, host, and optional path of the
+    /// endpoint can be set, such as https://graph.microsoft.com/v1.0
+    #[error("invalid endpoint {0}: the endpoint must not have a query")]
+    EndpointQuery(String),
+
+    /// The endpoint does not use the https scheme.
+    #[error("invalid endpoint {0}: the endpoint must use the https scheme")]
+    InsecureEndpoint(String),
+
+    /// The host of the endpoint is not one of the allowed hosts of the client.
+    #[error("invalid endpoint {0}: the host is not an allowed Microsoft Graph host")]
+    HostNotAllowed(String),
+
+    /// The minimum TLS version is lower than TLS 1.2, which is required by Microsoft Graph.
+    #[error(
+        "the minimum TLS version {0} is not supported, Microsoft Graph requires TLS 1.2 or higher"
+    )]
+    TlsVersion(String),
+
+    /// The http client could not be built from the configuration, such as when the TLS
+    /// backend does not support the TLS settings or the proxy is invalid.
+    #[error("unable to build the http client: {0}")]
+    HttpClient(#[from] reqwest::Error),
+}
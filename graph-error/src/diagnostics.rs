@@ -0,0 +1,139 @@
+use reqwest::header::HeaderMap;
+
+static REQUEST_ID: &str = "request-id";
+static CLIENT_REQUEST_ID: &str = "client-request-id";
+static AGS_DIAGNOSTIC: &str = "x-ms-ags-diagnostic";
+static DATE: &str = "date";
+static THROTTLE_SCOPE: &str = "x-ms-throttle-scope";
+
+/// The headers of a Microsoft Graph response that are needed when opening a support
+/// ticket or correlating a request with the service logs.
+///
+/// See [Microsoft Graph error responses](https://learn.microsoft.com/en-us/graph/errors)
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ResponseDiagnostics {
+    /// The `request-id` response header that identifies the request in the service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+
+    /// The `client-request-id` header that was sent with the request and echoed by
+    /// the service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_request_id: Option<String>,
+
+    /// The `x-ms-ags-diagnostic` response header with the data center and slice
+    /// that handled the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostic: Option<String>,
+
+    /// The `date` response header.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+
+    /// The `x-ms-throttle-scope` response header of throttled requests that shows
+    /// which limit was exceeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throttle_scope: Option<String>,
+}
+
+impl ResponseDiagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.request_id.is_none()
+            && self.client_request_id.is_none()
+            && self.diagnostic.is_none()
+            && self.date.is_none()
+            && self.throttle_scope.is_none()
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+impl From<&HeaderMap> for ResponseDiagnostics {
+    fn from(headers: &HeaderMap) -> Self {
+        ResponseDiagnostics {
+            request_id: header_value(headers, REQUEST_ID),
+            client_request_id: header_value(headers, CLIENT_REQUEST_ID),
+            diagnostic: header_value(headers, AGS_DIAGNOSTIC),
+            date: header_value(headers, DATE),
+            throttle_scope: header_value(headers, THROTTLE_SCOPE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn diagnostics_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID, HeaderValue::from_static("request"));
+        headers.insert(CLIENT_REQUEST_ID, HeaderValue::from_static("client"));
+        headers.insert(
+            AGS_DIAGNOSTIC,
+            HeaderValue::from_static(
+                r#"{"ServerInfo":{"DataCenter":"West US","Slice":"E","Ring":"5"}}"#,
+            ),
+        );
+        headers.insert(
+            THROTTLE_SCOPE,
+            HeaderValue::from_static("Tenant_Application/ReadWrite/tenant/app"),
+        );
+
+        let diagnostics = ResponseDiagnostics::from(&headers);
+        assert_eq!(Some("request"), diagnostics.request_id.as_deref());
+        assert_eq!(Some("client"), diagnostics.client_request_id.as_deref());
+        assert!(diagnostics.diagnostic.unwrap().contains("West US"));
+        assert_eq!(None, diagnostics.date);
+        assert_eq!(
+            Some("Tenant_Application/ReadWrite/tenant/app"),
+            diagnostics.throttle_scope.as_deref()
+        );
+        assert!(ResponseDiagnostics::from(&HeaderMap::new()).is_empty());
+    }
+
+    #[test]
+    fn graph_failure_diagnostics() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CLIENT_REQUEST_ID, HeaderValue::from_static("client"));
+        let failure = crate::GraphFailure::PreFlightError {
+            url: None,
+            headers: Some(headers),
+            error: None,
+            message: String::new(),
+        };
+        assert_eq!(
+            Some("client"),
+            failure.diagnostics().unwrap().client_request_id.as_deref()
+        );
+
+        let failure = crate::GraphFailure::PreFlightError {
+            url: None,
+            headers: Some(HeaderMap::new()),
+            error: None,
+            message: String::new(),
+        };
+        assert_eq!(None, failure.diagnostics());
+        assert_eq!(None, crate::GraphFailure::TemporaryError.diagnostics());
+    }
+
+    #[test]
+    fn error_message_equality_ignores_diagnostics() {
+        let error_message = crate::ErrorMessage::default();
+        let with_diagnostics = error_message.clone().with_diagnostics(ResponseDiagnostics {
+            request_id: Some("request".into()),
+            ..Default::default()
+        });
+        assert_eq!(error_message, with_diagnostics);
+        assert_eq!(
+            Some("request"),
+            with_diagnostics.diagnostics.unwrap().request_id.as_deref()
+        );
+    }
+}
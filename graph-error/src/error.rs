@@ -1,3 +1,4 @@
+use crate::ResponseDiagnostics;
 use serde::Serialize;
 use std::fmt::{Display, Formatter};
 
@@ -10,6 +11,32 @@ pub struct InnerError {
     pub request_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub date: Option<String>,
+    #[serde(rename = "client-request-id")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_request_id: Option<String>,
+    /// A more specific error nested within this inner error.
+    #[serde(rename = "innerError")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inner_error: Option<Box<InnerError>>,
+}
+
+impl InnerError {
+    /// The inner errors nested within this inner error, starting with the inner error itself.
+    pub fn chain(&self) -> impl Iterator<Item = &InnerError> {
+        std::iter::successors(Some(self), |inner_error| inner_error.inner_error.as_deref())
+    }
+}
+
+/// An additional error object returned in the `details` of an error response, such as
+/// one error for each invalid property of the request.
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDetail {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
 }
 
 /// An error resource included in the error response returned from
@@ -27,6 +54,15 @@ pub struct ErrorStatus {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
 
+    /// Optional. The target of the error, such as the name of an invalid property.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    /// Optional. A list of additional error objects, such as one for each invalid property.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<ErrorDetail>,
+
     /// Optional. Additional error objects that may be more specific than the top level error.
     #[serde(rename = "innerError")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -34,6 +70,7 @@ pub struct ErrorStatus {
 }
 
 #[derive(thiserror::Error, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum HttpResponseErrorMessage {
     #[error("{0:#?}")]
     GraphErrorMessage(#[from] ErrorMessage),
@@ -43,9 +80,13 @@ pub enum HttpResponseErrorMessage {
     ReqwestError(#[from] reqwest::Error),
 }
 
-#[derive(thiserror::Error, Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(thiserror::Error, Default, Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct ErrorMessage {
     pub error: ErrorStatus,
+    /// The diagnostics of the response headers. Not part of the json error body and not
+    /// compared by [`PartialEq`].
+    #[serde(skip)]
+    pub diagnostics: Option<ResponseDiagnostics>,
 }
 
 impl ErrorMessage {
@@ -72,6 +113,34 @@ impl ErrorMessage {
     pub fn date(&self) -> Option<String> {
         self.error.inner_error.as_ref()?.date.clone()
     }
+
+    /// Attach the [`ResponseDiagnostics`] of the response the error was returned in.
+    pub fn with_diagnostics(mut self, diagnostics: ResponseDiagnostics) -> ErrorMessage {
+        self.diagnostics = Some(diagnostics);
+        self
+    }
+
+    /// The diagnostics of the response the error was returned in. When the response
+    /// headers are not available the diagnostics are taken from the inner error.
+    pub fn diagnostics(&self) -> Option<ResponseDiagnostics> {
+        if let Some(diagnostics) = self.diagnostics.as_ref() {
+            return Some(diagnostics.clone());
+        }
+
+        let inner_error = self.error.inner_error.as_ref()?;
+        Some(ResponseDiagnostics {
+            request_id: inner_error.request_id.clone(),
+            client_request_id: inner_error.client_request_id.clone(),
+            date: inner_error.date.clone(),
+            ..Default::default()
+        })
+    }
+}
+
+impl PartialEq for ErrorMessage {
+    fn eq(&self, other: &Self) -> bool {
+        self.error == other.error
+    }
 }
 
 impl Display for ErrorMessage {
@@ -0,0 +1,398 @@
+use crate::{ErrorDetail, ErrorMessage, ErrorStatus, ErrorType, InnerError, ResponseDiagnostics};
+use http::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Well known error codes returned by Microsoft Graph in the `code` of an error
+/// or inner error. Codes that are not listed are kept in [`GraphErrorCode::Other`].
+///
+/// [Code property](https://learn.microsoft.com/en-us/graph/errors#code-property)
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum GraphErrorCode {
+    AccessDenied,
+    ActivityLimitReached,
+    GeneralException,
+    InvalidRange,
+    InvalidRequest,
+    ItemNotFound,
+    MalwareDetected,
+    NameAlreadyExists,
+    NotAllowed,
+    NotSupported,
+    QuotaLimitReached,
+    ResourceModified,
+    ResyncRequired,
+    ServiceNotAvailable,
+    SyncStateNotFound,
+    Unauthenticated,
+    BadRequest,
+    TooManyRequests,
+    ErrorAccessDenied,
+    ErrorInvalidIdMalformed,
+    ErrorItemNotFound,
+    InvalidAuthenticationToken,
+    AuthorizationRequestDenied,
+    RequestBadRequest,
+    RequestResourceNotFound,
+    Other(String),
+}
+
+impl GraphErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            GraphErrorCode::AccessDenied => "accessDenied",
+            GraphErrorCode::ActivityLimitReached => "activityLimitReached",
+            GraphErrorCode::GeneralException => "generalException",
+            GraphErrorCode::InvalidRange => "invalidRange",
+            GraphErrorCode::InvalidRequest => "invalidRequest",
+            GraphErrorCode::ItemNotFound => "itemNotFound",
+            GraphErrorCode::MalwareDetected => "malwareDetected",
+            GraphErrorCode::NameAlreadyExists => "nameAlreadyExists",
+            GraphErrorCode::NotAllowed => "notAllowed",
+            GraphErrorCode::NotSupported => "notSupported",
+            GraphErrorCode::QuotaLimitReached => "quotaLimitReached",
+            GraphErrorCode::ResourceModified => "resourceModified",
+            GraphErrorCode::ResyncRequired => "resyncRequired",
+            GraphErrorCode::ServiceNotAvailable => "serviceNotAvailable",
+            GraphErrorCode::SyncStateNotFound => "syncStateNotFound",
+            GraphErrorCode::Unauthenticated => "unauthenticated",
+            GraphErrorCode::BadRequest => "BadRequest",
+            GraphErrorCode::TooManyRequests => "TooManyRequests",
+            GraphErrorCode::ErrorAccessDenied => "ErrorAccessDenied",
+            GraphErrorCode::ErrorInvalidIdMalformed => "ErrorInvalidIdMalformed",
+            GraphErrorCode::ErrorItemNotFound => "ErrorItemNotFound",
+            GraphErrorCode::InvalidAuthenticationToken => "InvalidAuthenticationToken",
+            GraphErrorCode::AuthorizationRequestDenied => "Authorization_RequestDenied",
+            GraphErrorCode::RequestBadRequest => "Request_BadRequest",
+            GraphErrorCode::RequestResourceNotFound => "Request_ResourceNotFound",
+            GraphErrorCode::Other(code) => code.as_str(),
+        }
+    }
+
+    /// Returns true for the codes of errors that can be resolved by sending the
+    /// same request again after a delay.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            GraphErrorCode::ActivityLimitReached
+                | GraphErrorCode::ServiceNotAvailable
+                | GraphErrorCode::TooManyRequests
+        )
+    }
+}
+
+impl From<&str> for GraphErrorCode {
+    fn from(value: &str) -> Self {
+        match value {
+            "accessDenied" => GraphErrorCode::AccessDenied,
+            "activityLimitReached" => GraphErrorCode::ActivityLimitReached,
+            "generalException" => GraphErrorCode::GeneralException,
+            "invalidRange" => GraphErrorCode::InvalidRange,
+            "invalidRequest" => GraphErrorCode::InvalidRequest,
+            "itemNotFound" => GraphErrorCode::ItemNotFound,
+            "malwareDetected" => GraphErrorCode::MalwareDetected,
+            "nameAlreadyExists" => GraphErrorCode::NameAlreadyExists,
+            "notAllowed" => GraphErrorCode::NotAllowed,
+            "notSupported" => GraphErrorCode::NotSupported,
+            "quotaLimitReached" => GraphErrorCode::QuotaLimitReached,
+            "resourceModified" => GraphErrorCode::ResourceModified,
+            "resyncRequired" => GraphErrorCode::ResyncRequired,
+            "serviceNotAvailable" => GraphErrorCode::ServiceNotAvailable,
+            "syncStateNotFound" => GraphErrorCode::SyncStateNotFound,
+            "unauthenticated" => GraphErrorCode::Unauthenticated,
+            "BadRequest" => GraphErrorCode::BadRequest,
+            "TooManyRequests" => GraphErrorCode::TooManyRequests,
+            "ErrorAccessDenied" => GraphErrorCode::ErrorAccessDenied,
+            "ErrorInvalidIdMalformed" => GraphErrorCode::ErrorInvalidIdMalformed,
+            "ErrorItemNotFound" => GraphErrorCode::ErrorItemNotFound,
+            "InvalidAuthenticationToken" => GraphErrorCode::InvalidAuthenticationToken,
+            "Authorization_RequestDenied" => GraphErrorCode::AuthorizationRequestDenied,
+            "Request_BadRequest" => GraphErrorCode::RequestBadRequest,
+            "Request_ResourceNotFound" => GraphErrorCode::RequestResourceNotFound,
+            _ => GraphErrorCode::Other(value.to_string()),
+        }
+    }
+}
+
+impl Display for GraphErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// An error response returned by Microsoft Graph. Keeps the http status, the parsed
+/// error resource with its `details` and chain of inner errors, and the diagnostics
+/// of the response.
+///
+/// Returned by `send_checked()` for responses that do not have a success status code.
+///
+/// [Microsoft Graph error responses](https://learn.microsoft.com/en-us/graph/errors)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GraphApiError {
+    status: StatusCode,
+    error: ErrorStatus,
+    retry_after: Option<Duration>,
+    diagnostics: ResponseDiagnostics,
+}
+
+impl GraphApiError {
+    /// Create the error from the parts of a response. A body that is not a Graph
+    /// error resource leaves the error resource empty.
+    pub fn from_parts(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> GraphApiError {
+        let error = serde_json::from_slice::<ErrorMessage>(body)
+            .map(|error_message| error_message.error)
+            .unwrap_or_default();
+
+        let mut diagnostics = ResponseDiagnostics::from(headers);
+        if let Some(inner_error) = error.inner_error.as_ref() {
+            if diagnostics.request_id.is_none() {
+                diagnostics.request_id = inner_error.request_id.clone();
+            }
+            if diagnostics.client_request_id.is_none() {
+                diagnostics.client_request_id = inner_error.client_request_id.clone();
+            }
+            if diagnostics.date.is_none() {
+                diagnostics.date = inner_error.date.clone();
+            }
+        }
+
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+
+        GraphApiError {
+            status,
+            error,
+            retry_after,
+            diagnostics,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn error_type(&self) -> Option<ErrorType> {
+        ErrorType::from_u16(self.status.as_u16())
+    }
+
+    /// The error resource of the response body.
+    pub fn error(&self) -> &ErrorStatus {
+        &self.error
+    }
+
+    /// The top level error code.
+    pub fn code(&self) -> Option<GraphErrorCode> {
+        self.error.code.as_deref().map(GraphErrorCode::from)
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.error.message.as_deref()
+    }
+
+    pub fn details(&self) -> &[ErrorDetail] {
+        self.error.details.as_slice()
+    }
+
+    /// The inner errors of the response, starting with the outermost inner error.
+    pub fn inner_errors(&self) -> impl Iterator<Item = &InnerError> {
+        self.error
+            .inner_error
+            .as_ref()
+            .into_iter()
+            .flat_map(|inner_error| inner_error.chain())
+    }
+
+    /// The most specific error code, which is the code of the innermost error that has one.
+    pub fn detailed_code(&self) -> Option<GraphErrorCode> {
+        self.inner_errors()
+            .filter_map(|inner_error| inner_error.code.as_deref())
+            .last()
+            .map(GraphErrorCode::from)
+            .or_else(|| self.code())
+    }
+
+    /// All error codes of the response: the top level code, the codes of the inner
+    /// errors, and the codes of the details.
+    pub fn codes(&self) -> Vec<GraphErrorCode> {
+        self.error
+            .code
+            .as_deref()
+            .into_iter()
+            .chain(
+                self.inner_errors()
+                    .filter_map(|inner_error| inner_error.code.as_deref()),
+            )
+            .chain(
+                self.error
+                    .details
+                    .iter()
+                    .filter_map(|detail| detail.code.as_deref()),
+            )
+            .map(GraphErrorCode::from)
+            .collect()
+    }
+
+    /// Returns true if the top level error, an inner error, or one of the details
+    /// has the code.
+    pub fn has_code(&self, code: &GraphErrorCode) -> bool {
+        self.codes().contains(code)
+    }
+
+    /// Returns true if the request may succeed when sent again after a delay, such as
+    /// when the request was throttled or the service was temporarily unavailable.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.status,
+            StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::INTERNAL_SERVER_ERROR
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT
+        ) || self.codes().iter().any(GraphErrorCode::is_transient)
+    }
+
+    /// Returns true if the request was not applied because its `If-Match` or
+    /// `If-None-Match` header did not match the current ETag of the resource.
+    pub fn is_precondition_failed(&self) -> bool {
+        self.status == StatusCode::PRECONDITION_FAILED
+    }
+
+    /// The delay from the `Retry-After` header before the request should be sent again.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    pub fn diagnostics(&self) -> &ResponseDiagnostics {
+        &self.diagnostics
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.diagnostics.request_id.as_deref()
+    }
+
+    pub fn client_request_id(&self) -> Option<&str> {
+        self.diagnostics.client_request_id.as_deref()
+    }
+}
+
+impl Display for GraphApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let Some(code) = self.error.code.as_deref() {
+            write!(f, " {code}")?;
+        }
+        if let Some(message) = self.error.message.as_deref() {
+            write!(f, ": {message}")?;
+        }
+        if let Some(request_id) = self.request_id() {
+            write!(f, " (request-id: {request_id})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for GraphApiError {}
+
+impl From<GraphApiError> for ErrorMessage {
+    fn from(value: GraphApiError) -> Self {
+        let mut error_message = ErrorMessage::default().with_diagnostics(value.diagnostics);
+        error_message.error = value.error;
+        error_message
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn error_body() -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "error": {
+                "code": "BadRequest",
+                "message": "Invalid request.",
+                "target": "user",
+                "details": [
+                    { "code": "nameAlreadyExists", "message": "Name exists.", "target": "displayName" }
+                ],
+                "innerError": {
+                    "code": "invalidRequest",
+                    "request-id": "body-request-id",
+                    "client-request-id": "body-client-request-id",
+                    "date": "2024-01-01T00:00:00",
+                    "innerError": {
+                        "code": "ErrorInvalidIdMalformed"
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_error_chain() {
+        let mut headers = HeaderMap::new();
+        headers.insert("request-id", HeaderValue::from_static("header-request-id"));
+        let error = GraphApiError::from_parts(StatusCode::BAD_REQUEST, &headers, &error_body());
+
+        assert_eq!(StatusCode::BAD_REQUEST, error.status());
+        assert_eq!(Some(ErrorType::BadRequest), error.error_type());
+        assert_eq!(Some(GraphErrorCode::BadRequest), error.code());
+        assert_eq!(Some("Invalid request."), error.message());
+        assert_eq!(Some("user"), error.error().target.as_deref());
+        assert_eq!(1, error.details().len());
+        assert_eq!(2, error.inner_errors().count());
+        assert_eq!(
+            Some(GraphErrorCode::ErrorInvalidIdMalformed),
+            error.detailed_code()
+        );
+        assert_eq!(
+            vec![
+                GraphErrorCode::BadRequest,
+                GraphErrorCode::InvalidRequest,
+                GraphErrorCode::ErrorInvalidIdMalformed,
+                GraphErrorCode::NameAlreadyExists,
+            ],
+            error.codes()
+        );
+        assert!(error.has_code(&GraphErrorCode::NameAlreadyExists));
+        assert!(!error.is_retryable());
+        assert_eq!(Some("header-request-id"), error.request_id());
+        assert_eq!(Some("body-client-request-id"), error.client_request_id());
+    }
+
+    #[test]
+    fn retryable_errors() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("10"));
+        let error = GraphApiError::from_parts(StatusCode::TOO_MANY_REQUESTS, &headers, b"");
+        assert!(error.is_retryable());
+        assert_eq!(Some(Duration::from_secs(10)), error.retry_after());
+        assert_eq!(None, error.code());
+
+        let body = br#"{"error":{"code":"activityLimitReached","message":"Throttled."}}"#;
+        let error = GraphApiError::from_parts(StatusCode::BAD_REQUEST, &HeaderMap::new(), body);
+        assert!(error.is_retryable());
+        assert_eq!(None, error.retry_after());
+    }
+
+    #[test]
+    fn error_code_round_trip() {
+        for code in [
+            "Request_ResourceNotFound",
+            "ErrorItemNotFound",
+            "resyncRequired",
+            "InvalidAuthenticationToken",
+            "unknownCode",
+        ] {
+            assert_eq!(code, GraphErrorCode::from(code).as_str());
+        }
+        assert_eq!(
+            GraphErrorCode::Other("unknownCode".into()),
+            GraphErrorCode::from("unknownCode")
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use crate::download::AsyncDownloadError;
 use crate::internal::GraphRsError;
-use crate::{AuthExecutionError, AuthorizationFailure, ErrorMessage};
+use crate::{
+    AsyncOperationError, AuthExecutionError, AuthorizationFailure, ConfigurationError,
+    ErrorMessage, GraphApiError, IdentityError, ResponseDiagnostics,
+};
 use reqwest::header::HeaderMap;
 use std::cell::BorrowMutError;
 use std::error::Error;
@@ -77,6 +80,21 @@ pub enum GraphFailure {
     #[error("{0:#?}")]
     ErrorMessage(#[from] ErrorMessage),
 
+    #[error("{0}")]
+    GraphApiError(Box<GraphApiError>),
+
+    /// A `412 Precondition Failed` response to a request with an `If-Match` or
+    /// `If-None-Match` header, such as when the resource was changed by another
+    /// client since its ETag was read.
+    #[error("{0}")]
+    PreconditionFailed(Box<GraphApiError>),
+
+    #[error("{0}")]
+    AsyncOperation(#[from] AsyncOperationError),
+
+    #[error("{0}")]
+    Configuration(#[from] ConfigurationError),
+
     #[error("Temporary Graph API Error")]
     TemporaryError,
 
@@ -89,6 +107,12 @@ pub enum GraphFailure {
         response: http::Response<Result<serde_json::Value, ErrorMessage>>,
     },
 
+    /// The revocation endpoint did not revoke the refresh token.
+    #[error("token revocation failed, response: {0:#?}", response)]
+    Revocation {
+        response: http::Response<Result<serde_json::Value, ErrorMessage>>,
+    },
+
     #[error("{0:#?}")]
     JsonWebToken(#[from] jsonwebtoken::errors::Error),
 
@@ -113,6 +137,66 @@ impl GraphFailure {
     pub fn invalid(msg: &str) -> Self {
         GraphFailure::internal(GraphRsError::InvalidOrMissing { msg: msg.into() })
     }
+
+    /// The typed identity platform error of a failed token request. See
+    /// [`AuthExecutionError::identity_error`].
+    pub fn identity_error(&self) -> Option<IdentityError> {
+        match self {
+            GraphFailure::SilentTokenAuth { response, .. }
+            | GraphFailure::Revocation { response } => IdentityError::from_response(response),
+            _ => None,
+        }
+    }
+
+    /// Returns true if a token cannot be acquired silently and the user must go
+    /// through an interactive sign in. See [`IdentityError::requires_user_interaction`]
+    pub fn requires_user_interaction(&self) -> bool {
+        self.identity_error()
+            .map(|identity_error| identity_error.requires_user_interaction())
+            .unwrap_or_default()
+    }
+
+    /// The [`ResponseDiagnostics`] of the error for correlating the request with the
+    /// service, such as when opening a support ticket. Errors that happen before a request
+    /// is sent only have the `client-request-id` of the request. Returns `None` if the
+    /// error has no request or response information.
+    pub fn diagnostics(&self) -> Option<ResponseDiagnostics> {
+        let diagnostics = match self {
+            GraphFailure::PreFlightError { headers, .. }
+            | GraphFailure::Default { headers, .. } => {
+                headers.as_ref().map(ResponseDiagnostics::from)
+            }
+            GraphFailure::ErrorMessage(error_message) => error_message.diagnostics(),
+            GraphFailure::GraphApiError(error) | GraphFailure::PreconditionFailed(error) => {
+                Some(error.diagnostics().clone())
+            }
+            GraphFailure::SilentTokenAuth { response, .. }
+            | GraphFailure::Revocation { response } => {
+                Some(ResponseDiagnostics::from(response.headers()))
+            }
+            GraphFailure::Io(_)
+            | GraphFailure::Utf8Error(_)
+            | GraphFailure::ReqwestError(_)
+            | GraphFailure::SerdeJson(_)
+            | GraphFailure::DecodeError(_)
+            | GraphFailure::RecvError(_)
+            | GraphFailure::BorrowMutError(_)
+            | GraphFailure::UrlParse(_)
+            | GraphFailure::HttpError(_)
+            | GraphFailure::GraphRsError(_)
+            | GraphFailure::HandlebarsRenderError(_)
+            | GraphFailure::HandlebarsTemplateRenderError(_)
+            | GraphFailure::CryptoError
+            | GraphFailure::AsyncDownloadError(_)
+            | GraphFailure::AsyncOperation(_)
+            | GraphFailure::Configuration(_)
+            | GraphFailure::TemporaryError
+            | GraphFailure::ParseIntError(_)
+            | GraphFailure::JsonWebToken(_)
+            | GraphFailure::Other(_) => None,
+        };
+        diagnostics.filter(|diagnostics| !diagnostics.is_empty())
+    }
 }
 
 impl Default for GraphFailure {
@@ -125,6 +209,16 @@ impl Default for GraphFailure {
     }
 }
 
+impl From<GraphApiError> for GraphFailure {
+    fn from(value: GraphApiError) -> Self {
+        if value.is_precondition_failed() {
+            GraphFailure::PreconditionFailed(Box::new(value))
+        } else {
+            GraphFailure::GraphApiError(Box::new(value))
+        }
+    }
+}
+
 impl From<ring::error::Unspecified> for GraphFailure {
     fn from(_: ring::error::Unspecified) -> Self {
         GraphFailure::CryptoError
@@ -168,7 +262,9 @@ impl From<AuthExecutionError> for GraphFailure {
             AuthExecutionError::SilentTokenAuth { message, response } => {
                 GraphFailure::SilentTokenAuth { message, response }
             }
+            AuthExecutionError::Revocation { response } => GraphFailure::Revocation { response },
             AuthExecutionError::JsonWebToken(error) => GraphFailure::JsonWebToken(error),
+            AuthExecutionError::Configuration(error) => GraphFailure::Configuration(error),
             AuthExecutionError::Other(e) => GraphFailure::Other(e),
         }
     }
@@ -0,0 +1,347 @@
+use crate::ErrorMessage;
+use std::fmt::{Display, Formatter};
+
+/// AADSTS error code returned when the user must use multi-factor authentication.
+pub const AADSTS_MFA_REQUIRED: i64 = 50076;
+/// AADSTS error code returned when the user must register for multi-factor authentication.
+pub const AADSTS_MFA_REGISTRATION_REQUIRED: i64 = 50079;
+/// AADSTS error code returned when the user or administrator has not consented to the application.
+pub const AADSTS_CONSENT_REQUIRED: i64 = 65001;
+
+/// Error response body returned by the Microsoft identity platform token endpoint.
+///
+/// [Error response](https://learn.microsoft.com/en-us/entra/identity-platform/v2-oauth2-auth-code-flow#error-response-1)
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct IdentityErrorResponse {
+    /// An error code string that can be used to classify types of errors that occur,
+    /// and to react to errors.
+    pub error: String,
+    /// A specific error message that can help a developer identify the root cause
+    /// of an authentication error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+    /// A list of AADSTS error codes that can help in diagnostics.
+    #[serde(default)]
+    pub error_codes: Vec<i64>,
+    /// The time at which the error occurred.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// A unique identifier for the request that can help in diagnostics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// A unique identifier for the request that can help in diagnostics across components.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// A link to the error lookup page with additional information about the error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_uri: Option<String>,
+    /// Used to further classify `invalid_grant` and `interaction_required` errors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suberror: Option<String>,
+    /// Claims that must be sent in the `claims` parameter of the next authorization
+    /// or token request, such as for a conditional access policy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claims: Option<String>,
+}
+
+impl IdentityErrorResponse {
+    /// The parsed [`Suberror`], if any.
+    pub fn suberror(&self) -> Option<Suberror> {
+        self.suberror.as_deref().map(Suberror::from)
+    }
+}
+
+/// Further classification of `invalid_grant` and `interaction_required` errors.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Suberror {
+    /// The user can resolve the condition with a basic interaction, such as re-entering
+    /// their password.
+    BasicAction,
+    /// The user must take additional action outside of the sign in, such as
+    /// registering for multi-factor authentication.
+    AdditionalAction,
+    /// The user must be shown a message and cannot resolve it with an interaction.
+    MessageOnly,
+    /// The user or administrator must consent to the application.
+    ConsentRequired,
+    /// The password of the user has expired.
+    UserPasswordExpired,
+    /// The token is invalid or has been revoked.
+    BadToken,
+    /// The token was issued to a different client.
+    ClientMismatch,
+    Unknown(String),
+}
+
+impl Suberror {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Suberror::BasicAction => "basic_action",
+            Suberror::AdditionalAction => "additional_action",
+            Suberror::MessageOnly => "message_only",
+            Suberror::ConsentRequired => "consent_required",
+            Suberror::UserPasswordExpired => "user_password_expired",
+            Suberror::BadToken => "bad_token",
+            Suberror::ClientMismatch => "client_mismatch",
+            Suberror::Unknown(suberror) => suberror.as_str(),
+        }
+    }
+}
+
+impl From<&str> for Suberror {
+    fn from(value: &str) -> Self {
+        match value {
+            "basic_action" => Suberror::BasicAction,
+            "additional_action" => Suberror::AdditionalAction,
+            "message_only" => Suberror::MessageOnly,
+            "consent_required" => Suberror::ConsentRequired,
+            "user_password_expired" => Suberror::UserPasswordExpired,
+            "bad_token" => Suberror::BadToken,
+            "client_mismatch" => Suberror::ClientMismatch,
+            _ => Suberror::Unknown(value.to_owned()),
+        }
+    }
+}
+
+impl Display for Suberror {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Typed error returned by the Microsoft identity platform when a token request fails.
+///
+/// The variant is chosen from the AADSTS error codes first and the `error` value second
+/// so that conditional access and multi-factor authentication step-up errors can be
+/// detected without inspecting the raw response.
+///
+/// # Example
+/// ```rust,ignore
+/// match confidential_client.get_token_silent_async().await {
+///     Err(error) if error.requires_user_interaction() => {
+///         // Open an interactive prompt and send the claims of error.identity_error()
+///         // in the authorization request.
+///     }
+///     _ => {}
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum IdentityError {
+    /// `interaction_required` - the request requires user interaction.
+    #[error("interaction_required: {0:#?}")]
+    InteractionRequired(IdentityErrorResponse),
+    /// `consent_required` or AADSTS65001 - the user must consent to the application.
+    #[error("consent_required: {0:#?}")]
+    ConsentRequired(IdentityErrorResponse),
+    /// `login_required` - the user must sign in again.
+    #[error("login_required: {0:#?}")]
+    LoginRequired(IdentityErrorResponse),
+    /// AADSTS50076 - the user must use multi-factor authentication.
+    #[error("mfa_required: {0:#?}")]
+    MfaRequired(IdentityErrorResponse),
+    /// AADSTS50079 - the user must register for multi-factor authentication.
+    #[error("mfa_registration_required: {0:#?}")]
+    MfaRegistrationRequired(IdentityErrorResponse),
+    /// `invalid_grant` - the refresh token, authorization code, or credential is invalid
+    /// or has expired.
+    #[error("invalid_grant: {0:#?}")]
+    InvalidGrant(IdentityErrorResponse),
+    /// Any other error returned by the identity platform.
+    #[error("{0:#?}")]
+    Other(IdentityErrorResponse),
+}
+
+impl IdentityError {
+    /// Parse the error response body of a failed token request. Returns `None` if the
+    /// body is not an identity platform error response.
+    pub fn from_value(value: &serde_json::Value) -> Option<IdentityError> {
+        let response: IdentityErrorResponse = serde_json::from_value(value.clone()).ok()?;
+        Some(IdentityError::from(response))
+    }
+
+    /// The typed error of a failed token request response. Returns `None` when the body
+    /// is not an identity platform error response.
+    pub fn from_response(
+        response: &http::Response<Result<serde_json::Value, ErrorMessage>>,
+    ) -> Option<IdentityError> {
+        response
+            .body()
+            .as_ref()
+            .ok()
+            .and_then(IdentityError::from_value)
+    }
+
+    /// The error response returned by the identity platform.
+    pub fn response(&self) -> &IdentityErrorResponse {
+        match self {
+            IdentityError::InteractionRequired(response)
+            | IdentityError::ConsentRequired(response)
+            | IdentityError::LoginRequired(response)
+            | IdentityError::MfaRequired(response)
+            | IdentityError::MfaRegistrationRequired(response)
+            | IdentityError::InvalidGrant(response)
+            | IdentityError::Other(response) => response,
+        }
+    }
+
+    pub fn error(&self) -> &str {
+        self.response().error.as_str()
+    }
+
+    pub fn error_description(&self) -> Option<&str> {
+        self.response().error_description.as_deref()
+    }
+
+    pub fn error_codes(&self) -> &[i64] {
+        self.response().error_codes.as_slice()
+    }
+
+    pub fn suberror(&self) -> Option<Suberror> {
+        self.response().suberror()
+    }
+
+    /// Claims that must be sent in the `claims` parameter of the next authorization request.
+    pub fn claims(&self) -> Option<&str> {
+        self.response().claims.as_deref()
+    }
+
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.response().correlation_id.as_deref()
+    }
+
+    pub fn trace_id(&self) -> Option<&str> {
+        self.response().trace_id.as_deref()
+    }
+
+    pub fn error_uri(&self) -> Option<&str> {
+        self.response().error_uri.as_deref()
+    }
+
+    /// Returns true if a token cannot be acquired silently and the user must go
+    /// through an interactive sign in, such as for multi-factor authentication
+    /// step-up, consent, or a conditional access policy.
+    pub fn requires_user_interaction(&self) -> bool {
+        match self {
+            IdentityError::InteractionRequired(_)
+            | IdentityError::ConsentRequired(_)
+            | IdentityError::LoginRequired(_)
+            | IdentityError::MfaRequired(_)
+            | IdentityError::MfaRegistrationRequired(_) => true,
+            IdentityError::InvalidGrant(response) => matches!(
+                response.suberror(),
+                Some(
+                    Suberror::BasicAction
+                        | Suberror::AdditionalAction
+                        | Suberror::ConsentRequired
+                        | Suberror::UserPasswordExpired
+                        | Suberror::BadToken
+                )
+            ),
+            IdentityError::Other(_) => false,
+        }
+    }
+}
+
+impl From<IdentityErrorResponse> for IdentityError {
+    fn from(response: IdentityErrorResponse) -> Self {
+        let has_code = |code: i64| response.error_codes.contains(&code);
+
+        if has_code(AADSTS_MFA_REQUIRED) {
+            IdentityError::MfaRequired(response)
+        } else if has_code(AADSTS_MFA_REGISTRATION_REQUIRED) {
+            IdentityError::MfaRegistrationRequired(response)
+        } else if has_code(AADSTS_CONSENT_REQUIRED) {
+            IdentityError::ConsentRequired(response)
+        } else {
+            match response.error.as_str() {
+                "interaction_required" => IdentityError::InteractionRequired(response),
+                "consent_required" => IdentityError::ConsentRequired(response),
+                "login_required" => IdentityError::LoginRequired(response),
+                "invalid_grant" => IdentityError::InvalidGrant(response),
+                _ => IdentityError::Other(response),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mfa_required_from_error_codes() {
+        let value = serde_json::json!({
+            "error": "invalid_grant",
+            "error_description": "AADSTS50076: Due to a configuration change made by your administrator, or because you moved to a new location, you must use multi-factor authentication to access.",
+            "error_codes": [50076],
+            "timestamp": "2023-11-01 10:00:00Z",
+            "trace_id": "0b4c6a6e-0000-0000-0000-000000000000",
+            "correlation_id": "1c5d7b7f-0000-0000-0000-000000000000",
+            "error_uri": "https://login.microsoftonline.com/error?code=50076",
+            "suberror": "basic_action",
+            "claims": "{\"access_token\":{\"capolids\":{\"essential\":true,\"values\":[\"1\"]}}}"
+        });
+
+        let error = IdentityError::from_value(&value).unwrap();
+        assert!(matches!(error, IdentityError::MfaRequired(_)));
+        assert!(error.requires_user_interaction());
+        assert_eq!(error.suberror(), Some(Suberror::BasicAction));
+        assert_eq!(
+            error.correlation_id(),
+            Some("1c5d7b7f-0000-0000-0000-000000000000")
+        );
+        assert_eq!(
+            error.trace_id(),
+            Some("0b4c6a6e-0000-0000-0000-000000000000")
+        );
+        assert!(error.claims().is_some());
+    }
+
+    #[test]
+    fn invalid_client_does_not_require_interaction() {
+        let value = serde_json::json!({
+            "error": "invalid_client",
+            "error_codes": [7000215]
+        });
+
+        let error = IdentityError::from_value(&value).unwrap();
+        assert!(matches!(error, IdentityError::Other(_)));
+        assert!(!error.requires_user_interaction());
+        assert!(IdentityError::from_value(&serde_json::json!({ "value": [] })).is_none());
+    }
+
+    #[test]
+    fn silent_token_auth_keeps_response() {
+        let value = serde_json::json!({
+            "error": "interaction_required",
+            "error_codes": [50076],
+            "claims": "{\"access_token\":{\"capolids\":{\"essential\":true,\"values\":[\"1\"]}}}"
+        });
+        let response = http::Response::builder()
+            .status(400)
+            .header("x-ms-request-id", "request-id")
+            .body(Ok(value))
+            .unwrap();
+
+        let error = crate::AuthExecutionError::silent_token_auth(response);
+        match &error {
+            crate::AuthExecutionError::SilentTokenAuth { response, .. } => {
+                assert_eq!(400, response.status().as_u16());
+                assert!(response.headers().contains_key("x-ms-request-id"));
+            }
+            _ => panic!("expected SilentTokenAuth"),
+        }
+        assert!(matches!(
+            error.identity_error(),
+            Some(IdentityError::MfaRequired(_))
+        ));
+        assert!(error.requires_user_interaction());
+
+        let failure = crate::GraphFailure::from(error);
+        assert!(matches!(
+            failure.identity_error(),
+            Some(IdentityError::MfaRequired(_))
+        ));
+        assert!(failure.requires_user_interaction());
+    }
+}
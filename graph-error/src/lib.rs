@@ -3,17 +3,27 @@
 #[macro_use]
 extern crate serde;
 
+mod async_operation_error;
 mod authorization_failure;
+mod configuration_error;
+mod diagnostics;
 pub mod download;
 mod error;
+mod graph_api_error;
 mod graph_failure;
+mod identity_error;
 mod internal;
 pub mod io_error;
 mod webview_error;
 
+pub use async_operation_error::*;
 pub use authorization_failure::*;
+pub use configuration_error::*;
+pub use diagnostics::*;
 pub use error::*;
+pub use graph_api_error::*;
 pub use graph_failure::*;
+pub use identity_error::*;
 pub use internal::*;
 pub use webview_error::*;
 
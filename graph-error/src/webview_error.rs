@@ -42,6 +42,7 @@ impl From<AuthorizationFailure> for WebViewError {
 }
 
 #[derive(Debug, thiserror::Error)]
+#[allow(clippy::large_enum_variant)]
 pub enum WebViewDeviceCodeError {
     /// Webview Window closed for one of the following reasons:
     /// 1. The user closed the webview window without logging in.
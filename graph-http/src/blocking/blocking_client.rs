@@ -10,6 +10,7 @@ pub struct BlockingClient {
     pub(crate) inner: reqwest::blocking::Client,
     pub(crate) client_application: Box<dyn ClientApplication>,
     pub(crate) headers: HeaderMap,
+    pub(crate) strict_advanced_queries: bool,
 }
 
 impl BlockingClient {
@@ -47,6 +48,7 @@ impl Debug for BlockingClient {
         f.debug_struct("BlockingClient")
             .field("inner", &self.inner)
             .field("headers", &self.headers)
+            .field("strict_advanced_queries", &self.strict_advanced_queries)
             .finish()
     }
 }
@@ -1,7 +1,9 @@
 use crate::blocking::blocking_client::BlockingClient;
 use crate::internal::*;
-use graph_error::{ErrorMessage, GraphFailure, GraphResult};
-use http::header::CONTENT_TYPE;
+use crate::odata::Filter;
+use graph_core::identity::{AuthScheme, ClientApplication};
+use graph_error::{ErrorMessage, GraphApiError, GraphFailure, GraphResult, ResponseDiagnostics};
+use http::header::{AUTHORIZATION, CONTENT_TYPE, IF_MATCH, IF_NONE_MATCH};
 use http::{HeaderMap, HeaderName, HeaderValue};
 use serde::de::DeserializeOwned;
 use std::collections::VecDeque;
@@ -13,6 +15,7 @@ pub struct BlockingRequestHandler {
     pub(crate) request_components: RequestComponents,
     pub(crate) error: Option<GraphFailure>,
     pub(crate) body: Option<BodyRead>,
+    pub(crate) typed_filter: Option<TypedFilter>,
 }
 
 impl BlockingRequestHandler {
@@ -23,6 +26,7 @@ impl BlockingRequestHandler {
         body: Option<BodyRead>,
     ) -> BlockingRequestHandler {
         request_components.headers.extend(inner.headers.clone());
+        request_components.client_request_id();
 
         let mut error = None;
         if let Some(err) = err {
@@ -40,6 +44,7 @@ impl BlockingRequestHandler {
             request_components,
             error,
             body,
+            typed_filter: None,
         }
     }
 
@@ -129,13 +134,81 @@ impl BlockingRequestHandler {
         self.request_components.as_mut()
     }
 
+    /// Only apply the request if the ETag of the resource matches `etag`, or if the
+    /// resource exists when `etag` is `*`. Planner requires `If-Match` for updates and
+    /// deletes. A resource that changed since `etag` was read fails the request with
+    /// `412 Precondition Failed`, returned as [`GraphFailure::PreconditionFailed`] by
+    /// `send_checked`.
+    #[inline]
+    pub fn if_match<T: AsRef<str>>(self, etag: T) -> Self {
+        self.conditional_header(IF_MATCH, etag.as_ref())
+    }
+
+    /// Only apply the request if the ETag of the resource does not match `etag`. A `GET`
+    /// for a resource that was not modified returns `304 Not Modified` without a body.
+    #[inline]
+    pub fn if_none_match<T: AsRef<str>>(self, etag: T) -> Self {
+        self.conditional_header(IF_NONE_MATCH, etag.as_ref())
+    }
+
+    fn conditional_header(mut self, header_name: HeaderName, etag: &str) -> Self {
+        match HeaderValue::from_str(etag) {
+            Ok(header_value) => {
+                self.request_components
+                    .headers
+                    .insert(header_name, header_value);
+            }
+            Err(err) => {
+                if self.error.is_none() {
+                    self.error = Some(GraphFailure::from(http::Error::from(err)));
+                }
+            }
+        }
+        self
+    }
+
+    /// Set the `client-request-id` header of the request. By default a random UUID is
+    /// used. The same id is sent when the request is retried.
+    #[inline]
+    pub fn client_request_id<T: AsRef<str>>(mut self, client_request_id: T) -> Self {
+        match HeaderValue::from_str(client_request_id.as_ref()) {
+            Ok(header_value) => {
+                self.request_components
+                    .headers
+                    .insert(CLIENT_REQUEST_ID.clone(), header_value);
+            }
+            Err(err) => {
+                if self.error.is_none() {
+                    self.error = Some(GraphFailure::from(http::Error::from(err)));
+                }
+            }
+        }
+        self
+    }
+
     pub fn paging(self) -> BlockingPaging {
         BlockingPaging(self)
     }
 
     #[inline]
     fn default_request_builder(&mut self) -> GraphResult<reqwest::blocking::RequestBuilder> {
+        let (_, request_builder) = self.default_request_builder_with_token()?;
+        Ok(request_builder)
+    }
+
+    fn default_request_builder_with_token(
+        &mut self,
+    ) -> GraphResult<(String, reqwest::blocking::RequestBuilder)> {
         let access_token = self.inner.client_application.get_token_silent()?;
+        let authorization = self
+            .inner
+            .client_application
+            .auth_scheme()
+            .authorization_header(
+                access_token.as_str(),
+                &self.request_components.method,
+                &self.request_components.url,
+            )?;
 
         let request_builder = self
             .inner
@@ -144,7 +217,7 @@ impl BlockingRequestHandler {
                 self.request_components.method.clone(),
                 self.request_components.url.clone(),
             )
-            .bearer_auth(access_token.as_str())
+            .header(AUTHORIZATION, authorization)
             .headers(self.request_components.headers.clone());
 
         if let Some(body) = self.body.take() {
@@ -159,11 +232,14 @@ impl BlockingRequestHandler {
                     .entry(CONTENT_TYPE)
                     .or_insert(HeaderValue::from_static("application/json"));
             }
-            return Ok(request_builder
-                .body::<reqwest::blocking::Body>(body.into())
-                .headers(self.request_components.headers.clone()));
+            return Ok((
+                access_token,
+                request_builder
+                    .body::<reqwest::blocking::Body>(body.into())
+                    .headers(self.request_components.headers.clone()),
+            ));
         }
-        Ok(request_builder)
+        Ok((access_token, request_builder))
     }
 
     /// Builds the request and returns a [`reqwest::blocking::RequestBuilder`].
@@ -172,13 +248,63 @@ impl BlockingRequestHandler {
         if let Some(err) = self.error {
             return Err(err);
         }
+        self.advanced_query()?;
         self.default_request_builder()
     }
 
+    /// Sends the request. If the resource responds with a claims challenge, such as when
+    /// Continuous Access Evaluation (CAE) revokes a token, a new access token is requested
+    /// with the claims from the challenge and the request is retried once.
     #[inline]
-    pub fn send(self) -> GraphResult<reqwest::blocking::Response> {
-        let request_builder = self.build()?;
-        request_builder.send().map_err(GraphFailure::from)
+    pub fn send(mut self) -> GraphResult<reqwest::blocking::Response> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.advanced_query()?;
+
+        let (mut access_token, request) = self.default_request_builder_with_token()?;
+        execute_with_claims_challenge(
+            &mut self.inner.client_application,
+            &self.inner.inner,
+            request.build()?,
+            &mut access_token,
+        )
+    }
+
+    /// Sends the request and returns an error for responses that do not have a success
+    /// status code. The error is a [`GraphFailure::GraphApiError`] with the status, the
+    /// parsed error resource, and the diagnostics of the response, or a
+    /// [`GraphFailure::PreconditionFailed`] when the `If-Match` or `If-None-Match` header
+    /// of the request did not match the ETag of the resource.
+    pub fn send_checked(self) -> GraphResult<reqwest::blocking::Response> {
+        let response = self.send()?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let headers = response.headers().clone();
+        let body = response.bytes()?;
+        Err(GraphApiError::from_parts(status, &headers, &body).into())
+    }
+
+    /// Adds the `ConsistencyLevel` header and `$count=true` query parameter required by
+    /// advanced queries on directory objects, or returns an error in strict mode.
+    fn advanced_query(&mut self) -> GraphResult<()> {
+        let strict = self.inner.strict_advanced_queries;
+        self.request_components
+            .advanced_query(strict, self.typed_filter.as_ref())
+    }
+
+    /// Sets the `ConsistencyLevel: eventual` header for the query methods that use advanced
+    /// query capabilities. Only directory objects support advanced queries. In strict mode
+    /// the header is not set so that a missing header is returned as an error.
+    fn advanced_query_consistency_level(self) -> Self {
+        if self.request_components.is_directory_object() && !self.inner.strict_advanced_queries {
+            self.consistency_level_eventual()
+        } else {
+            self
+        }
     }
 }
 
@@ -186,6 +312,37 @@ impl ODataQuery for BlockingRequestHandler {
     fn append_query_pair<KV: AsRef<str>>(self, key: KV, value: KV) -> Self {
         self.append_query_pair(key.as_ref(), value.as_ref())
     }
+
+    fn consistency_level_eventual(self) -> Self {
+        self.header(
+            HeaderName::from_static("consistencylevel"),
+            HeaderValue::from_static("eventual"),
+        )
+    }
+
+    fn count<S: AsRef<str>>(self, value: S) -> Self {
+        match value.as_ref().eq("true") {
+            true => self.advanced_query_consistency_level(),
+            false => self,
+        }
+        .append_query_pair("$count", value.as_ref())
+    }
+
+    fn filter_by(self, filter: Filter) -> Self {
+        let typed_filter = TypedFilter::from(&filter);
+        let mut handler = match typed_filter.is_advanced_query {
+            true => self.advanced_query_consistency_level(),
+            false => self,
+        }
+        .append_query_pair("$filter", typed_filter.filter.as_str());
+        handler.typed_filter = Some(typed_filter);
+        handler
+    }
+
+    fn search<S: AsRef<str>>(self, value: S) -> Self {
+        self.advanced_query_consistency_level()
+            .append_query_pair("$search", value.as_ref())
+    }
 }
 
 impl AsRef<Url> for BlockingRequestHandler {
@@ -203,6 +360,24 @@ impl AsMut<Url> for BlockingRequestHandler {
 pub struct BlockingPaging(BlockingRequestHandler);
 
 impl BlockingPaging {
+    fn authorization(
+        auth_scheme: &AuthScheme,
+        access_token: &str,
+        next_link: &str,
+    ) -> GraphResult<String> {
+        let url = Url::parse(next_link)?;
+        Ok(auth_scheme.authorization_header(access_token, &http::Method::GET, &url)?)
+    }
+
+    fn call(
+        client_application: &mut Box<dyn ClientApplication>,
+        client: &reqwest::blocking::Client,
+        request: reqwest::blocking::RequestBuilder,
+        access_token: &mut String,
+    ) -> GraphResult<reqwest::blocking::Response> {
+        execute_with_claims_challenge(client_application, client, request.build()?, access_token)
+    }
+
     fn http_response<T: DeserializeOwned>(
         response: reqwest::blocking::Response,
     ) -> GraphResult<(Option<String>, PagingResponse<T>)> {
@@ -214,8 +389,11 @@ impl BlockingPaging {
         let body: serde_json::Value = response.json()?;
         let next_link = body.odata_next_link();
         let json = body.clone();
-        let body_result: Result<T, ErrorMessage> = serde_json::from_value(body)
-            .map_err(|_| serde_json::from_value(json.clone()).unwrap_or(ErrorMessage::default()));
+        let body_result: Result<T, ErrorMessage> = serde_json::from_value(body).map_err(|_| {
+            serde_json::from_value::<ErrorMessage>(json.clone())
+                .unwrap_or_default()
+                .with_diagnostics(ResponseDiagnostics::from(&headers))
+        });
 
         let mut builder = http::Response::builder()
             .url(url)
@@ -256,23 +434,31 @@ impl BlockingPaging {
         if let Some(err) = self.0.error {
             return Err(err);
         }
+        self.0.advanced_query()?;
 
-        let request = self.0.default_request_builder()?;
-        let response = request.send()?;
+        let client = self.0.inner.inner.clone();
+        let mut client_application = self.0.inner.client_application.clone();
+        let (mut access_token, request) = self.0.default_request_builder_with_token()?;
+        let response =
+            BlockingPaging::call(&mut client_application, &client, request, &mut access_token)?;
 
         let (next, http_response) = BlockingPaging::http_response(response)?;
         let mut next_link = next;
         let mut vec = VecDeque::new();
         vec.push_back(http_response);
 
-        let client = self.0.inner.inner.clone();
-        let access_token = self.0.inner.client_application.get_token_silent()?;
+        let auth_scheme = client_application.auth_scheme();
         while let Some(next) = next_link {
-            let response = client
-                .get(next)
-                .bearer_auth(access_token.as_str())
-                .send()
-                .map_err(GraphFailure::from)?;
+            let authorization = BlockingPaging::authorization(&auth_scheme, &access_token, &next)?;
+            let response = BlockingPaging::call(
+                &mut client_application,
+                &client,
+                client
+                    .get(next)
+                    .header(AUTHORIZATION, authorization)
+                    .header(&CLIENT_REQUEST_ID, new_client_request_id()),
+                &mut access_token,
+            )?;
 
             let (next, http_response) = BlockingPaging::http_response(response)?;
 
@@ -285,14 +471,21 @@ impl BlockingPaging {
 
     fn send_channel_request<T: DeserializeOwned>(
         client: &reqwest::blocking::Client,
+        client_application: &mut Box<dyn ClientApplication>,
         next: &str,
-        access_token: &str,
+        access_token: &mut String,
+        auth_scheme: &AuthScheme,
     ) -> GraphResult<(Option<String>, PagingResponse<T>)> {
-        let response = client
-            .get(next)
-            .bearer_auth(access_token)
-            .send()
-            .map_err(GraphFailure::from)?;
+        let authorization = BlockingPaging::authorization(auth_scheme, access_token, next)?;
+        let response = BlockingPaging::call(
+            client_application,
+            client,
+            client
+                .get(next)
+                .header(AUTHORIZATION, authorization)
+                .header(&CLIENT_REQUEST_ID, new_client_request_id()),
+            access_token,
+        )?;
 
         BlockingPaging::http_response(response)
     }
@@ -301,22 +494,27 @@ impl BlockingPaging {
         mut self,
     ) -> GraphResult<std::sync::mpsc::Receiver<Option<PagingResult<T>>>> {
         let (sender, receiver) = std::sync::mpsc::channel();
-        let request = self.0.default_request_builder()?;
-        let response = request.send()?;
+        self.0.advanced_query()?;
+        let client = self.0.inner.inner.clone();
+        let mut client_application = self.0.inner.client_application.clone();
+        let (mut access_token, request) = self.0.default_request_builder_with_token()?;
+        let response =
+            BlockingPaging::call(&mut client_application, &client, request, &mut access_token)?;
 
         let (next, http_response) = BlockingPaging::http_response(response)?;
         let mut next_link = next;
         sender.send(Some(Ok(http_response))).unwrap();
 
-        let client = self.0.inner.inner.clone();
-        let access_token = self.0.inner.client_application.get_token_silent()?;
+        let auth_scheme = client_application.auth_scheme();
 
         std::thread::spawn(move || {
             while let Some(next) = next_link.as_ref() {
                 let result = BlockingPaging::send_channel_request(
                     &client,
+                    &mut client_application,
                     next.as_str(),
-                    access_token.as_str(),
+                    &mut access_token,
+                    &auth_scheme,
                 );
                 if let Ok((next_option, http_response)) = result {
                     next_link = next_option;
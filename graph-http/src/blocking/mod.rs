@@ -1,7 +1,9 @@
 mod blocking_client;
 mod blocking_request_handler;
+mod typed_blocking_request_handler;
 mod upload_session_blocking;
 
 pub use blocking_client::*;
 pub use blocking_request_handler::*;
+pub use typed_blocking_request_handler::*;
 pub use upload_session_blocking::*;
@@ -0,0 +1,135 @@
+use crate::blocking::{BlockingPaging, BlockingRequestHandler};
+use crate::internal::ODataQuery;
+use crate::odata::Filter;
+use crate::typed_request_handler::{typed_response, TypedResult};
+use graph_error::GraphFailure;
+use http::{HeaderMap, HeaderName, HeaderValue};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use url::Url;
+
+/// Blocking version of [`TypedRequestHandler`](crate::api_impl::TypedRequestHandler).
+pub struct TypedBlockingRequestHandler<T> {
+    handler: BlockingRequestHandler,
+    response_type: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> TypedBlockingRequestHandler<T> {
+    pub fn new(handler: BlockingRequestHandler) -> TypedBlockingRequestHandler<T> {
+        TypedBlockingRequestHandler {
+            handler,
+            response_type: PhantomData,
+        }
+    }
+
+    /// Returns the untyped [`BlockingRequestHandler`].
+    pub fn into_inner(self) -> BlockingRequestHandler {
+        self.handler
+    }
+
+    /// Returns true if any errors occurred prior to sending the request.
+    pub fn is_err(&self) -> bool {
+        self.handler.is_err()
+    }
+
+    /// Returns any error wrapped in an Option that occurred prior to sending a request
+    pub fn err(&self) -> Option<&GraphFailure> {
+        self.handler.err()
+    }
+
+    #[inline]
+    pub fn url(&self) -> Url {
+        self.handler.url()
+    }
+
+    #[inline]
+    pub fn query<Q: serde::Serialize + ?Sized>(mut self, query: &Q) -> Self {
+        self.handler = self.handler.query(query);
+        self
+    }
+
+    #[inline]
+    pub fn append_query_pair<KV: AsRef<str>>(mut self, key: KV, value: KV) -> Self {
+        self.handler = self.handler.append_query_pair(key, value);
+        self
+    }
+
+    /// Insert a header for the request.
+    #[inline]
+    pub fn header<K: Into<HeaderName>, V: Into<HeaderValue>>(
+        mut self,
+        header_name: K,
+        header_value: V,
+    ) -> Self {
+        self.handler = self.handler.header(header_name, header_value);
+        self
+    }
+
+    /// Set the headers for the request using reqwest::HeaderMap
+    #[inline]
+    pub fn headers(mut self, header_map: HeaderMap) -> Self {
+        self.handler = self.handler.headers(header_map);
+        self
+    }
+
+    /// Get a mutable reference to the headers.
+    #[inline]
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        self.handler.headers_mut()
+    }
+
+    pub fn paging(self) -> BlockingPaging {
+        self.handler.paging()
+    }
+
+    /// Sends the request and deserializes the response body. See
+    /// [`TypedRequestHandler::send`](crate::api_impl::TypedRequestHandler::send).
+    pub fn send(self) -> TypedResult<T> {
+        let response = self.handler.send()?;
+        let status = response.status();
+        let url = response.url().clone();
+        let headers = response.headers().clone();
+        let version = response.version();
+        let bytes = response.bytes()?;
+
+        typed_response(status, url, headers, version, bytes.as_ref())
+    }
+}
+
+impl<T: DeserializeOwned> ODataQuery for TypedBlockingRequestHandler<T> {
+    fn append_query_pair<KV: AsRef<str>>(self, key: KV, value: KV) -> Self {
+        self.append_query_pair(key.as_ref(), value.as_ref())
+    }
+
+    fn consistency_level_eventual(mut self) -> Self {
+        self.handler = self.handler.consistency_level_eventual();
+        self
+    }
+
+    fn count<S: AsRef<str>>(mut self, value: S) -> Self {
+        self.handler = self.handler.count(value);
+        self
+    }
+
+    fn filter_by(mut self, filter: Filter) -> Self {
+        self.handler = self.handler.filter_by(filter);
+        self
+    }
+
+    fn search<S: AsRef<str>>(mut self, value: S) -> Self {
+        self.handler = self.handler.search(value);
+        self
+    }
+}
+
+impl<T> AsRef<Url> for TypedBlockingRequestHandler<T> {
+    fn as_ref(&self) -> &Url {
+        self.handler.as_ref()
+    }
+}
+
+impl<T> AsMut<Url> for TypedBlockingRequestHandler<T> {
+    fn as_mut(&mut self) -> &mut Url {
+        self.handler.as_mut()
+    }
+}
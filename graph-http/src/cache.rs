@@ -0,0 +1,408 @@
+use crate::internal::GraphService;
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use bytes::Bytes;
+use http::StatusCode;
+use reqwest::header::{
+    HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_NONE_MATCH, RANGE,
+};
+use reqwest::{Method, Request, Response, ResponseBuilderExt};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tower::{Service, ServiceExt};
+use url::Url;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A `GET` response that was returned with an `ETag` header.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub etag: HeaderValue,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl CachedResponse {
+    /// The cached response with the headers of a `304 Not Modified` response.
+    fn into_response(self, url: Url, not_modified: &HeaderMap) -> Result<Response, BoxError> {
+        let mut headers = self.headers;
+        for (name, value) in not_modified.iter() {
+            headers.insert(name.clone(), value.clone());
+        }
+
+        let mut builder = http::Response::builder().status(self.status).url(url);
+        if let Some(response_headers) = builder.headers_mut() {
+            *response_headers = headers;
+        }
+        Ok(Response::from(builder.body(self.body)?))
+    }
+}
+
+/// Storage of the responses cached by the client. Implement this trait to keep
+/// responses in a shared or persistent store. See [`InMemoryCache`] for the default
+/// in memory store.
+///
+/// Keys are made of the subject of the access token and the url of the request so
+/// responses are never shared between users.
+#[async_trait]
+pub trait ResponseCache: Send + Sync {
+    async fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    async fn insert(&self, key: String, response: CachedResponse);
+
+    async fn remove(&self, key: &str);
+}
+
+#[async_trait]
+impl<T: ResponseCache + ?Sized> ResponseCache for Arc<T> {
+    async fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.as_ref().get(key).await
+    }
+
+    async fn insert(&self, key: String, response: CachedResponse) {
+        self.as_ref().insert(key, response).await
+    }
+
+    async fn remove(&self, key: &str) {
+        self.as_ref().remove(key).await
+    }
+}
+
+#[derive(Debug, Default)]
+struct InMemoryCacheEntries {
+    responses: HashMap<String, CachedResponse>,
+    // Keys in the order they were inserted, oldest first.
+    order: VecDeque<String>,
+}
+
+/// A [`ResponseCache`] that keeps responses in memory. The oldest responses are removed
+/// once the cache holds `max_entries` responses.
+#[derive(Debug)]
+pub struct InMemoryCache {
+    max_entries: usize,
+    entries: Mutex<InMemoryCacheEntries>,
+}
+
+impl InMemoryCache {
+    /// The default maximum number of cached responses.
+    pub const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+    pub fn new() -> InMemoryCache {
+        InMemoryCache::with_max_entries(InMemoryCache::DEFAULT_MAX_ENTRIES)
+    }
+
+    pub fn with_max_entries(max_entries: usize) -> InMemoryCache {
+        InMemoryCache {
+            max_entries: max_entries.max(1),
+            entries: Default::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.responses.clear();
+        entries.order.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, InMemoryCacheEntries> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Default for InMemoryCache {
+    fn default() -> Self {
+        InMemoryCache::new()
+    }
+}
+
+#[async_trait]
+impl ResponseCache for InMemoryCache {
+    async fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.lock().responses.get(key).cloned()
+    }
+
+    async fn insert(&self, key: String, response: CachedResponse) {
+        let mut entries = self.lock();
+        if entries.responses.insert(key.clone(), response).is_some() {
+            entries.order.retain(|k| k != &key);
+        }
+        entries.order.push_back(key);
+        while entries.responses.len() > self.max_entries {
+            match entries.order.pop_front() {
+                Some(oldest) => {
+                    entries.responses.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+
+    async fn remove(&self, key: &str) {
+        let mut entries = self.lock();
+        if entries.responses.remove(key).is_some() {
+            entries.order.retain(|k| k != key);
+        }
+    }
+}
+
+/// The claims of the payload of a JWT.
+fn jwt_claims(token: &str) -> Option<serde_json::Value> {
+    let payload = token.split('.').nth(1)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice(&payload).ok()
+}
+
+/// The tenant and object id of the access token of the request. Tokens that are not
+/// JWTs, such as the tokens of personal accounts, are hashed instead.
+///
+/// Proof of Possession (PoP) tokens are signed for each request, so the access token
+/// in their `at` claim is used instead of the whole token.
+fn auth_subject(headers: &HeaderMap) -> String {
+    let authorization = match headers.get(AUTHORIZATION) {
+        Some(authorization) => authorization,
+        None => return String::from("anonymous"),
+    };
+    let mut token = match authorization
+        .to_str()
+        .ok()
+        .and_then(|value| value.split_whitespace().last())
+    {
+        Some(token) => token.to_string(),
+        None => return hash_subject(authorization.as_bytes()),
+    };
+
+    let mut claims = jwt_claims(&token);
+    if let Some(access_token) = claims
+        .as_ref()
+        .and_then(|claims| claims["at"].as_str())
+        .map(|access_token| access_token.to_string())
+    {
+        claims = jwt_claims(&access_token);
+        token = access_token;
+    }
+
+    if let Some(claims) = claims {
+        let subject = claims["oid"].as_str().or_else(|| claims["sub"].as_str());
+        if let Some(subject) = subject {
+            return format!("{}/{subject}", claims["tid"].as_str().unwrap_or_default());
+        }
+    }
+
+    hash_subject(token.as_bytes())
+}
+
+fn hash_subject(token: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    token.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn cache_key(request: &Request) -> String {
+    format!("{} {}", auth_subject(request.headers()), request.url())
+}
+
+/// Only json responses are cached so that downloads of file content are not kept
+/// in memory.
+fn is_cacheable(response: &Response) -> bool {
+    response.status() == StatusCode::OK
+        && response.headers().contains_key(ETAG)
+        && response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.starts_with("application/json"))
+            .unwrap_or_default()
+}
+
+/// Revalidates cached `GET` responses with `If-None-Match` and returns the cached
+/// response when the resource was not modified. Successful requests with other methods
+/// remove the cached response of their url.
+#[derive(Clone)]
+pub(crate) struct CacheService {
+    inner: GraphService,
+    cache: Arc<dyn ResponseCache>,
+}
+
+impl CacheService {
+    pub(crate) fn new(inner: GraphService, cache: Arc<dyn ResponseCache>) -> CacheService {
+        CacheService { inner, cache }
+    }
+}
+
+impl Service<Request> for CacheService {
+    type Response = Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let inner = self.inner.clone();
+        let cache = self.cache.clone();
+        Box::pin(async move {
+            let key = cache_key(&request);
+            if request.method() != Method::GET {
+                let response = inner.oneshot(request).await?;
+                if response.status().is_success() {
+                    cache.remove(&key).await;
+                }
+                return Ok(response);
+            }
+
+            // Conditional and range requests are sent as they are.
+            if request.headers().contains_key(IF_NONE_MATCH)
+                || request.headers().contains_key(RANGE)
+            {
+                return inner.oneshot(request).await;
+            }
+
+            let cached = cache.get(&key).await;
+            if let Some(cached) = cached.as_ref() {
+                request
+                    .headers_mut()
+                    .insert(IF_NONE_MATCH, cached.etag.clone());
+            }
+
+            let response = inner.oneshot(request).await?;
+            if response.status() == StatusCode::NOT_MODIFIED {
+                if let Some(cached) = cached {
+                    return cached.into_response(response.url().clone(), response.headers());
+                }
+            }
+            if !is_cacheable(&response) {
+                if cached.is_some() && response.status().is_client_error() {
+                    cache.remove(&key).await;
+                }
+                return Ok(response);
+            }
+
+            let status = response.status();
+            let version = response.version();
+            let url = response.url().clone();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?;
+            if let Some(etag) = headers.get(ETAG) {
+                cache
+                    .insert(
+                        key,
+                        CachedResponse {
+                            etag: etag.clone(),
+                            status,
+                            headers: headers.clone(),
+                            body: body.clone(),
+                        },
+                    )
+                    .await;
+            }
+
+            let mut builder = http::Response::builder()
+                .status(status)
+                .version(version)
+                .url(url);
+            if let Some(response_headers) = builder.headers_mut() {
+                *response_headers = headers;
+            }
+            Ok(Response::from(builder.body(body)?))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cached_response(etag: &'static str) -> CachedResponse {
+        CachedResponse {
+            etag: HeaderValue::from_static(etag),
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(b"{}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn in_memory_cache_removes_oldest_entries() {
+        let cache = InMemoryCache::with_max_entries(2);
+        cache.insert("a".into(), cached_response("1")).await;
+        cache.insert("b".into(), cached_response("2")).await;
+        cache.insert("a".into(), cached_response("3")).await;
+        cache.insert("c".into(), cached_response("4")).await;
+
+        assert_eq!(2, cache.len());
+        assert!(cache.get("b").await.is_none());
+        assert_eq!("3", cache.get("a").await.unwrap().etag);
+
+        cache.remove("a").await;
+        assert_eq!(1, cache.len());
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn auth_subject_from_access_token() {
+        let claims = URL_SAFE_NO_PAD.encode(r#"{"tid":"tenant","oid":"user","sub":"subject"}"#);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer header.{claims}.signature")).unwrap(),
+        );
+        assert_eq!("tenant/user", auth_subject(&headers));
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer token"));
+        let subject = auth_subject(&headers);
+        assert_eq!(16, subject.len());
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_static("Bearer other-token"),
+        );
+        assert_ne!(subject, auth_subject(&headers));
+        assert_eq!("anonymous", auth_subject(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn auth_subject_from_pop_token() {
+        let access_token = format!(
+            "header.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"tid":"tenant","oid":"user"}"#)
+        );
+        let pop_token = |ts: u64, at: &str| {
+            let claims = serde_json::json!({ "at": at, "ts": ts, "m": "GET" }).to_string();
+            format!("PoP header.{}.signature", URL_SAFE_NO_PAD.encode(claims))
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&pop_token(1, &access_token)).unwrap(),
+        );
+        assert_eq!("tenant/user", auth_subject(&headers));
+
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&pop_token(1, "opaque-token")).unwrap(),
+        );
+        let subject = auth_subject(&headers);
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&pop_token(2, "opaque-token")).unwrap(),
+        );
+        assert_eq!(subject, auth_subject(&headers));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::internal::GraphService;
+use graph_core::identity::{ClaimsChallenge, ClientApplication};
+use graph_error::{GraphFailure, GraphResult};
+use http::header::AUTHORIZATION;
+use http::{HeaderValue, Method};
+use tower::{Service, ServiceExt};
+use url::Url;
+
+/// Sends the request using the service. If the resource responds with a claims challenge,
+/// such as when Continuous Access Evaluation (CAE) revokes a token, a new access token is
+/// requested with the claims from the challenge and the request is retried once.
+///
+/// `access_token` is replaced with the new access token when the request is retried so
+/// that later requests, such as next links, use it.
+pub(crate) async fn send_with_claims_challenge(
+    client_application: &mut Box<dyn ClientApplication>,
+    service: &mut GraphService,
+    request: reqwest::Request,
+    access_token: &mut String,
+) -> GraphResult<reqwest::Response> {
+    let retry_request = request.try_clone();
+    let response = service
+        .ready()
+        .await
+        .map_err(GraphFailure::from)?
+        .call(request)
+        .await
+        .map_err(GraphFailure::from)?;
+
+    let mut retry_request = match (
+        ClaimsChallenge::from_response_parts(response.status(), response.headers()),
+        retry_request,
+    ) {
+        (Some(claims_challenge), Some(retry_request)) => {
+            client_application.with_claims_challenge(claims_challenge.claims);
+            retry_request
+        }
+        _ => return Ok(response),
+    };
+
+    *access_token = client_application.get_token_silent_async().await?;
+    let authorization = authorization_header_value(
+        client_application.as_ref(),
+        access_token.as_str(),
+        retry_request.method(),
+        retry_request.url(),
+    )?;
+    retry_request
+        .headers_mut()
+        .insert(AUTHORIZATION, authorization);
+
+    service
+        .ready()
+        .await
+        .map_err(GraphFailure::from)?
+        .call(retry_request)
+        .await
+        .map_err(GraphFailure::from)
+}
+
+/// Blocking version of [`send_with_claims_challenge`].
+pub(crate) fn execute_with_claims_challenge(
+    client_application: &mut Box<dyn ClientApplication>,
+    client: &reqwest::blocking::Client,
+    request: reqwest::blocking::Request,
+    access_token: &mut String,
+) -> GraphResult<reqwest::blocking::Response> {
+    let retry_request = request.try_clone();
+    let response = client.execute(request)?;
+
+    let mut retry_request = match (
+        ClaimsChallenge::from_response_parts(response.status(), response.headers()),
+        retry_request,
+    ) {
+        (Some(claims_challenge), Some(retry_request)) => {
+            client_application.with_claims_challenge(claims_challenge.claims);
+            retry_request
+        }
+        _ => return Ok(response),
+    };
+
+    *access_token = client_application.get_token_silent()?;
+    let authorization = authorization_header_value(
+        client_application.as_ref(),
+        access_token.as_str(),
+        retry_request.method(),
+        retry_request.url(),
+    )?;
+    retry_request
+        .headers_mut()
+        .insert(AUTHORIZATION, authorization);
+
+    client.execute(retry_request).map_err(GraphFailure::from)
+}
+
+fn authorization_header_value(
+    client_application: &dyn ClientApplication,
+    access_token: &str,
+    method: &Method,
+    url: &Url,
+) -> GraphResult<HeaderValue> {
+    let authorization =
+        client_application
+            .auth_scheme()
+            .authorization_header(access_token, method, url)?;
+    let mut header_value =
+        HeaderValue::from_str(authorization.as_str()).map_err(http::Error::from)?;
+    header_value.set_sensitive(true);
+    Ok(header_value)
+}
@@ -1,29 +1,59 @@
 use crate::blocking::BlockingClient;
-use graph_core::identity::{ClientApplication, ForceTokenRefresh};
+use crate::cache::{CacheService, ResponseCache};
+use crate::rate_limit::{RateLimiter, RateLimiterService};
+use crate::telemetry::{count_attempts, RequestContext, Telemetry, TelemetryService};
+use graph_core::identity::{ClientApplication, CloudEnvironment, ForceTokenRefresh};
+use graph_error::ConfigurationError;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, USER_AGENT};
 use reqwest::redirect::Policy;
 use reqwest::tls::Version;
-use reqwest::Proxy;
-use reqwest::{Request, Response};
+use reqwest::{Certificate, Identity, Proxy};
+use reqwest::{Request, Response, ResponseBuilderExt};
 use std::env::VarError;
 use std::ffi::OsStr;
 use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
-use tower::limit::ConcurrencyLimitLayer;
+use tokio::sync::Semaphore;
+use tower::limit::{ConcurrencyLimit, ConcurrencyLimitLayer};
 use tower::retry::RetryLayer;
 use tower::util::BoxCloneService;
-use tower::ServiceExt;
+use tower::{Service, ServiceExt};
+use url::Url;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 fn user_agent_header_from_env() -> Option<HeaderValue> {
     let header = std::option_env!("GRAPH_CLIENT_USER_AGENT")?;
     HeaderValue::from_str(header).ok()
 }
 
+/// The tower service that sends the requests of a [`Client`].
+pub type GraphService =
+    BoxCloneService<Request, Response, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Wraps the service that sends requests. See [`GraphClientConfiguration::wrap_service`].
+pub type ServiceWrapper = Arc<dyn Fn(GraphService) -> GraphService + Send + Sync>;
+
+/// A service that sends http requests over the network in place of the default
+/// `reqwest::Client`. See [`GraphClientConfiguration::transport`].
+pub type HttpTransport =
+    BoxCloneService<http::Request<reqwest::Body>, http::Response<reqwest::Body>, BoxError>;
+
 #[derive(Default, Clone)]
 struct ServiceLayersConfiguration {
     concurrency_limit: Option<usize>,
+    // The semaphore is shared by every client built from clones of the configuration.
+    shared_concurrency_limit: Option<Arc<Semaphore>>,
     retry: Option<usize>,
     wait_for_retry_after_headers: Option<()>,
+    service_wrappers: Vec<ServiceWrapper>,
+    // BoxCloneService is not Sync so the transport is cloned out of a Mutex
+    // each time a service is built.
+    transport: Option<Arc<Mutex<HttpTransport>>>,
+    telemetry: Option<Telemetry>,
+    rate_limiter: Option<RateLimiter>,
+    response_cache: Option<Arc<dyn ResponseCache>>,
 }
 
 #[derive(Clone)]
@@ -40,6 +70,12 @@ struct ClientConfiguration {
     min_tls_version: Version,
     service_layers_configuration: ServiceLayersConfiguration,
     proxy: Option<Proxy>,
+    strict_advanced_queries: bool,
+    shared_http_client: Option<Arc<OnceLock<reqwest::Client>>>,
+    cloud_environment: Option<CloudEnvironment>,
+    trusted_graph_hosts: Vec<Url>,
+    root_certificates: Vec<Certificate>,
+    identity: Option<Identity>,
 }
 
 impl ClientConfiguration {
@@ -62,6 +98,12 @@ impl ClientConfiguration {
             min_tls_version: Version::TLS_1_2,
             service_layers_configuration: ServiceLayersConfiguration::default(),
             proxy: None,
+            strict_advanced_queries: false,
+            shared_http_client: None,
+            cloud_environment: None,
+            trusted_graph_hosts: Vec::new(),
+            root_certificates: Vec::new(),
+            identity: None,
         }
     }
 }
@@ -74,6 +116,9 @@ impl PartialEq for ClientConfiguration {
             && self.connection_verbose == other.connection_verbose
             && self.https_only == other.https_only
             && self.min_tls_version == other.min_tls_version
+            && self.strict_advanced_queries == other.strict_advanced_queries
+            && self.cloud_environment == other.cloud_environment
+            && self.trusted_graph_hosts == other.trusted_graph_hosts
     }
 }
 
@@ -87,6 +132,12 @@ impl Debug for ClientConfiguration {
             .field("https_only", &self.https_only)
             .field("min_tls_version", &self.min_tls_version)
             .field("proxy", &self.proxy)
+            .field("strict_advanced_queries", &self.strict_advanced_queries)
+            .field("shared_http_client", &self.shared_http_client.is_some())
+            .field("cloud_environment", &self.cloud_environment)
+            .field("trusted_graph_hosts", &self.trusted_graph_hosts)
+            .field("root_certificates", &self.root_certificates.len())
+            .field("identity", &self.identity.is_some())
             .finish()
     }
 }
@@ -170,11 +221,57 @@ impl GraphClientConfiguration {
 
     /// TLS 1.2 required to support all features in Microsoft Graph
     /// See [Reliability and Support](https://learn.microsoft.com/en-us/graph/best-practices-concept#reliability-and-support)
+    ///
+    /// [`GraphClientConfiguration::try_build`] returns a [`ConfigurationError::TlsVersion`]
+    /// error for a version lower than TLS 1.2.
     pub fn min_tls_version(mut self, version: Version) -> GraphClientConfiguration {
         self.config.min_tls_version = version;
         self
     }
 
+    /// Add a custom root certificate that is trusted by the http client, such as the
+    /// certificate authority of an API gateway or reverse proxy in front of Microsoft Graph.
+    /// The certificate is trusted in addition to the system root certificates.
+    ///
+    /// Used by both the async and blocking clients and for token requests of credentials
+    /// built with this configuration.
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> GraphClientConfiguration {
+        self.config.root_certificates.push(certificate);
+        self
+    }
+
+    /// Set the client certificate [`Identity`] that is sent when the server requests
+    /// client authentication (mutual TLS).
+    ///
+    /// Used by both the async and blocking clients and for token requests of credentials
+    /// built with this configuration.
+    pub fn identity(mut self, identity: Identity) -> GraphClientConfiguration {
+        self.config.identity = Some(identity);
+        self
+    }
+
+    /// Trust a host that is not a Microsoft Graph host as the endpoint of the client, such
+    /// as an API gateway or reverse proxy that forwards requests to Microsoft Graph.
+    /// Only the scheme, host, and port of the url are used and the scheme must be https.
+    ///
+    /// By default only the Microsoft Graph hosts can be set as the endpoint. Access tokens
+    /// are sent to a trusted host so only trust hosts that you control. The endpoint is
+    /// not changed by this method, use `GraphClient::use_endpoint` to send requests to
+    /// the host.
+    ///
+    /// Building a client with a host that does not use the https scheme returns a
+    /// [`ConfigurationError::InsecureEndpoint`] error.
+    pub fn trust_graph_host(mut self, host: Url) -> GraphClientConfiguration {
+        let mut host = host;
+        host.set_path("");
+        host.set_query(None);
+        host.set_fragment(None);
+        if !self.config.trusted_graph_hosts.contains(&host) {
+            self.config.trusted_graph_hosts.push(host);
+        }
+        self
+    }
+
     /// Set [`Proxy`] for all network operations.
     ///
     /// Default is no proxy.
@@ -183,6 +280,25 @@ impl GraphClientConfiguration {
         self
     }
 
+    /// Advanced queries on directory objects such as users and groups, for instance
+    /// queries using `$search`, `$count=true`, or a `$filter` with `endswith`, `ne`, or `not`,
+    /// require the `ConsistencyLevel: eventual` header and the `$count=true` query parameter.
+    ///
+    /// By default the header and query parameter are added to these requests when they are
+    /// missing. In strict mode the request is not sent and a
+    /// [`GraphFailure::PreFlightError`](graph_error::GraphFailure::PreFlightError) is returned
+    /// instead.
+    ///
+    /// Default is `false`.
+    pub fn strict_advanced_queries(mut self, strict: bool) -> GraphClientConfiguration {
+        self.config.strict_advanced_queries = strict;
+        self
+    }
+
+    pub(crate) fn is_strict_advanced_queries(&self) -> bool {
+        self.config.strict_advanced_queries
+    }
+
     #[cfg(feature = "test-util")]
     pub fn https_only(mut self, https_only: bool) -> GraphClientConfiguration {
         self.config.https_only = https_only;
@@ -224,6 +340,117 @@ impl GraphClientConfiguration {
         self
     }
 
+    /// Wrap the service that sends requests over the network. The function is given the
+    /// service that sends the request and returns the service that is used instead, which
+    /// can be used to inspect, record, or replace requests and responses. Retries and the
+    /// concurrency limit are applied on top of the wrapped service so every attempt goes
+    /// through it.
+    ///
+    /// Multiple wrappers are applied in the order they are added. Only requests sent by the
+    /// async client go through the service.
+    pub fn wrap_service<F>(mut self, wrapper: F) -> GraphClientConfiguration
+    where
+        F: Fn(GraphService) -> GraphService + Send + Sync + 'static,
+    {
+        self.config
+            .service_layers_configuration
+            .service_wrappers
+            .push(Arc::new(wrapper));
+        self
+    }
+
+    /// Send requests with a tower service instead of the `reqwest::Client` that is
+    /// built from this configuration. The transport can be a hyper client with a custom
+    /// connector, an mTLS stack, a test double, or a `reqwest::Client` with extra tower
+    /// layers.
+    ///
+    /// The default headers of the configuration are added to each request before it is
+    /// given to the transport. Settings that only apply to the `reqwest::Client` such as
+    /// timeouts, the proxy, and tls settings are not used. Clones of the transport are
+    /// shared by every client built from this configuration, including the credentials
+    /// that are given the configuration with `with_config`, so token requests use the
+    /// same transport.
+    ///
+    /// Service wrappers, retries, and the concurrency limit are applied on top of the
+    /// transport. The blocking client and upload session requests do not use the transport.
+    ///
+    /// ```rust,ignore
+    /// use graph_rs_sdk::GraphClientConfiguration;
+    ///
+    /// let transport = tower::service_fn(|request: http::Request<reqwest::Body>| async move {
+    ///     Ok::<_, std::convert::Infallible>(http::Response::new(String::from("{}")))
+    /// });
+    /// let config = GraphClientConfiguration::new()
+    ///     .access_token("ACCESS_TOKEN")
+    ///     .transport(transport);
+    /// ```
+    pub fn transport<S, B>(mut self, transport: S) -> GraphClientConfiguration
+    where
+        S: Service<http::Request<reqwest::Body>, Response = http::Response<B>>
+            + Clone
+            + Send
+            + 'static,
+        S::Future: Send + 'static,
+        S::Error: Into<BoxError>,
+        B: http_body::Body + Send + Sync + 'static,
+        B::Data: Into<bytes::Bytes>,
+        B::Error: Into<BoxError>,
+    {
+        let transport = transport
+            .map_response(|response: http::Response<B>| response.map(reqwest::Body::wrap))
+            .map_err(Into::into)
+            .boxed_clone();
+        self.config.service_layers_configuration.transport = Some(Arc::new(Mutex::new(transport)));
+        self
+    }
+
+    /// Open a `graph.request` tracing span for each request sent by the async client.
+    ///
+    /// The span records the method, the path template of the request with ids left as
+    /// templates such as `/users/{{RID}}/messages/{{id}}`, the [`ResourceIdentity`],
+    /// the status code, the `request-id` and `client-request-id` headers, the number of
+    /// retries, the time spent waiting on `Retry-After` headers, and the latency of the
+    /// request including retries. Field names follow the OpenTelemetry semantic
+    /// conventions where one exists so spans can be exported with `tracing-opentelemetry`.
+    ///
+    /// Default is no telemetry.
+    ///
+    /// [`ResourceIdentity`]: graph_core::resource::ResourceIdentity
+    pub fn telemetry(mut self, enable: bool) -> GraphClientConfiguration {
+        let telemetry = &mut self.config.service_layers_configuration.telemetry;
+        match enable {
+            true => {
+                telemetry.get_or_insert_with(Telemetry::default);
+            }
+            false => *telemetry = None,
+        }
+        self
+    }
+
+    /// Record counters and histograms of each request sent by the async client through
+    /// the [`metrics`](https://docs.rs/metrics) facade. Enables
+    /// [`telemetry`](GraphClientConfiguration::telemetry) spans as well.
+    ///
+    /// The metrics are `graph_requests_total`, `graph_request_duration_seconds`,
+    /// `graph_request_retries_total`, `graph_throttled_responses_total` and
+    /// `graph_throttle_wait_seconds`, labeled by method, path template, resource and status.
+    /// They are given to the recorder installed with the `metrics` crate.
+    ///
+    /// Default is no metrics.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, enable: bool) -> GraphClientConfiguration {
+        let telemetry = &mut self.config.service_layers_configuration.telemetry;
+        match enable {
+            true => telemetry.get_or_insert_with(Telemetry::default).metrics = true,
+            false => {
+                if let Some(telemetry) = telemetry {
+                    telemetry.metrics = false;
+                }
+            }
+        }
+        self
+    }
+
     /// Enable a concurrency limit on the client.
     ///
     /// Every request through this client will be subject to a concurrency limit.
@@ -238,34 +465,239 @@ impl GraphClientConfiguration {
         self
     }
 
+    /// Enable a concurrency limit that is shared by every client built from this
+    /// configuration or its clones, including the credentials that are given the
+    /// configuration with `with_config`. Calling this method again starts a new limit
+    /// that is not shared with clones made before the call.
+    ///
+    /// Applied in addition to [`GraphClientConfiguration::concurrency_limit`].
+    ///
+    /// Default is no shared concurrency limit.
+    pub fn shared_concurrency_limit(
+        mut self,
+        concurrency_limit: Option<usize>,
+    ) -> GraphClientConfiguration {
+        self.config
+            .service_layers_configuration
+            .shared_concurrency_limit =
+            concurrency_limit.map(|limit| Arc::new(Semaphore::new(limit)));
+        self
+    }
+
+    /// Use the Graph endpoint and allowed hosts of a national cloud [`CloudEnvironment`].
+    ///
+    /// Building a client fails if the client application requests tokens from the authority
+    /// of a different cloud. Give the same environment to the credential with
+    /// `with_cloud_environment`. When the environment is not set, the environment of the
+    /// credential is used.
+    ///
+    /// Default is no environment, which uses the Microsoft Graph global service.
+    pub fn cloud_environment(
+        mut self,
+        cloud_environment: CloudEnvironment,
+    ) -> GraphClientConfiguration {
+        self.config.cloud_environment = Some(cloud_environment);
+        self
+    }
+
+    /// Returns the cloud environment of the client, which is the environment of this
+    /// configuration or, when it is not set, the environment of the client application.
+    /// Returns an error if the client application requests tokens from the authority of
+    /// another cloud.
+    pub(crate) fn resolve_cloud_environment(
+        &self,
+    ) -> Result<Option<CloudEnvironment>, ConfigurationError> {
+        let client_application = self.config.client_application.as_ref();
+        let credential_environment = client_application
+            .and_then(|client_application| client_application.cloud_environment());
+        let Some(cloud_environment) = self
+            .config
+            .cloud_environment
+            .clone()
+            .or_else(|| credential_environment.clone())
+        else {
+            return Ok(None);
+        };
+
+        if let Some(authority_host) =
+            client_application.and_then(|client_application| client_application.authority_host())
+        {
+            if !cloud_environment.is_authority_host(&authority_host) {
+                return Err(ConfigurationError::CloudEnvironmentMismatch {
+                    expected: cloud_environment.authority_host().to_string(),
+                    found: authority_host.to_string(),
+                });
+            }
+        }
+
+        if let Some(credential_environment) = credential_environment {
+            if credential_environment.graph_host() != cloud_environment.graph_host() {
+                return Err(ConfigurationError::CloudEnvironmentMismatch {
+                    expected: cloud_environment.graph_host().to_string(),
+                    found: credential_environment.graph_host().to_string(),
+                });
+            }
+        }
+
+        Ok(Some(cloud_environment))
+    }
+
+    /// Share one connection pool between the clients built from this configuration and
+    /// its clones. By default each client, including the http client that a credential
+    /// uses for token requests, opens its own connections.
+    ///
+    /// The `reqwest::Client` is created when the first client is built, so settings such
+    /// as timeouts, the proxy, and tls settings must be set before calling this method.
+    /// Settings changed on clones after this method is called are not used for the
+    /// shared http client. Only the async client shares connections.
+    pub fn share_connection_pool(mut self) -> GraphClientConfiguration {
+        self.config.shared_http_client = Some(Arc::new(OnceLock::new()));
+        self
+    }
+
+    /// Hold back requests with a [`RateLimiter`] to stay under the throttling limits of
+    /// each Microsoft Graph service. The limiter waits before each attempt of a request,
+    /// including retries, and learns from throttled responses and rate limit headers.
+    ///
+    /// Clones of the limiter share their state, so the same limiter can be given to
+    /// several clients. Only requests sent by the async client are rate limited.
+    ///
+    /// Default is no rate limiter.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> GraphClientConfiguration {
+        self.config.service_layers_configuration.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Cache `GET` responses that have an `ETag` header in a [`ResponseCache`] such as
+    /// [`InMemoryCache`](crate::api_impl::InMemoryCache). Cached responses are revalidated
+    /// with an `If-None-Match` header and a `304 Not Modified` response returns the cached
+    /// response. Responses are cached for each url and the user or application of the
+    /// access token, and successful requests with other methods remove the cached
+    /// response of their url.
+    ///
+    /// Requests that set their own `If-None-Match` or `Range` header are not cached. Only
+    /// json responses of requests sent by the async client are cached.
+    ///
+    /// Default is no cache.
+    pub fn response_cache<C: ResponseCache + 'static>(
+        mut self,
+        cache: C,
+    ) -> GraphClientConfiguration {
+        self.config.service_layers_configuration.response_cache = Some(Arc::new(cache));
+        self
+    }
+
     pub(crate) fn build_tower_service(
         &self,
         client: &reqwest::Client,
-    ) -> BoxCloneService<Request, Response, Box<dyn std::error::Error + Send + Sync>> {
-        tower::ServiceBuilder::new()
+        context: RequestContext,
+    ) -> GraphService {
+        let telemetry = self.config.service_layers_configuration.telemetry.clone();
+        let transport: GraphService = match &self.config.service_layers_configuration.transport {
+            Some(transport) => {
+                let transport = transport
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .clone();
+                http_transport_service(transport, self.config.headers.clone())
+            }
+            None => tower::ServiceBuilder::new()
+                .map_err(|err: reqwest::Error| Box::new(err) as BoxError)
+                .service(client.clone())
+                .boxed_clone(),
+        };
+        let transport = self
+            .config
+            .service_layers_configuration
+            .service_wrappers
+            .iter()
+            .fold(transport, |service, wrapper| wrapper(service));
+        let transport = match telemetry {
+            Some(_) => count_attempts(transport),
+            None => transport,
+        };
+
+        let service = match &self
+            .config
+            .service_layers_configuration
+            .shared_concurrency_limit
+        {
+            Some(semaphore) => {
+                ConcurrencyLimit::with_semaphore(transport, semaphore.clone()).boxed_clone()
+            }
+            None => transport,
+        };
+        let service = tower::ServiceBuilder::new()
             .option_layer(
                 self.config
                     .service_layers_configuration
-                    .retry
-                    .map(|num| RetryLayer::new(crate::tower_services::Attempts(num))),
+                    .concurrency_limit
+                    .map(ConcurrencyLimitLayer::new),
             )
+            .service(service)
+            .boxed_clone();
+        let service = match &self.config.service_layers_configuration.rate_limiter {
+            Some(rate_limiter) => {
+                RateLimiterService::new(service, rate_limiter.clone(), context.clone())
+                    .boxed_clone()
+            }
+            None => service,
+        };
+
+        let service = tower::ServiceBuilder::new()
             .option_layer(
                 self.config
                     .service_layers_configuration
-                    .wait_for_retry_after_headers
-                    .map(|_| RetryLayer::new(crate::tower_services::WaitFor())),
+                    .retry
+                    .map(|num| RetryLayer::new(crate::tower_services::Attempts(num))),
             )
             .option_layer(
                 self.config
                     .service_layers_configuration
-                    .concurrency_limit
-                    .map(ConcurrencyLimitLayer::new),
+                    .wait_for_retry_after_headers
+                    .map(|_| RetryLayer::new(crate::tower_services::WaitFor())),
             )
-            .service(client.clone())
-            .boxed_clone()
+            .service(service)
+            .boxed_clone();
+        let service = match &self.config.service_layers_configuration.response_cache {
+            Some(cache) => CacheService::new(service, cache.clone()).boxed_clone(),
+            None => service,
+        };
+
+        match telemetry {
+            Some(telemetry) => TelemetryService::new(service, telemetry, context).boxed_clone(),
+            None => service,
+        }
     }
 
-    fn build_http_client(&self) -> reqwest::Client {
+    fn build_http_client(&self) -> Result<reqwest::Client, ConfigurationError> {
+        match &self.config.shared_http_client {
+            Some(shared_http_client) => {
+                if let Some(client) = shared_http_client.get() {
+                    return Ok(client.clone());
+                }
+                let client = self.new_http_client()?;
+                Ok(shared_http_client.get_or_init(|| client).clone())
+            }
+            None => self.new_http_client(),
+        }
+    }
+
+    fn validate_tls_version(&self) -> Result<(), ConfigurationError> {
+        if self.config.min_tls_version < Version::TLS_1_2 {
+            let version = if self.config.min_tls_version == Version::TLS_1_0 {
+                "TLS 1.0".to_owned()
+            } else if self.config.min_tls_version == Version::TLS_1_1 {
+                "TLS 1.1".to_owned()
+            } else {
+                format!("{:?}", self.config.min_tls_version)
+            };
+            return Err(ConfigurationError::TlsVersion(version));
+        }
+        Ok(())
+    }
+
+    fn new_http_client(&self) -> Result<reqwest::Client, ConfigurationError> {
         let headers = self.config.headers.clone();
         let mut builder = reqwest::ClientBuilder::new()
             .referer(self.config.referer)
@@ -287,10 +719,18 @@ impl GraphClientConfiguration {
             builder = builder.proxy(proxy.clone());
         }
 
-        builder.build().unwrap()
+        for certificate in self.config.root_certificates.iter() {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+
+        if let Some(identity) = &self.config.identity {
+            builder = builder.identity(identity.clone());
+        }
+
+        Ok(builder.build()?)
     }
 
-    fn build_blocking_http_client(&self) -> reqwest::blocking::Client {
+    fn build_blocking_http_client(&self) -> Result<reqwest::blocking::Client, ConfigurationError> {
         let headers = self.config.headers.clone();
         let mut builder = reqwest::blocking::ClientBuilder::new()
             .referer(self.config.referer)
@@ -312,68 +752,172 @@ impl GraphClientConfiguration {
             builder = builder.proxy(proxy.clone());
         }
 
-        builder.build().unwrap()
+        for certificate in self.config.root_certificates.iter() {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+
+        if let Some(identity) = &self.config.identity {
+            builder = builder.identity(identity.clone());
+        }
+
+        Ok(builder.build()?)
     }
 
-    pub(crate) fn build(self) -> Client {
-        let config = self.clone();
+    /// Build a [`Client`], returning a [`ConfigurationError`] instead of panicking when the
+    /// configuration is invalid. Checks that an access token or client application is set,
+    /// that the credential and the [cloud environment](GraphClientConfiguration::cloud_environment)
+    /// match, that the minimum TLS version is at least TLS 1.2, that the Graph host of the
+    /// cloud environment uses https, and that the http client can be built with the TLS and
+    /// proxy settings.
+    ///
+    /// The minimum TLS version and the https scheme of the cloud environment are only
+    /// checked here. Clients built with `From<GraphClientConfiguration>` accept them as before.
+    pub fn try_build(self) -> Result<Client, ConfigurationError> {
+        self.validate_try_build()?;
+        self.build_client()
+    }
+
+    /// Build a [`BlockingClient`], returning a [`ConfigurationError`] instead of panicking
+    /// when the configuration is invalid. Applies the same checks as
+    /// [`GraphClientConfiguration::try_build`].
+    pub fn try_build_blocking(self) -> Result<BlockingClient, ConfigurationError> {
+        self.validate_try_build()?;
+        self.build_blocking_client()
+    }
+
+    /// The checks of the `try_build` methods that clients built with
+    /// `From<GraphClientConfiguration>` do not apply.
+    fn validate_try_build(&self) -> Result<(), ConfigurationError> {
+        if self.config.client_application.is_none() {
+            return Err(ConfigurationError::MissingCredentials);
+        }
+        self.validate_tls_version()?;
+        if let Some(graph_host) = self
+            .resolve_cloud_environment()?
+            .as_ref()
+            .map(CloudEnvironment::graph_host)
+        {
+            if graph_host.scheme() != "https" {
+                return Err(ConfigurationError::InsecureEndpoint(graph_host.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build a [`BlockingClient`] without the checks of
+    /// [`GraphClientConfiguration::try_build_blocking`], returning an error only when the
+    /// http client cannot be built with the TLS, identity, and proxy settings.
+    pub(crate) fn build_blocking_client(self) -> Result<BlockingClient, ConfigurationError> {
         let headers = self.config.headers.clone();
-        let client = self.build_http_client();
+        let strict_advanced_queries = self.config.strict_advanced_queries;
+        let client = self.build_blocking_http_client()?;
 
         if let Some(client_application) = self.config.client_application {
-            Client {
+            Ok(BlockingClient {
                 client_application,
                 inner: client,
                 headers,
-                builder: config,
-            }
+                strict_advanced_queries,
+            })
         } else {
-            Client {
+            Ok(BlockingClient {
                 client_application: Box::<String>::default(),
                 inner: client,
                 headers,
-                builder: config,
-            }
+                strict_advanced_queries,
+            })
         }
     }
 
-    pub(crate) fn build_blocking(self) -> BlockingClient {
+    /// Build a [`MinimalAsyncClient`], returning a [`ConfigurationError`] instead of
+    /// panicking when the http client cannot be built from the configuration.
+    pub fn try_build_minimal_async_client(self) -> Result<MinimalAsyncClient, ConfigurationError> {
+        let config = self.clone();
+        let client = self.build_http_client()?;
+        let service = self.build_tower_service(&client, RequestContext::default());
+        Ok(MinimalAsyncClient {
+            inner: client,
+            builder: config,
+            service,
+        })
+    }
+
+    /// Build a [`MinimalBlockingClient`], returning a [`ConfigurationError`] instead of
+    /// panicking when the http client cannot be built from the configuration.
+    pub fn try_build_minimal_blocking_client(
+        self,
+    ) -> Result<MinimalBlockingClient, ConfigurationError> {
+        let config = self.clone();
+        let client = self.build_blocking_client()?;
+        Ok(MinimalBlockingClient {
+            inner: client.inner,
+            builder: config,
+        })
+    }
+
+    /// Build a [`Client`] with the checks of `From<GraphClientConfiguration>`, returning a
+    /// [`ConfigurationError`] instead of panicking. Checks that the credential and the
+    /// [cloud environment](GraphClientConfiguration::cloud_environment) match, that trusted
+    /// Graph hosts use https, and that the http client can be built.
+    ///
+    /// Unlike [`GraphClientConfiguration::try_build`], credentials are not required and the
+    /// minimum TLS version and the scheme of the cloud environment are not checked.
+    pub fn try_build_lenient(self) -> Result<Client, ConfigurationError> {
+        self.build_client()
+    }
+
+    pub(crate) fn build(self) -> Client {
+        self.try_build_lenient()
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn build_client(self) -> Result<Client, ConfigurationError> {
+        let cloud_environment = self.resolve_cloud_environment()?;
+        if let Some(host) = self
+            .config
+            .trusted_graph_hosts
+            .iter()
+            .find(|host| host.scheme() != "https")
+        {
+            return Err(ConfigurationError::InsecureEndpoint(host.to_string()));
+        }
+
+        let config = self.clone();
         let headers = self.config.headers.clone();
-        let client = self.build_blocking_http_client();
+        let client = self.build_http_client()?;
 
         if let Some(client_application) = self.config.client_application {
-            BlockingClient {
+            Ok(Client {
                 client_application,
                 inner: client,
                 headers,
-            }
+                builder: config,
+                cloud_environment,
+            })
         } else {
-            BlockingClient {
+            Ok(Client {
                 client_application: Box::<String>::default(),
                 inner: client,
                 headers,
-            }
+                builder: config,
+                cloud_environment,
+            })
         }
     }
 
+    pub(crate) fn build_blocking(self) -> BlockingClient {
+        self.build_blocking_client()
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
     pub(crate) fn build_minimal_async_client(self) -> MinimalAsyncClient {
-        let config = self.clone();
-        let client = self.build_http_client();
-        let service = self.build_tower_service(&client);
-        MinimalAsyncClient {
-            inner: client,
-            builder: config,
-            service,
-        }
+        self.try_build_minimal_async_client()
+            .unwrap_or_else(|err| panic!("{err}"))
     }
 
     pub(crate) fn build_minimal_blocking_client(self) -> MinimalBlockingClient {
-        let config = self.clone();
-        let client = self.build_blocking();
-        MinimalBlockingClient {
-            inner: client.inner,
-            builder: config,
-        }
+        self.try_build_minimal_blocking_client()
+            .unwrap_or_else(|err| panic!("{err}"))
     }
 }
 
@@ -383,12 +927,45 @@ impl Default for GraphClientConfiguration {
     }
 }
 
+/// Adapts an [`HttpTransport`] to the reqwest request and response types used by the
+/// rest of the tower stack.
+fn http_transport_service(transport: HttpTransport, headers: HeaderMap) -> GraphService {
+    tower::service_fn(move |mut request: Request| {
+        let transport = transport.clone();
+        for (name, value) in headers.iter() {
+            if !request.headers().contains_key(name) {
+                request.headers_mut().insert(name, value.clone());
+            }
+        }
+
+        async move {
+            let url = request.url().clone();
+            let request = http::Request::try_from(request)?;
+            let (parts, body) = transport.oneshot(request).await?.into_parts();
+
+            let mut builder = http::Response::builder()
+                .status(parts.status)
+                .version(parts.version);
+            if let Some(extensions) = builder.extensions_mut() {
+                extensions.extend(parts.extensions);
+            }
+            if let Some(response_headers) = builder.headers_mut() {
+                *response_headers = parts.headers;
+            }
+            let response = builder.url(url).body(body)?;
+            Ok::<Response, BoxError>(Response::from(response))
+        }
+    })
+    .boxed_clone()
+}
+
 #[derive(Clone)]
 pub struct Client {
     pub(crate) client_application: Box<dyn ClientApplication>,
     pub(crate) inner: reqwest::Client,
     pub(crate) headers: HeaderMap,
     pub(crate) builder: GraphClientConfiguration,
+    pub(crate) cloud_environment: Option<CloudEnvironment>,
 }
 
 impl Client {
@@ -424,6 +1001,17 @@ impl Client {
         self.client_application
             .with_force_token_refresh(force_token_refresh);
     }
+
+    /// The cloud environment of the client. See [`GraphClientConfiguration::cloud_environment`].
+    pub fn cloud_environment(&self) -> Option<&CloudEnvironment> {
+        self.cloud_environment.as_ref()
+    }
+
+    /// The hosts other than the Microsoft Graph hosts that are trusted as the endpoint
+    /// of the client. See [`GraphClientConfiguration::trust_graph_host`].
+    pub fn trusted_graph_hosts(&self) -> &[Url] {
+        &self.builder.config.trusted_graph_hosts
+    }
 }
 
 impl Default for Client {
@@ -452,13 +1040,21 @@ impl From<GraphClientConfiguration> for Client {
 pub struct MinimalAsyncClient {
     pub inner: reqwest::Client,
     pub builder: GraphClientConfiguration,
-    pub service: BoxCloneService<Request, Response, Box<dyn std::error::Error + Send + Sync>>,
+    pub service: GraphService,
 }
 
 /*
 let service = inner.builder.build_tower_service(&inner.inner);
  */
 
+impl MinimalAsyncClient {
+    /// Send the request through the tower service of the client so that it uses the
+    /// transport and service layers of the configuration.
+    pub async fn execute(&mut self, request: Request) -> Result<Response, BoxError> {
+        self.service.ready().await?.call(request).await
+    }
+}
+
 impl From<GraphClientConfiguration> for MinimalAsyncClient {
     fn from(value: GraphClientConfiguration) -> Self {
         value.build_minimal_async_client()
@@ -513,4 +1109,123 @@ mod test {
         let user_agent_header = client.builder.config.headers.get(USER_AGENT).unwrap();
         assert_eq!("user_agent", user_agent_header.to_str().unwrap());
     }
+
+    #[test]
+    fn shared_concurrency_limit_is_shared_by_clones() {
+        let config = GraphClientConfiguration::new().shared_concurrency_limit(Some(2));
+        let clone = config.clone();
+        let semaphore = config
+            .config
+            .service_layers_configuration
+            .shared_concurrency_limit
+            .unwrap();
+        assert!(Arc::ptr_eq(
+            &semaphore,
+            clone
+                .config
+                .service_layers_configuration
+                .shared_concurrency_limit
+                .as_ref()
+                .unwrap()
+        ));
+
+        let clone = clone.shared_concurrency_limit(Some(2));
+        assert!(!Arc::ptr_eq(
+            &semaphore,
+            clone
+                .config
+                .service_layers_configuration
+                .shared_concurrency_limit
+                .as_ref()
+                .unwrap()
+        ));
+    }
+
+    #[test]
+    fn try_build_configuration_errors() {
+        assert!(matches!(
+            GraphClientConfiguration::new().try_build(),
+            Err(ConfigurationError::MissingCredentials)
+        ));
+        assert!(matches!(
+            GraphClientConfiguration::new()
+                .access_token("access_token")
+                .min_tls_version(Version::TLS_1_1)
+                .try_build(),
+            Err(ConfigurationError::TlsVersion(version)) if version == "TLS 1.1"
+        ));
+        assert!(GraphClientConfiguration::new()
+            .access_token("access_token")
+            .min_tls_version(Version::TLS_1_2)
+            .try_build()
+            .is_ok());
+    }
+
+    #[test]
+    fn try_build_minimal_clients() {
+        let config = GraphClientConfiguration::new().min_tls_version(Version::TLS_1_0);
+        assert!(config.clone().try_build_minimal_async_client().is_ok());
+        assert!(config.clone().try_build_minimal_blocking_client().is_ok());
+    }
+
+    #[test]
+    fn try_build_blocking_configuration_errors() {
+        assert!(matches!(
+            GraphClientConfiguration::new().try_build_blocking(),
+            Err(ConfigurationError::MissingCredentials)
+        ));
+        assert!(matches!(
+            GraphClientConfiguration::new()
+                .access_token("access_token")
+                .min_tls_version(Version::TLS_1_0)
+                .try_build_blocking(),
+            Err(ConfigurationError::TlsVersion(version)) if version == "TLS 1.0"
+        ));
+        assert!(GraphClientConfiguration::new()
+            .access_token("access_token")
+            .try_build_blocking()
+            .is_ok());
+    }
+
+    #[test]
+    fn root_certificate_and_identity() {
+        let cert = include_bytes!("../../graph-oauth/src/identity/credentials/test/cert.pem");
+        let config = GraphClientConfiguration::new()
+            .access_token("access_token")
+            .add_root_certificate(Certificate::from_pem(cert).unwrap());
+
+        #[cfg(feature = "native-tls")]
+        let config = config.identity(
+            Identity::from_pkcs8_pem(
+                cert,
+                include_bytes!("../../graph-oauth/src/identity/credentials/test/key.pem"),
+            )
+            .unwrap(),
+        );
+
+        assert!(config.clone().try_build().is_ok());
+        let _ = config.build_blocking();
+    }
+
+    #[test]
+    fn trusted_graph_hosts() {
+        let client = GraphClientConfiguration::new()
+            .access_token("access_token")
+            .trust_graph_host(Url::parse("https://graph.corp.internal/v1.0?query").unwrap())
+            .trust_graph_host(Url::parse("https://graph.corp.internal").unwrap())
+            .try_build()
+            .unwrap();
+        assert_eq!(
+            vec![Url::parse("https://graph.corp.internal").unwrap()],
+            client.trusted_graph_hosts()
+        );
+
+        assert!(matches!(
+            GraphClientConfiguration::new()
+                .access_token("access_token")
+                .trust_graph_host(Url::parse("http://graph.corp.internal").unwrap())
+                .try_build(),
+            Err(ConfigurationError::InsecureEndpoint(_))
+        ));
+    }
 }
@@ -2,32 +2,50 @@
 extern crate serde;
 
 mod blocking;
+mod cache;
+mod claims_challenge;
 mod client;
 mod core;
+mod long_running_operation;
+mod rate_limit;
 mod request_components;
 mod request_handler;
 mod resource_identifier;
+mod telemetry;
 mod tower_services;
+mod typed_request_handler;
 mod upload_session;
 
 pub mod url;
 
+/// Typed OData query expressions.
+pub mod odata;
+
 /// Traits for http utilities.
 pub mod traits;
 
 /// Io utilities for creating directories and files.
 pub mod io_tools;
 
+/// Record and replay requests for tests.
+#[cfg(feature = "test-util")]
+pub mod vcr;
+
 #[allow(unused_imports)]
 pub(crate) mod internal {
 
+    pub use crate::cache::*;
+    pub use crate::claims_challenge::*;
     pub use crate::client::*;
     pub use crate::core::*;
     pub use crate::io_tools::*;
+    pub use crate::long_running_operation::*;
+    pub use crate::rate_limit::*;
     pub use crate::request_components::*;
     pub use crate::request_handler::*;
     #[allow(unused_imports)]
     pub use crate::resource_identifier::*;
+    pub use crate::telemetry::*;
     pub use crate::tower_services::*;
     pub use crate::traits::*;
     pub use crate::upload_session::*;
@@ -35,13 +53,25 @@ pub(crate) mod internal {
 }
 
 pub mod api_impl {
-    pub use crate::blocking::{BlockingClient, BlockingRequestHandler, UploadSessionBlocking};
+    pub use crate::blocking::{
+        BlockingClient, BlockingRequestHandler, TypedBlockingRequestHandler, UploadSessionBlocking,
+    };
+    pub use crate::cache::{CachedResponse, InMemoryCache, ResponseCache};
     pub use crate::client::*;
     pub use crate::core::*;
+    pub use crate::long_running_operation::{
+        AsyncOperationState, AsyncOperationStatus, LongRunningOperation,
+    };
+    pub use crate::rate_limit::{RateLimit, RateLimitService, RateLimiter};
     pub use crate::request_components::RequestComponents;
     pub use crate::request_handler::{PagingResponse, PagingResult, RequestHandler};
     pub use crate::resource_identifier::{ResourceConfig, ResourceIdentifier};
+    pub use crate::telemetry::{
+        GRAPH_REQUESTS_TOTAL, GRAPH_REQUEST_DURATION_SECONDS, GRAPH_REQUEST_RETRIES_TOTAL,
+        GRAPH_THROTTLED_RESPONSES_TOTAL, GRAPH_THROTTLE_WAIT_SECONDS,
+    };
     pub use crate::traits::{ApiClientImpl, BodyExt, ODataQuery};
+    pub use crate::typed_request_handler::{TypedRequestHandler, TypedResponse, TypedResult};
     pub use crate::upload_session::UploadSession;
     pub use graph_core::identity::ClientApplication;
     pub use graph_error::{GraphFailure, GraphResult};
@@ -0,0 +1,465 @@
+use crate::internal::{new_client_request_id, Client, GraphService, CLIENT_REQUEST_ID};
+use graph_error::{AsyncOperationError, ErrorStatus, GraphApiError, GraphFailure, GraphResult};
+use reqwest::header::{
+    HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_LOCATION, LOCATION, RETRY_AFTER,
+};
+use reqwest::{Method, Response, StatusCode};
+use std::fmt::{Debug, Formatter};
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::{Service, ServiceExt};
+use url::Url;
+
+/// The state of a long running operation. The statuses of `asyncJobStatus`,
+/// `teamsAsyncOperation`, and `dataPolicyOperation` are mapped to the same states.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum AsyncOperationState {
+    NotStarted,
+    InProgress,
+    Completed,
+    Failed,
+    /// A status that is not known to the client. The operation keeps being polled.
+    Other(String),
+}
+
+impl AsyncOperationState {
+    /// Returns true if the operation has completed or failed.
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self,
+            AsyncOperationState::Completed | AsyncOperationState::Failed
+        )
+    }
+}
+
+impl From<&str> for AsyncOperationState {
+    fn from(value: &str) -> Self {
+        match value {
+            "notStarted" | "waiting" => AsyncOperationState::NotStarted,
+            "inProgress" | "running" | "updating" => AsyncOperationState::InProgress,
+            "completed" | "complete" | "succeeded" => AsyncOperationState::Completed,
+            "failed" | "deleteFailed" => AsyncOperationState::Failed,
+            _ => AsyncOperationState::Other(value.to_string()),
+        }
+    }
+}
+
+/// The status returned by the monitor url of a long running operation.
+///
+/// [asyncJobStatus](https://learn.microsoft.com/en-us/graph/long-running-actions-overview),
+/// [teamsAsyncOperation](https://learn.microsoft.com/en-us/graph/api/resources/teamsasyncoperation)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsyncOperationStatus {
+    pub state: AsyncOperationState,
+    /// The status as returned by the Api such as `inProgress` or `succeeded`.
+    pub status: Option<String>,
+    /// The type of the operation such as `itemCopy` or `createTeam`.
+    pub operation: Option<String>,
+    /// A value between 0 and 100 when the Api reports the progress of the operation.
+    pub percentage_complete: Option<f64>,
+    pub status_description: Option<String>,
+    /// The id of the resource created by the operation.
+    pub resource_id: Option<String>,
+    /// The url of the resource created by the operation, or the location of the exported
+    /// data of `exportPersonalData`.
+    pub resource_location: Option<String>,
+    pub error: Option<ErrorStatus>,
+    /// The response body of the monitor url.
+    pub body: serde_json::Value,
+}
+
+impl AsyncOperationStatus {
+    fn in_progress() -> AsyncOperationStatus {
+        AsyncOperationStatus::from(serde_json::json!({ "status": "inProgress" }))
+    }
+}
+
+impl From<serde_json::Value> for AsyncOperationStatus {
+    fn from(body: serde_json::Value) -> Self {
+        let string = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| body[name].as_str())
+                .map(String::from)
+        };
+
+        let status = string(&["status"]);
+        let state = match status.as_deref() {
+            Some(status) => AsyncOperationState::from(status),
+            // The monitor url of a completed copy redirects to the created item.
+            None if body["id"].is_string() => AsyncOperationState::Completed,
+            None => AsyncOperationState::InProgress,
+        };
+        let resource_id = match status {
+            Some(_) => string(&["resourceId", "targetResourceId"]),
+            None => string(&["id"]),
+        };
+
+        AsyncOperationStatus {
+            state,
+            operation: string(&["operation", "operationType"]),
+            percentage_complete: body["percentageComplete"]
+                .as_f64()
+                .or_else(|| body["progress"].as_f64()),
+            status_description: string(&["statusDescription"]),
+            resource_id,
+            resource_location: string(&["targetResourceLocation", "storageLocation"]),
+            error: serde_json::from_value(body["error"].clone()).ok(),
+            status,
+            body,
+        }
+    }
+}
+
+type ProgressHandler = Arc<dyn Fn(&AsyncOperationStatus) + Send + Sync>;
+
+/// Polls the monitor url of an operation that returned `202 Accepted`, such as copying a
+/// drive item, creating or cloning a team, or `exportPersonalData`, until the operation
+/// completes or fails.
+///
+/// The monitor url is polled with an exponential backoff starting at the poll interval.
+/// A `Retry-After` header of the monitor response is used as the delay until the next
+/// poll instead. Awaiting the operation resolves to the final [`AsyncOperationStatus`]
+/// with the id or url of the created resource, or a [`GraphFailure::AsyncOperation`]
+/// error when the operation fails, times out, or is cancelled.
+///
+/// # Example
+/// ```rust,ignore
+/// let status = client
+///     .drive("DRIVE_ID")
+///     .item("ITEM_ID")
+///     .copy(&serde_json::json!({ "name": "copy.txt" }))
+///     .send_long_running()
+///     .await?
+///     .timeout(Duration::from_secs(300))
+///     .on_progress(|status| println!("{:?}%", status.percentage_complete))
+///     .await?;
+///
+/// println!("{:?}", status.resource_id);
+/// ```
+pub struct LongRunningOperation {
+    client: Client,
+    service: GraphService,
+    monitor_url: Url,
+    authenticate: bool,
+    content_location: Option<String>,
+    poll_interval: Duration,
+    max_poll_interval: Duration,
+    timeout: Option<Duration>,
+    cancellation: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+    on_progress: Option<ProgressHandler>,
+}
+
+impl LongRunningOperation {
+    /// The default delay before the monitor url is polled the second time. The delay
+    /// doubles after each poll.
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// The default maximum delay between polls.
+    pub const DEFAULT_MAX_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+    /// Create the operation from the `202 Accepted` response of a request to `request_url`.
+    /// Monitor urls that are relative to the Graph Api are polled with the access token
+    /// of the client. Monitor urls on other hosts, such as the pre-authenticated urls
+    /// returned for copying drive items, are polled without an access token.
+    pub(crate) fn from_response(
+        client: Client,
+        service: GraphService,
+        request_url: &Url,
+        response: &Response,
+    ) -> GraphResult<LongRunningOperation> {
+        let location = header_str(response.headers(), LOCATION)
+            .ok_or(AsyncOperationError::MissingMonitorUrl)?;
+        let monitor_url = graph_url(request_url, location)?;
+        let authenticate = monitor_url.host_str() == request_url.host_str()
+            && monitor_url.port_or_known_default() == request_url.port_or_known_default();
+        let content_location = header_str(response.headers(), CONTENT_LOCATION)
+            .and_then(|location| graph_url(request_url, location).ok())
+            .map(String::from);
+
+        Ok(LongRunningOperation {
+            client,
+            service,
+            monitor_url,
+            authenticate,
+            content_location,
+            poll_interval: LongRunningOperation::DEFAULT_POLL_INTERVAL,
+            max_poll_interval: LongRunningOperation::DEFAULT_MAX_POLL_INTERVAL,
+            timeout: None,
+            cancellation: None,
+            on_progress: None,
+        })
+    }
+
+    /// The url that reports the status of the operation.
+    pub fn monitor_url(&self) -> &Url {
+        &self.monitor_url
+    }
+
+    /// The delay before the monitor url is polled the second time.
+    ///
+    /// Default is 1 second.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> LongRunningOperation {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// The maximum delay between polls.
+    ///
+    /// Default is 30 seconds.
+    pub fn max_poll_interval(mut self, max_poll_interval: Duration) -> LongRunningOperation {
+        self.max_poll_interval = max_poll_interval;
+        self
+    }
+
+    /// Stop polling and return [`AsyncOperationError::TimedOut`] when the operation has
+    /// not completed within the timeout.
+    ///
+    /// Default is no timeout.
+    pub fn timeout(mut self, timeout: Duration) -> LongRunningOperation {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Stop polling and return [`AsyncOperationError::Cancelled`] when the future
+    /// completes, such as a shutdown signal or the receiver of a channel. The operation
+    /// itself keeps running in the service.
+    pub fn cancel_on<F>(mut self, cancellation: F) -> LongRunningOperation
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.cancellation = Some(Box::pin(cancellation));
+        self
+    }
+
+    /// Called with the status of the operation each time the monitor url is polled.
+    pub fn on_progress<F>(mut self, on_progress: F) -> LongRunningOperation
+    where
+        F: Fn(&AsyncOperationStatus) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+
+    /// Poll the monitor url once.
+    pub async fn status(&mut self) -> GraphResult<AsyncOperationStatus> {
+        self.poll().await.map(|(status, _)| status)
+    }
+
+    /// Poll the monitor url until the operation completes or fails.
+    pub async fn wait(mut self) -> GraphResult<AsyncOperationStatus> {
+        let timeout = self.timeout;
+        let cancellation = self.cancellation.take();
+        let poll = async move {
+            match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, self.poll_until_done())
+                    .await
+                    .map_err(|_| AsyncOperationError::TimedOut(timeout))?,
+                None => self.poll_until_done().await,
+            }
+        };
+
+        match cancellation {
+            Some(cancellation) => {
+                tokio::select! {
+                    result = poll => result,
+                    _ = cancellation => Err(AsyncOperationError::Cancelled.into()),
+                }
+            }
+            None => poll.await,
+        }
+    }
+
+    async fn poll_until_done(&mut self) -> GraphResult<AsyncOperationStatus> {
+        let mut interval = self.poll_interval;
+        loop {
+            let (mut status, retry_after) = self.poll().await?;
+            if let Some(on_progress) = self.on_progress.as_ref() {
+                on_progress(&status);
+            }
+
+            match status.state {
+                AsyncOperationState::Completed => {
+                    if status.resource_location.is_none() {
+                        status.resource_location = self.content_location.clone();
+                    }
+                    return Ok(status);
+                }
+                AsyncOperationState::Failed => {
+                    return Err(AsyncOperationError::Failed {
+                        status: status.status.unwrap_or_default(),
+                        error: status.error,
+                        monitor_url: self.monitor_url.clone(),
+                    }
+                    .into());
+                }
+                _ => {}
+            }
+
+            tokio::time::sleep(retry_after.unwrap_or(interval)).await;
+            interval = interval.saturating_mul(2).min(self.max_poll_interval);
+        }
+    }
+
+    /// The status of the operation and the delay from the `Retry-After` header.
+    async fn poll(&mut self) -> GraphResult<(AsyncOperationStatus, Option<Duration>)> {
+        let mut request = self.client.inner.get(self.monitor_url.clone()).build()?;
+        request
+            .headers_mut()
+            .insert(CLIENT_REQUEST_ID.clone(), new_client_request_id());
+        if self.authenticate {
+            let access_token = self
+                .client
+                .client_application
+                .get_token_silent_async()
+                .await?;
+            let authorization = self
+                .client
+                .client_application
+                .auth_scheme()
+                .authorization_header(access_token.as_str(), &Method::GET, &self.monitor_url)?;
+            let mut header_value =
+                HeaderValue::from_str(authorization.as_str()).map_err(http::Error::from)?;
+            header_value.set_sensitive(true);
+            request.headers_mut().insert(AUTHORIZATION, header_value);
+        }
+
+        let response = self
+            .service
+            .ready()
+            .await
+            .map_err(GraphFailure::from)?
+            .call(request)
+            .await
+            .map_err(GraphFailure::from)?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        if !status.is_success() && status != StatusCode::SEE_OTHER {
+            return Err(GraphApiError::from_parts(status, &headers, &body).into());
+        }
+
+        let retry_after = header_str(&headers, RETRY_AFTER)
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let mut operation_status = match serde_json::from_slice::<serde_json::Value>(&body) {
+            Ok(body) => AsyncOperationStatus::from(body),
+            Err(_) if status == StatusCode::ACCEPTED => AsyncOperationStatus::in_progress(),
+            Err(err) => return Err(err.into()),
+        };
+        if status == StatusCode::SEE_OTHER {
+            operation_status.state = AsyncOperationState::Completed;
+            operation_status.resource_location = header_str(&headers, LOCATION).map(String::from);
+        }
+        Ok((operation_status, retry_after))
+    }
+}
+
+impl IntoFuture for LongRunningOperation {
+    type Output = GraphResult<AsyncOperationStatus>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.wait())
+    }
+}
+
+impl Debug for LongRunningOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LongRunningOperation")
+            .field("monitor_url", &self.monitor_url)
+            .field("authenticate", &self.authenticate)
+            .field("content_location", &self.content_location)
+            .field("poll_interval", &self.poll_interval)
+            .field("max_poll_interval", &self.max_poll_interval)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: reqwest::header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Locations such as `/teams('{id}')/operations('{id}')` are relative to the version of
+/// the Graph Api used by the request.
+fn graph_url(request_url: &Url, location: &str) -> GraphResult<Url> {
+    if !location.starts_with('/') {
+        return Ok(Url::parse(location)?);
+    }
+
+    let version = request_url
+        .path_segments()
+        .and_then(|mut segments| segments.next())
+        .unwrap_or_default();
+    Ok(request_url.join(&format!("/{version}{location}"))?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_async_job_status() {
+        let status = AsyncOperationStatus::from(serde_json::json!({
+            "operation": "itemCopy",
+            "percentageComplete": 27.8,
+            "status": "inProgress"
+        }));
+        assert_eq!(AsyncOperationState::InProgress, status.state);
+        assert_eq!(Some("itemCopy"), status.operation.as_deref());
+        assert_eq!(Some(27.8), status.percentage_complete);
+
+        let status = AsyncOperationStatus::from(serde_json::json!({
+            "id": "item-id",
+            "name": "copy.txt"
+        }));
+        assert_eq!(AsyncOperationState::Completed, status.state);
+        assert_eq!(Some("item-id"), status.resource_id.as_deref());
+    }
+
+    #[test]
+    fn parse_teams_async_operation() {
+        let status = AsyncOperationStatus::from(serde_json::json!({
+            "id": "operation-id",
+            "operationType": "createTeam",
+            "status": "succeeded",
+            "targetResourceId": "team-id",
+            "targetResourceLocation": "/teams('team-id')"
+        }));
+        assert_eq!(AsyncOperationState::Completed, status.state);
+        assert_eq!(Some("createTeam"), status.operation.as_deref());
+        assert_eq!(Some("team-id"), status.resource_id.as_deref());
+        assert_eq!(
+            Some("/teams('team-id')"),
+            status.resource_location.as_deref()
+        );
+
+        let status = AsyncOperationStatus::from(serde_json::json!({
+            "status": "failed",
+            "error": { "code": "Conflict", "message": "Team already exists." }
+        }));
+        assert!(status.state.is_done());
+        assert_eq!(Some("Conflict"), status.error.unwrap().code.as_deref());
+    }
+
+    #[test]
+    fn relative_monitor_url() {
+        let request_url = Url::parse("https://graph.microsoft.com/beta/teams").unwrap();
+        assert_eq!(
+            "https://graph.microsoft.com/beta/teams('id')/operations('op')",
+            graph_url(&request_url, "/teams('id')/operations('op')")
+                .unwrap()
+                .as_str()
+        );
+        assert_eq!(
+            "https://api.onedrive.com/monitor/id",
+            graph_url(&request_url, "https://api.onedrive.com/monitor/id")
+                .unwrap()
+                .as_str()
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::odata::Filter;
+use std::fmt::{Display, Formatter};
+
+/// Navigation property for the `$expand` query parameter with the query
+/// options that are applied to the related resources.
+///
+/// # Example
+/// ```
+/// use graph_http::odata::{Expand, Filter};
+///
+/// let expand = Expand::new("members")
+///     .select(&["id", "displayName"])
+///     .filter(Filter::eq("accountEnabled", true));
+///
+/// assert_eq!(
+///     expand.to_string(),
+///     "members($select=id,displayName;$filter=accountEnabled eq true)"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expand {
+    property: String,
+    select: Vec<String>,
+    filter: Option<Filter>,
+    top: Option<usize>,
+    expand: Vec<Expand>,
+}
+
+impl Expand {
+    pub fn new<P: AsRef<str>>(property: P) -> Expand {
+        Expand {
+            property: property.as_ref().to_string(),
+            select: Vec::new(),
+            filter: None,
+            top: None,
+            expand: Vec::new(),
+        }
+    }
+
+    /// Properties of the related resources to return.
+    pub fn select(mut self, properties: &[&str]) -> Expand {
+        self.select
+            .extend(properties.iter().map(|property| property.to_string()));
+        self
+    }
+
+    /// Filter the related resources.
+    pub fn filter(mut self, filter: Filter) -> Expand {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Max number of related resources to return.
+    pub fn top(mut self, top: usize) -> Expand {
+        self.top = Some(top);
+        self
+    }
+
+    /// Expand a navigation property of the related resources.
+    pub fn expand(mut self, expand: Expand) -> Expand {
+        self.expand.push(expand);
+        self
+    }
+}
+
+impl Display for Expand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut options = Vec::new();
+        if !self.select.is_empty() {
+            options.push(format!("$select={}", self.select.join(",")));
+        }
+        if let Some(filter) = self.filter.as_ref() {
+            options.push(format!("$filter={filter}"));
+        }
+        if let Some(top) = self.top {
+            options.push(format!("$top={top}"));
+        }
+        if !self.expand.is_empty() {
+            let expand: Vec<String> = self.expand.iter().map(|e| e.to_string()).collect();
+            options.push(format!("$expand={}", expand.join(",")));
+        }
+
+        if options.is_empty() {
+            write!(f, "{}", self.property)
+        } else {
+            write!(f, "{}({})", self.property, options.join(";"))
+        }
+    }
+}
+
+impl From<&str> for Expand {
+    fn from(property: &str) -> Self {
+        Expand::new(property)
+    }
+}
+
+impl From<String> for Expand {
+    fn from(property: String) -> Self {
+        Expand::new(property)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nested_expand() {
+        let expand = Expand::new("manager")
+            .select(&["id"])
+            .expand(Expand::new("directReports").select(&["id", "mail"]).top(5));
+        assert_eq!(
+            "manager($select=id;$expand=directReports($select=id,mail;$top=5))",
+            expand.to_string()
+        );
+        assert_eq!("members", Expand::from("members").to_string());
+    }
+}
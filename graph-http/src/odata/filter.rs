@@ -0,0 +1,529 @@
+use crate::odata::Literal;
+use std::fmt::{Display, Formatter};
+
+/// Comparison operators of the `$filter` query parameter.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ComparisonOperator {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl AsRef<str> for ComparisonOperator {
+    fn as_ref(&self) -> &str {
+        match self {
+            ComparisonOperator::Eq => "eq",
+            ComparisonOperator::Ne => "ne",
+            ComparisonOperator::Gt => "gt",
+            ComparisonOperator::Ge => "ge",
+            ComparisonOperator::Lt => "lt",
+            ComparisonOperator::Le => "le",
+        }
+    }
+}
+
+/// String functions of the `$filter` query parameter.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FilterFunction {
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+impl AsRef<str> for FilterFunction {
+    fn as_ref(&self) -> &str {
+        match self {
+            FilterFunction::StartsWith => "startswith",
+            FilterFunction::EndsWith => "endswith",
+            FilterFunction::Contains => "contains",
+        }
+    }
+}
+
+/// Lambda operators for filtering on the items of a collection property.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LambdaOperator {
+    Any,
+    All,
+}
+
+impl AsRef<str> for LambdaOperator {
+    fn as_ref(&self) -> &str {
+        match self {
+            LambdaOperator::Any => "any",
+            LambdaOperator::All => "all",
+        }
+    }
+}
+
+/// Expression for the `$filter` query parameter. Values are typed [`Literal`]s
+/// which are quoted and escaped when the expression is written.
+///
+/// Use `!` to negate an expression.
+///
+/// # Example
+/// ```
+/// use graph_http::odata::Filter;
+///
+/// let filter = Filter::eq("userType", "Member")
+///     .and(Filter::starts_with("displayName", "O'Neil").or(Filter::ends_with("mail", "@contoso.com")))
+///     .and(!Filter::is_in("department", ["Sales", "Marketing"]));
+///
+/// assert_eq!(
+///     filter.to_string(),
+///     "userType eq 'Member' and (startswith(displayName,'O''Neil') or endswith(mail,'@contoso.com')) \
+///     and not(department in ('Sales','Marketing'))"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Comparison {
+        property: String,
+        operator: ComparisonOperator,
+        value: Literal,
+    },
+    Function {
+        function: FilterFunction,
+        property: String,
+        value: Literal,
+    },
+    In {
+        property: String,
+        values: Vec<Literal>,
+    },
+    Lambda {
+        collection: String,
+        operator: LambdaOperator,
+        variable: String,
+        predicate: Box<Filter>,
+    },
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    fn comparison<P: AsRef<str>, V: Into<Literal>>(
+        property: P,
+        operator: ComparisonOperator,
+        value: V,
+    ) -> Filter {
+        Filter::Comparison {
+            property: property.as_ref().to_string(),
+            operator,
+            value: value.into(),
+        }
+    }
+
+    fn function<P: AsRef<str>, V: Into<Literal>>(
+        function: FilterFunction,
+        property: P,
+        value: V,
+    ) -> Filter {
+        Filter::Function {
+            function,
+            property: property.as_ref().to_string(),
+            value: value.into(),
+        }
+    }
+
+    fn lambda<C: AsRef<str>, V: AsRef<str>>(
+        collection: C,
+        operator: LambdaOperator,
+        variable: V,
+        predicate: Filter,
+    ) -> Filter {
+        Filter::Lambda {
+            collection: collection.as_ref().to_string(),
+            operator,
+            variable: variable.as_ref().to_string(),
+            predicate: Box::new(predicate),
+        }
+    }
+
+    pub fn eq<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::comparison(property, ComparisonOperator::Eq, value)
+    }
+
+    pub fn ne<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::comparison(property, ComparisonOperator::Ne, value)
+    }
+
+    pub fn gt<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::comparison(property, ComparisonOperator::Gt, value)
+    }
+
+    pub fn ge<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::comparison(property, ComparisonOperator::Ge, value)
+    }
+
+    pub fn lt<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::comparison(property, ComparisonOperator::Lt, value)
+    }
+
+    pub fn le<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::comparison(property, ComparisonOperator::Le, value)
+    }
+
+    pub fn starts_with<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::function(FilterFunction::StartsWith, property, value)
+    }
+
+    pub fn ends_with<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::function(FilterFunction::EndsWith, property, value)
+    }
+
+    pub fn contains<P: AsRef<str>, V: Into<Literal>>(property: P, value: V) -> Filter {
+        Filter::function(FilterFunction::Contains, property, value)
+    }
+
+    /// Property equals one of the values: `department in ('Sales','Marketing')`
+    pub fn is_in<P, I, V>(property: P, values: I) -> Filter
+    where
+        P: AsRef<str>,
+        I: IntoIterator<Item = V>,
+        V: Into<Literal>,
+    {
+        Filter::In {
+            property: property.as_ref().to_string(),
+            values: values.into_iter().map(|v| v.into()).collect(),
+        }
+    }
+
+    /// Any item of the collection matches the predicate. Properties of the item are
+    /// referenced using the variable: `Filter::any("emails", "e", Filter::eq("e", "a@contoso.com"))`
+    /// is written as `emails/any(e:e eq 'a@contoso.com')`.
+    pub fn any<C: AsRef<str>, V: AsRef<str>>(
+        collection: C,
+        variable: V,
+        predicate: Filter,
+    ) -> Filter {
+        Filter::lambda(collection, LambdaOperator::Any, variable, predicate)
+    }
+
+    /// Every item of the collection matches the predicate. See [`Filter::any`].
+    pub fn all<C: AsRef<str>, V: AsRef<str>>(
+        collection: C,
+        variable: V,
+        predicate: Filter,
+    ) -> Filter {
+        Filter::lambda(collection, LambdaOperator::All, variable, predicate)
+    }
+
+    pub fn and(self, other: Filter) -> Filter {
+        Filter::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Filter) -> Filter {
+        Filter::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Returns true if the expression uses operators that are only supported by the
+    /// advanced query capabilities of directory objects such as users and groups.
+    /// Advanced queries require the `ConsistencyLevel: eventual` header.
+    /// [See the docs](https://learn.microsoft.com/en-us/graph/aad-advanced-queries)
+    pub fn is_advanced_query(&self) -> bool {
+        match self {
+            Filter::Comparison {
+                property, operator, ..
+            } => operator.eq(&ComparisonOperator::Ne) || property.contains("$count"),
+            Filter::Function { function, .. } => function.eq(&FilterFunction::EndsWith),
+            Filter::In { property, .. } => property.contains("$count"),
+            Filter::Lambda { predicate, .. } => predicate.is_advanced_query(),
+            Filter::And(left, right) | Filter::Or(left, right) => {
+                left.is_advanced_query() || right.is_advanced_query()
+            }
+            Filter::Not(_) => true,
+        }
+    }
+
+    // Operands of `and` only need parentheses for `or` because `and` has a higher
+    // precedence. Operands of `or` are always grouped to make the expression easier to read.
+    fn fmt_and_operand(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::Or(_, _) => write!(f, "({self})"),
+            _ => write!(f, "{self}"),
+        }
+    }
+
+    fn fmt_or_operand(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::And(_, _) => write!(f, "({self})"),
+            _ => write!(f, "{self}"),
+        }
+    }
+}
+
+impl std::ops::Not for Filter {
+    type Output = Filter;
+
+    fn not(self) -> Self::Output {
+        Filter::Not(Box::new(self))
+    }
+}
+
+impl Display for Filter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::Comparison {
+                property,
+                operator,
+                value,
+            } => write!(f, "{property} {} {value}", operator.as_ref()),
+            Filter::Function {
+                function,
+                property,
+                value,
+            } => write!(f, "{}({property},{value})", function.as_ref()),
+            Filter::In { property, values } => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "{property} in ({})", values.join(","))
+            }
+            Filter::Lambda {
+                collection,
+                operator,
+                variable,
+                predicate,
+            } => write!(
+                f,
+                "{collection}/{}({variable}:{predicate})",
+                operator.as_ref()
+            ),
+            Filter::And(left, right) => {
+                left.fmt_and_operand(f)?;
+                write!(f, " and ")?;
+                right.fmt_and_operand(f)
+            }
+            Filter::Or(left, right) => {
+                left.fmt_or_operand(f)?;
+                write!(f, " or ")?;
+                right.fmt_or_operand(f)
+            }
+            Filter::Not(filter) => write!(f, "not({filter})"),
+        }
+    }
+}
+
+/// Tokens of a raw `$filter` expression.
+#[derive(Debug, PartialEq)]
+enum FilterToken {
+    Word(String),
+    Literal,
+    Open,
+    Close,
+    Comma,
+    Colon,
+}
+
+impl FilterToken {
+    fn is_operator(&self) -> bool {
+        matches!(self, FilterToken::Word(word) if matches!(
+            word.as_str(),
+            "eq" | "ne" | "gt" | "ge" | "lt" | "le" | "in" | "has" | "and" | "or"
+        ))
+    }
+
+    /// Returns true if the token can end an operand, which puts the token after it
+    /// in the position of a binary operator.
+    fn ends_operand(&self) -> bool {
+        match self {
+            FilterToken::Word(word) => !self.is_operator() && word != "not",
+            FilterToken::Literal | FilterToken::Close => true,
+            _ => false,
+        }
+    }
+
+    fn starts_operand(&self) -> bool {
+        match self {
+            FilterToken::Word(_) => !self.is_operator(),
+            FilterToken::Literal | FilterToken::Open => true,
+            _ => false,
+        }
+    }
+}
+
+fn tokenize(filter: &str) -> Vec<FilterToken> {
+    let mut tokens = Vec::new();
+    let mut chars = filter.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // Quotes inside of string literals are escaped by doubling them.
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                tokens.push(FilterToken::Literal);
+            }
+            '(' => tokens.push(FilterToken::Open),
+            ')' => tokens.push(FilterToken::Close),
+            ',' => tokens.push(FilterToken::Comma),
+            ':' => tokens.push(FilterToken::Colon),
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = String::from(c.to_ascii_lowercase());
+                while let Some(c) = chars
+                    .next_if(|c| !c.is_whitespace() && !matches!(c, '\'' | '(' | ')' | ',' | ':'))
+                {
+                    word.push(c.to_ascii_lowercase());
+                }
+                tokens.push(FilterToken::Word(word));
+            }
+        }
+    }
+    tokens
+}
+
+/// Returns true if a raw `$filter` expression uses the advanced query capabilities that
+/// [`Filter::is_advanced_query`] detects for typed expressions: the `ne` operator, the
+/// `not` operator, the `endswith` function, or the `$count` of a collection.
+///
+/// Operators are matched as whole tokens in the position of an operator, so property
+/// names such as `note` or a property named `ne`, and string literals, are ignored.
+pub(crate) fn is_advanced_filter(filter: &str) -> bool {
+    let tokens = tokenize(filter);
+    tokens.iter().enumerate().any(|(i, token)| {
+        let FilterToken::Word(word) = token else {
+            return false;
+        };
+        let previous = i.checked_sub(1).map(|i| &tokens[i]);
+        let next = tokens.get(i + 1);
+        match word.as_str() {
+            "ne" => {
+                previous.is_some_and(FilterToken::ends_operand)
+                    && next.is_some_and(FilterToken::starts_operand)
+            }
+            "not" => {
+                !previous.is_some_and(FilterToken::ends_operand)
+                    && next.is_some_and(FilterToken::starts_operand)
+            }
+            "endswith" => next == Some(&FilterToken::Open),
+            word => word.contains("$count"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn comparison() {
+        assert_eq!(
+            "accountEnabled eq true",
+            Filter::eq("accountEnabled", true).to_string()
+        );
+        assert_eq!(
+            "displayName ne 'Adele'",
+            Filter::ne("displayName", "Adele").to_string()
+        );
+        assert_eq!("size gt 1024", Filter::gt("size", 1024).to_string());
+        assert_eq!("size ge 1024", Filter::ge("size", 1024).to_string());
+        assert_eq!("size lt 1024", Filter::lt("size", 1024).to_string());
+        assert_eq!("size le 1024", Filter::le("size", 1024).to_string());
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(
+            "startswith(displayName,'a''b')",
+            Filter::starts_with("displayName", "a'b").to_string()
+        );
+        assert_eq!(
+            "endswith(mail,'@contoso.com')",
+            Filter::ends_with("mail", "@contoso.com").to_string()
+        );
+        assert_eq!(
+            "contains(subject,'report')",
+            Filter::contains("subject", "report").to_string()
+        );
+    }
+
+    #[test]
+    fn lambda() {
+        assert_eq!(
+            "assignedLicenses/any(l:l/skuId eq 'sku')",
+            Filter::any("assignedLicenses", "l", Filter::eq("l/skuId", "sku")).to_string()
+        );
+        assert_eq!(
+            "emails/all(e:endswith(e,'@contoso.com'))",
+            Filter::all("emails", "e", Filter::ends_with("e", "@contoso.com")).to_string()
+        );
+    }
+
+    #[test]
+    fn logical_precedence() {
+        let a = Filter::eq("a", 1);
+        let b = Filter::eq("b", 2);
+        let c = Filter::eq("c", 3);
+
+        assert_eq!(
+            "a eq 1 and b eq 2 and c eq 3",
+            a.clone().and(b.clone()).and(c.clone()).to_string()
+        );
+        assert_eq!(
+            "(a eq 1 or b eq 2) and c eq 3",
+            a.clone().or(b.clone()).and(c.clone()).to_string()
+        );
+        assert_eq!(
+            "(a eq 1 and b eq 2) or c eq 3",
+            a.clone().and(b.clone()).or(c.clone()).to_string()
+        );
+        assert_eq!("not(a eq 1 or b eq 2)", (!a.or(b)).to_string());
+    }
+
+    #[test]
+    fn advanced_query() {
+        assert!(!Filter::eq("a", 1).is_advanced_query());
+        assert!(Filter::ne("a", 1).is_advanced_query());
+        assert!(Filter::ends_with("mail", "x").is_advanced_query());
+        assert!((!Filter::eq("a", 1)).is_advanced_query());
+        assert!(Filter::eq("memberOf/$count", 0).is_advanced_query());
+        assert!(Filter::eq("a", 1)
+            .and(Filter::ends_with("mail", "x"))
+            .is_advanced_query());
+    }
+
+    #[test]
+    fn advanced_raw_filters() {
+        assert!(is_advanced_filter("endsWith(mail,'@contoso.com')"));
+        assert!(is_advanced_filter("companyName ne null"));
+        assert!(is_advanced_filter("NOT(displayName eq 'Adele')"));
+        assert!(is_advanced_filter("not startswith(displayName,'a')"));
+        assert!(is_advanced_filter("owners/$count eq 0"));
+        assert!(is_advanced_filter(
+            "emails/all(e:endswith(e,'@contoso.com'))"
+        ));
+        assert!(!is_advanced_filter("displayName eq 'not ne endswith'"));
+        assert!(!is_advanced_filter("displayName eq 'O''Neil ne x'"));
+        assert!(!is_advanced_filter("startswith(displayName,'a')"));
+        assert!(!is_advanced_filter("ne eq 'a' and not eq 'b'"));
+        assert!(!is_advanced_filter("endswith eq 'a' or notes eq 'b'"));
+    }
+
+    #[test]
+    fn raw_and_typed_filters_agree() {
+        let filters = [
+            Filter::eq("ne", "a"),
+            Filter::eq("not", "ne"),
+            Filter::ne("a", 1),
+            !Filter::eq("a", 1),
+            Filter::ends_with("mail", "x"),
+            Filter::starts_with("endswith", "x"),
+            Filter::eq("memberOf/$count", 0),
+            Filter::any("emails", "e", Filter::ends_with("e", "x")),
+            Filter::eq("a", 1).or(Filter::eq("b", "not")),
+        ];
+        for filter in filters {
+            assert_eq!(
+                filter.is_advanced_query(),
+                is_advanced_filter(&filter.to_string()),
+                "{filter}"
+            );
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use graph_error::{GraphFailure, GraphResult};
+use std::fmt::{Display, Formatter};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A typed value in an OData expression such as the right hand side of
+/// `displayName eq 'Adele'`. Strings are quoted and single quotes in the
+/// string are escaped so that user input can not change the expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    String(String),
+    Guid(Uuid),
+    /// A date and time formatted as RFC 3339, such as `2023-01-02T03:04:05Z`.
+    /// Use [`Literal::date_time`] to format an `OffsetDateTime`.
+    DateTime(String),
+    /// A member of an enum type such as `microsoft.graph.riskLevel'high'`. The
+    /// type name is optional for properties where the Api infers the enum type.
+    Enum {
+        type_name: Option<String>,
+        member: String,
+    },
+    Number(String),
+    Bool(bool),
+    Null,
+}
+
+impl Literal {
+    pub fn string<S: AsRef<str>>(value: S) -> Literal {
+        Literal::String(value.as_ref().to_string())
+    }
+
+    pub fn guid(value: Uuid) -> Literal {
+        Literal::Guid(value)
+    }
+
+    /// Formats the date and time as RFC 3339. Returns an error for values that
+    /// RFC 3339 can not represent, such as years after 9999 or offsets with seconds.
+    pub fn date_time(value: OffsetDateTime) -> GraphResult<Literal> {
+        let value = value.format(&Rfc3339).map_err(|err| {
+            GraphFailure::invalid(&format!("date time literal is not valid RFC 3339: {err}"))
+        })?;
+        Ok(Literal::DateTime(value))
+    }
+
+    /// Enum member with the namespace qualified type name,
+    /// for example `Literal::enum_member("microsoft.graph.riskLevel", "high")`
+    pub fn enum_member<T: AsRef<str>, M: AsRef<str>>(type_name: T, member: M) -> Literal {
+        Literal::Enum {
+            type_name: Some(type_name.as_ref().to_string()),
+            member: member.as_ref().to_string(),
+        }
+    }
+
+    /// Enum member without the type name, for example `'high'`.
+    pub fn enum_value<M: AsRef<str>>(member: M) -> Literal {
+        Literal::Enum {
+            type_name: None,
+            member: member.as_ref().to_string(),
+        }
+    }
+}
+
+/// Quotes a string literal. Single quotes are escaped by doubling them.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::String(value) => write!(f, "{}", quote(value)),
+            Literal::Guid(value) => write!(f, "{}", value.hyphenated()),
+            Literal::DateTime(value) => write!(f, "{value}"),
+            Literal::Enum { type_name, member } => match type_name {
+                Some(type_name) => write!(f, "{type_name}{}", quote(member)),
+                None => write!(f, "{}", quote(member)),
+            },
+            Literal::Number(value) => write!(f, "{value}"),
+            Literal::Bool(value) => write!(f, "{value}"),
+            Literal::Null => write!(f, "null"),
+        }
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::string(value)
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal::String(value)
+    }
+}
+
+impl From<&String> for Literal {
+    fn from(value: &String) -> Self {
+        Literal::string(value)
+    }
+}
+
+impl From<Uuid> for Literal {
+    fn from(value: Uuid) -> Self {
+        Literal::Guid(value)
+    }
+}
+
+impl TryFrom<OffsetDateTime> for Literal {
+    type Error = GraphFailure;
+
+    fn try_from(value: OffsetDateTime) -> Result<Self, Self::Error> {
+        Literal::date_time(value)
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(value: bool) -> Self {
+        Literal::Bool(value)
+    }
+}
+
+impl<T: Into<Literal>> From<Option<T>> for Literal {
+    fn from(value: Option<T>) -> Self {
+        value.map(|v| v.into()).unwrap_or(Literal::Null)
+    }
+}
+
+macro_rules! number_literal {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Literal {
+                fn from(value: $ty) -> Self {
+                    Literal::Number(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+number_literal!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize, f32, f64);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn string_escaping() {
+        assert_eq!("'Adele'", Literal::from("Adele").to_string());
+        assert_eq!("'O''Neil'", Literal::from("O'Neil").to_string());
+        assert_eq!(
+            "''' or 1 eq 1 or displayName eq '''",
+            Literal::from("' or 1 eq 1 or displayName eq '").to_string()
+        );
+    }
+
+    #[test]
+    fn typed_literals() {
+        let guid = Uuid::parse_str("f0b5f4d8-5a5b-4d1a-9c5a-2a3f1b0c8e7d").unwrap();
+        assert_eq!(
+            "f0b5f4d8-5a5b-4d1a-9c5a-2a3f1b0c8e7d",
+            Literal::from(guid).to_string()
+        );
+        assert_eq!(
+            "2023-01-02T03:04:05Z",
+            Literal::date_time(OffsetDateTime::from_unix_timestamp(1672628645).unwrap())
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "microsoft.graph.riskLevel'high'",
+            Literal::enum_member("microsoft.graph.riskLevel", "high").to_string()
+        );
+        assert_eq!("'high'", Literal::enum_value("high").to_string());
+        assert_eq!("10", Literal::from(10).to_string());
+        assert_eq!("true", Literal::from(true).to_string());
+        assert_eq!("null", Literal::from(None::<&str>).to_string());
+    }
+
+    #[test]
+    fn invalid_date_time() {
+        let offset = time::UtcOffset::from_hms(1, 0, 30).unwrap();
+        let value = OffsetDateTime::from_unix_timestamp(1672628645)
+            .unwrap()
+            .to_offset(offset);
+        assert!(Literal::date_time(value).is_err());
+        assert!(Literal::try_from(value).is_err());
+    }
+}
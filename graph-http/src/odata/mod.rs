@@ -0,0 +1,11 @@
+//! Typed expressions for the OData query parameters `$filter`, `$orderby`, and `$expand`.
+//! These are used with the methods of [`ODataQuery`](crate::traits::ODataQuery).
+mod expand;
+mod filter;
+mod literal;
+mod order_by;
+
+pub use expand::*;
+pub use filter::*;
+pub use literal::*;
+pub use order_by::*;
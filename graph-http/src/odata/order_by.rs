@@ -0,0 +1,34 @@
+use std::fmt::{Display, Formatter};
+
+/// Property and direction for the `$orderby` query parameter.
+///
+/// # Example
+/// ```
+/// use graph_http::odata::OrderBy;
+///
+/// assert_eq!(OrderBy::desc("createdDateTime").to_string(), "createdDateTime desc");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OrderBy {
+    Asc(String),
+    Desc(String),
+}
+
+impl OrderBy {
+    pub fn asc<P: AsRef<str>>(property: P) -> OrderBy {
+        OrderBy::Asc(property.as_ref().to_string())
+    }
+
+    pub fn desc<P: AsRef<str>>(property: P) -> OrderBy {
+        OrderBy::Desc(property.as_ref().to_string())
+    }
+}
+
+impl Display for OrderBy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderBy::Asc(property) => write!(f, "{property} asc"),
+            OrderBy::Desc(property) => write!(f, "{property} desc"),
+        }
+    }
+}
@@ -0,0 +1,637 @@
+use crate::internal::{GraphService, RequestContext};
+use graph_core::resource::ResourceIdentity;
+use http::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Request, Response};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Service, ServiceExt};
+use url::Url;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+static RATE_LIMIT_REMAINING: &str = "ratelimit-remaining";
+static RATE_LIMIT_RESET: &str = "ratelimit-reset";
+static THROTTLE_LIMIT_PERCENTAGE: &str = "x-ms-throttle-limit-percentage";
+
+/// The lowest fraction of the configured rate that requests are slowed down to.
+const MIN_RATE_FACTOR: f64 = 0.1;
+/// The fraction of the configured rate that is recovered by each successful response.
+const RATE_RECOVERY_STEP: f64 = 0.05;
+/// How long requests are held back after a 429 response without a `Retry-After` header.
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+/// How long a bucket is kept after its last request before it can be removed.
+const BUCKET_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+/// How often the buckets are checked for idle buckets to remove.
+const BUCKET_EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A limit of a number of requests per period. Requests are allowed in bursts of up
+/// to `requests` and then at an even rate of `requests / period`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, period: Duration) -> RateLimit {
+        RateLimit {
+            requests: requests.max(1),
+            period: period.max(Duration::from_millis(1)),
+        }
+    }
+
+    pub fn per_second(requests: u32) -> RateLimit {
+        RateLimit::new(requests, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(requests: u32) -> RateLimit {
+        RateLimit::new(requests, Duration::from_secs(60))
+    }
+
+    pub fn requests(&self) -> u32 {
+        self.requests
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    fn capacity(&self) -> f64 {
+        self.requests as f64
+    }
+
+    fn per_second_rate(&self) -> f64 {
+        self.requests as f64 / self.period.as_secs_f64()
+    }
+}
+
+/// The Microsoft Graph services that have their own throttling limits.
+///
+/// See [Microsoft Graph service-specific throttling limits](https://learn.microsoft.com/en-us/graph/throttling-limits)
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RateLimitService {
+    /// Mail, calendars, and contacts. Limited per app per mailbox.
+    Outlook,
+    /// OneDrive and SharePoint files, lists, and sites. Limited per app per tenant.
+    SharePoint,
+    /// Users, groups, applications, and other directory objects. Limited per app per tenant.
+    Identity,
+    /// Teams, channels, and chats.
+    Teams,
+    /// Any other resource.
+    Other,
+}
+
+impl From<ResourceIdentity> for RateLimitService {
+    fn from(value: ResourceIdentity) -> Self {
+        match value {
+            ResourceIdentity::CalendarGroups
+            | ResourceIdentity::CalendarView
+            | ResourceIdentity::Calendars
+            | ResourceIdentity::ChildFolders
+            | ResourceIdentity::ContactFolders
+            | ResourceIdentity::Contacts
+            | ResourceIdentity::DefaultCalendar
+            | ResourceIdentity::Events
+            | ResourceIdentity::EventsInstances
+            | ResourceIdentity::ExtendedProperties
+            | ResourceIdentity::InferenceClassification
+            | ResourceIdentity::MailboxSettings
+            | ResourceIdentity::MailFolders
+            | ResourceIdentity::Outlook
+            | ResourceIdentity::UsersAttachments
+            | ResourceIdentity::UsersMessages => RateLimitService::Outlook,
+            ResourceIdentity::Drive
+            | ResourceIdentity::Drives
+            | ResourceIdentity::DrivesItems
+            | ResourceIdentity::DrivesList
+            | ResourceIdentity::DrivesListContentTypes
+            | ResourceIdentity::FollowedSites
+            | ResourceIdentity::Shares
+            | ResourceIdentity::Sites
+            | ResourceIdentity::SitesContentTypes
+            | ResourceIdentity::SitesItems
+            | ResourceIdentity::SitesItemsVersions
+            | ResourceIdentity::SitesLists
+            | ResourceIdentity::TermStore
+            | ResourceIdentity::TermStores => RateLimitService::SharePoint,
+            ResourceIdentity::AdministrativeUnits
+            | ResourceIdentity::AppRoleAssignments
+            | ResourceIdentity::Application
+            | ResourceIdentity::Applications
+            | ResourceIdentity::ApplicationTemplates
+            | ResourceIdentity::CreatedObjects
+            | ResourceIdentity::Devices
+            | ResourceIdentity::DirectReports
+            | ResourceIdentity::Directory
+            | ResourceIdentity::DirectoryMembers
+            | ResourceIdentity::DirectoryObjects
+            | ResourceIdentity::DirectoryRoles
+            | ResourceIdentity::DirectoryRoleTemplates
+            | ResourceIdentity::Domains
+            | ResourceIdentity::Groups
+            | ResourceIdentity::GroupsOwners
+            | ResourceIdentity::Invitations
+            | ResourceIdentity::Me
+            | ResourceIdentity::MemberOf
+            | ResourceIdentity::Oauth2PermissionGrants
+            | ResourceIdentity::Organization
+            | ResourceIdentity::OrgContacts
+            | ResourceIdentity::OwnedDevices
+            | ResourceIdentity::OwnedObjects
+            | ResourceIdentity::ServicePrincipals
+            | ResourceIdentity::ServicePrincipalsOwners
+            | ResourceIdentity::TransitiveMemberOf
+            | ResourceIdentity::TransitiveMembers
+            | ResourceIdentity::Users => RateLimitService::Identity,
+            ResourceIdentity::AllChannels
+            | ResourceIdentity::Channels
+            | ResourceIdentity::Chats
+            | ResourceIdentity::ChatsAndChannelsMessages
+            | ResourceIdentity::ChatsMessages
+            | ResourceIdentity::ChatsMessagesReplies
+            | ResourceIdentity::DeletedTeams
+            | ResourceIdentity::GroupsTeam
+            | ResourceIdentity::IncomingChannels
+            | ResourceIdentity::JoinedTeams
+            | ResourceIdentity::PrimaryChannel
+            | ResourceIdentity::Schedule
+            | ResourceIdentity::SharedWithTeams
+            | ResourceIdentity::Tabs
+            | ResourceIdentity::Teams
+            | ResourceIdentity::TeamsMembers
+            | ResourceIdentity::TeamsPrimaryChannelTabs
+            | ResourceIdentity::TeamsTags
+            | ResourceIdentity::TeamsTemplates
+            | ResourceIdentity::Teamwork => RateLimitService::Teams,
+            _ => RateLimitService::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct BucketKey {
+    tenant: Option<String>,
+    service: RateLimitService,
+    mailbox: Option<String>,
+}
+
+/// Outlook limits apply per mailbox, which is the user of the request path.
+fn mailbox(url: &Url) -> Option<String> {
+    let mut segments = url.path_segments()?;
+    while let Some(segment) = segments.next() {
+        match segment {
+            "me" => return Some(String::from("me")),
+            "users" => return segments.next().map(|id| id.to_lowercase()),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn header_number<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<T>().ok())
+}
+
+#[derive(Debug)]
+struct Bucket {
+    limit: Option<RateLimit>,
+    // The limit was taken from the RateLimit headers of a response instead of
+    // the configuration.
+    learned: bool,
+    tokens: f64,
+    last_refill: Instant,
+    last_used: Instant,
+    rate_factor: f64,
+    blocked_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(limit: Option<RateLimit>, now: Instant) -> Bucket {
+        Bucket {
+            limit,
+            learned: false,
+            tokens: limit.map(|limit| limit.capacity()).unwrap_or_default(),
+            last_refill: now,
+            last_used: now,
+            rate_factor: 1.0,
+            blocked_until: None,
+        }
+    }
+
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_second_rate() * self.rate_factor)
+            .min(limit.capacity());
+        self.last_refill = now;
+    }
+
+    /// Returns true if the bucket has not been used for [`BUCKET_IDLE_TIMEOUT`] and a new
+    /// bucket would allow the same requests, so removing it does not change the limits.
+    fn is_idle(&self, now: Instant) -> bool {
+        if now.saturating_duration_since(self.last_used) < BUCKET_IDLE_TIMEOUT
+            || self.blocked_until.is_some_and(|blocked| blocked > now)
+        {
+            return false;
+        }
+
+        match self.limit {
+            Some(limit) if !self.learned => {
+                let elapsed = now
+                    .saturating_duration_since(self.last_refill)
+                    .as_secs_f64();
+                self.tokens + elapsed * limit.per_second_rate() * self.rate_factor
+                    >= limit.capacity()
+            }
+            // Learned limits are only kept until the limit resets.
+            _ => true,
+        }
+    }
+
+    /// Takes a token from the bucket or returns how long to wait before trying again.
+    fn try_acquire(&mut self, now: Instant) -> Option<Duration> {
+        self.last_used = now;
+        if let Some(blocked_until) = self.blocked_until {
+            if blocked_until > now {
+                return Some(blocked_until - now);
+            }
+            self.blocked_until = None;
+        }
+
+        let limit = self.limit?;
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return None;
+        }
+        let rate = limit.per_second_rate() * self.rate_factor;
+        Some(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+    }
+
+    fn block_for(&mut self, duration: Duration, now: Instant) {
+        let until = now + duration;
+        self.blocked_until = Some(
+            self.blocked_until
+                .map_or(until, |blocked| blocked.max(until)),
+        );
+        // No tokens are added while requests are held back.
+        self.last_refill = self.last_refill.max(until);
+    }
+
+    fn slow_down(&mut self, factor: f64) {
+        self.rate_factor = (self.rate_factor * factor).max(MIN_RATE_FACTOR);
+    }
+
+    fn observe(&mut self, status: StatusCode, headers: &HeaderMap, now: Instant) {
+        self.last_used = now;
+        if let Some(limit) = self.limit {
+            self.refill(limit, now);
+        }
+        let retry_after =
+            header_number::<u64>(headers, RETRY_AFTER.as_str()).map(Duration::from_secs);
+
+        if status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::SERVICE_UNAVAILABLE && retry_after.is_some())
+        {
+            self.slow_down(0.5);
+            self.tokens = 0.0;
+            self.block_for(retry_after.unwrap_or(DEFAULT_BACKOFF), now);
+            return;
+        }
+
+        let remaining = header_number::<u32>(headers, RATE_LIMIT_REMAINING);
+        let reset = header_number::<u64>(headers, RATE_LIMIT_RESET).map(Duration::from_secs);
+        if let (Some(remaining), Some(reset)) = (remaining, reset) {
+            if remaining == 0 {
+                self.tokens = 0.0;
+                self.block_for(reset, now);
+            } else if self.limit.is_none() || self.learned {
+                // Spread the requests that are left evenly until the limit resets.
+                if !self.learned {
+                    self.tokens = 1.0;
+                }
+                self.limit = Some(RateLimit::new(remaining, reset));
+                self.learned = true;
+                self.tokens = self.tokens.min(remaining as f64);
+            } else {
+                self.tokens = self.tokens.min(remaining as f64);
+            }
+        }
+
+        match header_number::<f64>(headers, THROTTLE_LIMIT_PERCENTAGE) {
+            // Only sent once more than 80% of the limit is used.
+            Some(_) => self.slow_down(0.9),
+            None if status.is_success() => {
+                self.rate_factor = (self.rate_factor + RATE_RECOVERY_STEP).min(1.0);
+            }
+            None => {}
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RateLimiterState {
+    limits: HashMap<RateLimitService, RateLimit>,
+    buckets: HashMap<BucketKey, Bucket>,
+    last_eviction: Instant,
+}
+
+impl RateLimiterState {
+    fn bucket(&mut self, key: &BucketKey, now: Instant) -> &mut Bucket {
+        if now.saturating_duration_since(self.last_eviction) >= BUCKET_EVICTION_INTERVAL {
+            self.buckets.retain(|_, bucket| !bucket.is_idle(now));
+            self.last_eviction = now;
+        }
+
+        let limit = self.limits.get(&key.service).copied();
+        self.buckets
+            .entry(key.clone())
+            .or_insert_with(|| Bucket::new(limit, now))
+    }
+}
+
+/// A client side rate limiter that holds back requests to stay under the throttling
+/// limits of each Microsoft Graph service.
+///
+/// Requests take a token from a bucket that is kept for each [`RateLimitService`] and
+/// tenant, and for each mailbox of Outlook requests. Buckets of services that have a
+/// [`RateLimit`] are refilled at the rate of the limit. The Outlook limit of 10,000
+/// requests per 10 minutes per mailbox is set by default, other services are only limited
+/// when they are given a limit or after they respond with throttling headers.
+///
+/// The limiter adapts to the responses of the service:
+/// - A `429 Too Many Requests` response, or a `503 Service Unavailable` response with a
+///   `Retry-After` header, holds back every request of the bucket until the `Retry-After`
+///   time has passed and halves the rate of the bucket. The rate is recovered step by
+///   step by successful responses.
+/// - The `RateLimit-Remaining` and `RateLimit-Reset` headers spread the remaining requests
+///   evenly until the limit resets, or hold back requests until the reset when none are left.
+/// - The `x-ms-throttle-limit-percentage` header, which is sent once more than 80% of a limit
+///   is used, slows down the bucket.
+///
+/// Clones of the limiter share the same buckets so a limiter can be given to several
+/// clients of the same application. Buckets that have not been used for 10 minutes, and
+/// that are no longer holding back requests, are removed.
+///
+/// ```rust,ignore
+/// use graph_rs_sdk::rate_limit::{RateLimit, RateLimitService, RateLimiter};
+///
+/// let rate_limiter = RateLimiter::new()
+///     .limit(RateLimitService::Identity, RateLimit::per_second(100))
+///     .for_tenant("tenant-id");
+/// let client = GraphClient::from(
+///     GraphClientConfiguration::new()
+///         .access_token("ACCESS_TOKEN")
+///         .rate_limiter(rate_limiter),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    tenant: Option<String>,
+    state: Arc<Mutex<RateLimiterState>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> RateLimiter {
+        let mut limits = HashMap::new();
+        limits.insert(
+            RateLimitService::Outlook,
+            RateLimit::new(10_000, Duration::from_secs(600)),
+        );
+        RateLimiter {
+            tenant: None,
+            state: Arc::new(Mutex::new(RateLimiterState {
+                limits,
+                buckets: HashMap::new(),
+                last_eviction: Instant::now(),
+            })),
+        }
+    }
+
+    /// Set the limit of a service. Buckets that were already used keep their limit.
+    pub fn limit(self, service: RateLimitService, limit: RateLimit) -> RateLimiter {
+        self.lock().limits.insert(service, limit);
+        self
+    }
+
+    /// Remove the limit of a service so that its requests are only held back after
+    /// throttling responses.
+    pub fn without_limit(self, service: RateLimitService) -> RateLimiter {
+        self.lock().limits.remove(&service);
+        self
+    }
+
+    /// A clone of the limiter with separate buckets for the tenant. The limits and
+    /// the buckets of other tenants are shared with this limiter.
+    pub fn for_tenant<T: ToString>(&self, tenant_id: T) -> RateLimiter {
+        RateLimiter {
+            tenant: Some(tenant_id.to_string()),
+            state: self.state.clone(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RateLimiterState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn key(&self, context: &RequestContext, url: &Url) -> BucketKey {
+        let service = context
+            .resource_identity
+            .map(RateLimitService::from)
+            .unwrap_or(RateLimitService::Other);
+        BucketKey {
+            tenant: self.tenant.clone(),
+            service,
+            mailbox: match service {
+                RateLimitService::Outlook => mailbox(url),
+                _ => None,
+            },
+        }
+    }
+
+    async fn acquire(&self, key: &BucketKey) {
+        loop {
+            let now = Instant::now();
+            let wait = self.lock().bucket(key, now).try_acquire(now);
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    fn observe(&self, key: &BucketKey, response: &Response) {
+        let now = Instant::now();
+        self.lock()
+            .bucket(key, now)
+            .observe(response.status(), response.headers(), now);
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new()
+    }
+}
+
+/// Waits for the [`RateLimiter`] before sending each attempt of a request. The inner
+/// service is only polled for readiness once the limiter allows the request so that
+/// waiting requests do not hold on to a concurrency limit permit.
+#[derive(Clone)]
+pub(crate) struct RateLimiterService {
+    inner: GraphService,
+    rate_limiter: RateLimiter,
+    context: RequestContext,
+}
+
+impl RateLimiterService {
+    pub(crate) fn new(
+        inner: GraphService,
+        rate_limiter: RateLimiter,
+        context: RequestContext,
+    ) -> RateLimiterService {
+        RateLimiterService {
+            inner,
+            rate_limiter,
+            context,
+        }
+    }
+}
+
+impl Service<Request> for RateLimiterService {
+    type Response = Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let key = self.rate_limiter.key(&self.context, request.url());
+        let rate_limiter = self.rate_limiter.clone();
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            rate_limiter.acquire(&key).await;
+            let response = inner.oneshot(request).await?;
+            rate_limiter.observe(&key, &response);
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn token_bucket_refills_at_limit_rate() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(Some(RateLimit::new(2, Duration::from_secs(1))), now);
+        assert_eq!(None, bucket.try_acquire(now));
+        assert_eq!(None, bucket.try_acquire(now));
+        assert_eq!(Some(Duration::from_millis(500)), bucket.try_acquire(now));
+        assert_eq!(None, bucket.try_acquire(now + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn throttled_response_blocks_and_slows_down() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(Some(RateLimit::new(10, Duration::from_secs(1))), now);
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        bucket.observe(StatusCode::TOO_MANY_REQUESTS, &headers, now);
+
+        assert_eq!(Some(Duration::from_secs(2)), bucket.try_acquire(now));
+        assert_eq!(0.5, bucket.rate_factor);
+        let after = now + Duration::from_secs(2);
+        assert_eq!(Some(Duration::from_millis(200)), bucket.try_acquire(after));
+
+        bucket.observe(StatusCode::OK, &HeaderMap::new(), after);
+        assert_eq!(0.55, bucket.rate_factor);
+    }
+
+    #[test]
+    fn unlimited_bucket_learns_from_rate_limit_headers() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(None, now);
+        assert_eq!(None, bucket.try_acquire(now));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RATE_LIMIT_REMAINING, HeaderValue::from_static("4"));
+        headers.insert(RATE_LIMIT_RESET, HeaderValue::from_static("2"));
+        bucket.observe(StatusCode::OK, &headers, now);
+        assert_eq!(
+            Some(RateLimit::new(4, Duration::from_secs(2))),
+            bucket.limit
+        );
+        assert_eq!(None, bucket.try_acquire(now));
+        assert_eq!(Some(Duration::from_millis(500)), bucket.try_acquire(now));
+
+        headers.insert(RATE_LIMIT_REMAINING, HeaderValue::from_static("0"));
+        bucket.observe(StatusCode::OK, &headers, now);
+        assert_eq!(Some(Duration::from_secs(2)), bucket.try_acquire(now));
+    }
+
+    #[test]
+    fn idle_buckets_are_evicted() {
+        let rate_limiter = RateLimiter::new();
+        let key = |mailbox: &str| BucketKey {
+            tenant: None,
+            service: RateLimitService::Outlook,
+            mailbox: Some(mailbox.into()),
+        };
+        let now = Instant::now();
+        let mut state = rate_limiter.lock();
+        assert_eq!(None, state.bucket(&key("a"), now).try_acquire(now));
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        state
+            .bucket(&key("b"), now)
+            .observe(StatusCode::TOO_MANY_REQUESTS, &headers, now);
+
+        let later = now + BUCKET_IDLE_TIMEOUT;
+        state.bucket(&key("c"), later);
+        assert!(!state.buckets.contains_key(&key("a")));
+        assert!(state.buckets.contains_key(&key("b")));
+        assert!(state.buckets.contains_key(&key("c")));
+    }
+
+    #[test]
+    fn outlook_buckets_are_per_mailbox() {
+        let rate_limiter = RateLimiter::new().for_tenant("tenant");
+        let context = RequestContext {
+            resource_identity: Some(ResourceIdentity::UsersMessages),
+            path_template: None,
+        };
+        let url = Url::parse("https://graph.microsoft.com/v1.0/users/ID/messages").unwrap();
+        let key = rate_limiter.key(&context, &url);
+        assert_eq!(
+            BucketKey {
+                tenant: Some("tenant".into()),
+                service: RateLimitService::Outlook,
+                mailbox: Some("id".into()),
+            },
+            key
+        );
+
+        let context = RequestContext {
+            resource_identity: Some(ResourceIdentity::Users),
+            path_template: None,
+        };
+        let key = rate_limiter.key(&context, &url);
+        assert_eq!(RateLimitService::Identity, key.service);
+        assert_eq!(None, key.mailbox);
+    }
+}
@@ -1,10 +1,37 @@
 use std::io::ErrorKind;
 
+use crate::odata::{is_advanced_filter, Filter};
 use graph_core::resource::ResourceIdentity;
 use graph_error::{GraphFailure, GraphResult};
-use http::{HeaderMap, Method};
+use http::header::HeaderName;
+use http::{HeaderMap, HeaderValue, Method};
 use url::Url;
 
+static CONSISTENCY_LEVEL: HeaderName = HeaderName::from_static("consistencylevel");
+pub(crate) static CLIENT_REQUEST_ID: HeaderName = HeaderName::from_static("client-request-id");
+
+/// A new random `client-request-id` header value.
+pub(crate) fn new_client_request_id() -> HeaderValue {
+    HeaderValue::from_str(&uuid::Uuid::new_v4().to_string())
+        .expect("a uuid is a valid header value")
+}
+
+/// The `$filter` of a request that was set with a typed [`Filter`].
+#[derive(Clone, Debug)]
+pub(crate) struct TypedFilter {
+    pub(crate) filter: String,
+    pub(crate) is_advanced_query: bool,
+}
+
+impl From<&Filter> for TypedFilter {
+    fn from(filter: &Filter) -> Self {
+        TypedFilter {
+            filter: filter.to_string(),
+            is_advanced_query: filter.is_advanced_query(),
+        }
+    }
+}
+
 /// Provides the necessary components for building a request.
 #[derive(Clone, Debug)]
 pub struct RequestComponents {
@@ -12,6 +39,9 @@ pub struct RequestComponents {
     pub url: Url,
     pub method: Method,
     pub headers: HeaderMap,
+    /// The path of the request with ids left as handlebars templates, such as
+    /// `/users/{{RID}}/messages/{{id}}`. Used to group requests in telemetry.
+    pub path_template: Option<String>,
 }
 
 impl AsRef<Url> for RequestComponents {
@@ -39,9 +69,15 @@ impl RequestComponents {
             url,
             method,
             headers: HeaderMap::with_capacity(2),
+            path_template: None,
         }
     }
 
+    pub fn with_path_template(mut self, path_template: String) -> RequestComponents {
+        self.path_template = Some(path_template);
+        self
+    }
+
     pub fn query<T: serde::Serialize + ?Sized>(&mut self, query: &T) -> GraphResult<()> {
         let mut pairs = self.url.query_pairs_mut();
         let serializer = serde_urlencoded::Serializer::new(&mut pairs);
@@ -55,6 +91,134 @@ impl RequestComponents {
 
         Ok(())
     }
+
+    /// Adds a `client-request-id` header unless the caller has set one. The header is
+    /// added when the request handler is created so that errors before the request is
+    /// sent include it and retries of the request reuse it.
+    pub(crate) fn client_request_id(&mut self) {
+        self.headers
+            .entry(&CLIENT_REQUEST_ID)
+            .or_insert_with(new_client_request_id);
+    }
+
+    /// Returns true if the resource is a directory object such as users, groups,
+    /// applications, service principals, devices, and their directory object relationships.
+    /// Only directory objects support the advanced query capabilities of the Api.
+    pub fn is_directory_object(&self) -> bool {
+        matches!(
+            self.resource_identity,
+            ResourceIdentity::AdministrativeUnits
+                | ResourceIdentity::Applications
+                | ResourceIdentity::CreatedObjects
+                | ResourceIdentity::Devices
+                | ResourceIdentity::DevicesRegisteredOwners
+                | ResourceIdentity::DevicesRegisteredUsers
+                | ResourceIdentity::DirectReports
+                | ResourceIdentity::DirectoryMembers
+                | ResourceIdentity::DirectoryObjects
+                | ResourceIdentity::DirectoryRoleTemplates
+                | ResourceIdentity::DirectoryRoles
+                | ResourceIdentity::Groups
+                | ResourceIdentity::GroupsOwners
+                | ResourceIdentity::Me
+                | ResourceIdentity::MemberOf
+                | ResourceIdentity::MembersWithLicenseErrors
+                | ResourceIdentity::OrgContacts
+                | ResourceIdentity::OwnedDevices
+                | ResourceIdentity::OwnedObjects
+                | ResourceIdentity::RegisteredDevices
+                | ResourceIdentity::ServicePrincipals
+                | ResourceIdentity::ServicePrincipalsOwners
+                | ResourceIdentity::TransitiveMemberOf
+                | ResourceIdentity::TransitiveMembers
+                | ResourceIdentity::Users
+        )
+    }
+
+    /// Returns true if the query of the request uses advanced query capabilities:
+    /// `$count=true`, `$search`, or a `$filter` using `endswith`, `ne`, `not`, or `$count`.
+    /// Advanced queries on directory objects require the `ConsistencyLevel: eventual`
+    /// header and the `$count=true` query parameter.
+    ///
+    /// See [Advanced query capabilities on Azure AD directory objects](https://learn.microsoft.com/en-us/graph/aad-advanced-queries)
+    pub fn is_advanced_query(&self) -> bool {
+        self.uses_advanced_query(None)
+    }
+
+    /// A `$filter` that was set with a typed [`Filter`](crate::odata::Filter) uses
+    /// [`Filter::is_advanced_query`](crate::odata::Filter::is_advanced_query) and only
+    /// raw string filters are checked with their tokens.
+    fn uses_advanced_query(&self, typed_filter: Option<&TypedFilter>) -> bool {
+        self.url
+            .query_pairs()
+            .any(|(key, value)| match key.as_ref() {
+                "$count" => value.eq_ignore_ascii_case("true"),
+                "$search" => true,
+                "$filter" => match typed_filter {
+                    Some(typed_filter) if typed_filter.filter == value => {
+                        typed_filter.is_advanced_query
+                    }
+                    _ => is_advanced_filter(value.as_ref()),
+                },
+                _ => false,
+            })
+    }
+
+    /// Adds the `ConsistencyLevel: eventual` header and the `$count=true` query parameter
+    /// to advanced queries on directory objects when they are missing. If strict is true
+    /// a [`GraphFailure::PreFlightError`] is returned instead of changing the request.
+    pub(crate) fn advanced_query(
+        &mut self,
+        strict: bool,
+        typed_filter: Option<&TypedFilter>,
+    ) -> GraphResult<()> {
+        if !self.is_directory_object() || !self.uses_advanced_query(typed_filter) {
+            return Ok(());
+        }
+
+        let has_consistency_level = self
+            .headers
+            .get(&CONSISTENCY_LEVEL)
+            .map(|value| value.as_bytes().eq_ignore_ascii_case(b"eventual"))
+            .unwrap_or_default();
+        let has_count = self.url.query_pairs().any(|(key, _)| key == "$count");
+
+        if has_consistency_level && has_count {
+            return Ok(());
+        }
+
+        if strict {
+            let mut missing = Vec::new();
+            if !has_consistency_level {
+                missing.push("the ConsistencyLevel: eventual header");
+            }
+            if !has_count {
+                missing.push("the $count=true query parameter");
+            }
+
+            return Err(GraphFailure::PreFlightError {
+                url: Some(self.url.clone()),
+                headers: Some(self.headers.clone()),
+                error: None,
+                message: format!(
+                    "The query uses advanced query capabilities of {:?} which requires {}",
+                    self.resource_identity,
+                    missing.join(" and ")
+                ),
+            });
+        }
+
+        if !has_consistency_level {
+            self.headers.insert(
+                CONSISTENCY_LEVEL.clone(),
+                HeaderValue::from_static("eventual"),
+            );
+        }
+        if !has_count {
+            self.url.query_pairs_mut().append_pair("$count", "true");
+        }
+        Ok(())
+    }
 }
 
 impl TryFrom<(ResourceIdentity, reqwest::Method, GraphResult<Url>)> for RequestComponents {
@@ -72,6 +236,100 @@ impl Default for RequestComponents {
             url: Url::parse("https://graph.microsoft.com/v1.0").unwrap(),
             method: Default::default(),
             headers: Default::default(),
+            path_template: None,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::odata::Literal;
+
+    fn request_components(resource_identity: ResourceIdentity, url: &str) -> RequestComponents {
+        RequestComponents::new(resource_identity, Url::parse(url).unwrap(), Method::GET)
+    }
+
+    #[test]
+    fn advanced_query_adds_consistency_level_and_count() {
+        let mut components = request_components(
+            ResourceIdentity::Users,
+            "https://graph.microsoft.com/v1.0/users?$filter=endswith(mail,'@contoso.com')",
+        );
+        components.advanced_query(false, None).unwrap();
+
+        assert_eq!(
+            Some(&HeaderValue::from_static("eventual")),
+            components.headers.get("ConsistencyLevel")
+        );
+        assert!(components
+            .url
+            .query_pairs()
+            .any(|(key, value)| key == "$count" && value == "true"));
+    }
+
+    #[test]
+    fn advanced_query_ignores_other_requests() {
+        let mut components = request_components(
+            ResourceIdentity::Users,
+            "https://graph.microsoft.com/v1.0/users?$filter=startswith(displayName,'a')",
+        );
+        components.advanced_query(true, None).unwrap();
+        assert!(components.headers.is_empty());
+
+        let mut components = request_components(
+            ResourceIdentity::Drive,
+            "https://graph.microsoft.com/v1.0/drive/root/children?$count=true",
+        );
+        components.advanced_query(true, None).unwrap();
+        assert!(components.headers.is_empty());
+    }
+
+    #[test]
+    fn advanced_query_strict() {
+        let mut components = request_components(
+            ResourceIdentity::Groups,
+            "https://graph.microsoft.com/v1.0/groups?$search=%22displayName:Video%22",
+        );
+        let err = components.advanced_query(true, None).unwrap_err();
+        assert!(matches!(err, GraphFailure::PreFlightError { .. }));
+        assert!(components.headers.is_empty());
+
+        components.headers.insert(
+            CONSISTENCY_LEVEL.clone(),
+            HeaderValue::from_static("eventual"),
+        );
+        components
+            .url
+            .query_pairs_mut()
+            .append_pair("$count", "true");
+        components.advanced_query(true, None).unwrap();
+    }
+
+    #[test]
+    fn advanced_query_uses_typed_filter() {
+        let filter = Filter::ne("companyName", Literal::Null);
+        let mut components = request_components(
+            ResourceIdentity::Users,
+            "https://graph.microsoft.com/v1.0/users",
+        );
+        components
+            .url
+            .query_pairs_mut()
+            .append_pair("$filter", &filter.to_string());
+
+        let typed_filter = TypedFilter {
+            filter: filter.to_string(),
+            is_advanced_query: false,
+        };
+        components
+            .advanced_query(true, Some(&typed_filter))
+            .unwrap();
+        assert!(components.headers.is_empty());
+
+        let err = components
+            .advanced_query(true, Some(&TypedFilter::from(&filter)))
+            .unwrap_err();
+        assert!(matches!(err, GraphFailure::PreFlightError { .. }));
+    }
+}
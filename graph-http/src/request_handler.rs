@@ -1,19 +1,24 @@
 use crate::blocking::BlockingRequestHandler;
 use crate::internal::{
-    BodyRead, Client, GraphClientConfiguration, HttpResponseBuilderExt, ODataNextLink, ODataQuery,
-    RequestComponents,
+    new_client_request_id, send_with_claims_challenge, BodyRead, Client, GraphClientConfiguration,
+    GraphService, HttpResponseBuilderExt, LongRunningOperation, ODataNextLink, ODataQuery,
+    RequestComponents, RequestContext, TypedFilter, CLIENT_REQUEST_ID,
 };
+use crate::odata::Filter;
 use async_stream::try_stream;
 use futures::Stream;
-use graph_error::{AuthExecutionResult, ErrorMessage, GraphFailure, GraphResult};
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
-use reqwest::{Request, Response};
+use graph_core::identity::{AuthScheme, ClientApplication};
+use graph_error::{
+    AuthExecutionResult, ErrorMessage, GraphApiError, GraphFailure, GraphResult,
+    ResponseDiagnostics,
+};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, IF_MATCH, IF_NONE_MATCH,
+};
 use serde::de::DeserializeOwned;
 use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::time::Duration;
-use tower::util::BoxCloneService;
-use tower::{Service, ServiceExt};
 use url::Url;
 
 pub struct RequestHandler {
@@ -22,8 +27,8 @@ pub struct RequestHandler {
     pub(crate) error: Option<GraphFailure>,
     pub(crate) body: Option<BodyRead>,
     pub(crate) client_builder: GraphClientConfiguration,
-    pub(crate) service:
-        BoxCloneService<Request, Response, Box<dyn std::error::Error + Send + Sync>>,
+    pub(crate) service: GraphService,
+    pub(crate) typed_filter: Option<TypedFilter>,
 }
 
 impl RequestHandler {
@@ -33,11 +38,14 @@ impl RequestHandler {
         err: Option<GraphFailure>,
         body: Option<BodyRead>,
     ) -> RequestHandler {
-        let service = inner.builder.build_tower_service(&inner.inner);
+        let service = inner
+            .builder
+            .build_tower_service(&inner.inner, RequestContext::from(&request_components));
         let client_builder = inner.builder.clone();
         let mut original_headers = inner.headers.clone();
         original_headers.extend(request_components.headers.clone());
         request_components.headers = original_headers;
+        request_components.client_request_id();
 
         let mut error = None;
         if let Some(err) = err {
@@ -57,16 +65,30 @@ impl RequestHandler {
             body,
             client_builder,
             service,
+            typed_filter: None,
         }
     }
 
+    /// # Panics
+    /// Panics if the blocking http client cannot be built from the configuration of the
+    /// client. Use [`RequestHandler::try_into_blocking`] to return an error instead.
     pub fn into_blocking(self) -> BlockingRequestHandler {
-        BlockingRequestHandler::new(
-            self.client_builder.build_blocking(),
+        self.try_into_blocking()
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as [`RequestHandler::into_blocking`] but returns a
+    /// [`GraphFailure::Configuration`] error when the blocking http client cannot be
+    /// built from the configuration of the client.
+    pub fn try_into_blocking(self) -> GraphResult<BlockingRequestHandler> {
+        let mut handler = BlockingRequestHandler::new(
+            self.client_builder.build_blocking_client()?,
             self.request_components,
             self.error,
             self.body,
-        )
+        );
+        handler.typed_filter = self.typed_filter;
+        Ok(handler)
     }
 
     /// Returns true if any errors occurred prior to sending the request.
@@ -155,6 +177,58 @@ impl RequestHandler {
         self.request_components.as_mut()
     }
 
+    /// Only apply the request if the ETag of the resource matches `etag`, or if the
+    /// resource exists when `etag` is `*`. Planner requires `If-Match` for updates and
+    /// deletes. A resource that changed since `etag` was read fails the request with
+    /// `412 Precondition Failed`, returned as [`GraphFailure::PreconditionFailed`] by
+    /// `send_checked`.
+    #[inline]
+    pub fn if_match<T: AsRef<str>>(self, etag: T) -> Self {
+        self.conditional_header(IF_MATCH, etag.as_ref())
+    }
+
+    /// Only apply the request if the ETag of the resource does not match `etag`. A `GET`
+    /// for a resource that was not modified returns `304 Not Modified` without a body.
+    #[inline]
+    pub fn if_none_match<T: AsRef<str>>(self, etag: T) -> Self {
+        self.conditional_header(IF_NONE_MATCH, etag.as_ref())
+    }
+
+    fn conditional_header(mut self, header_name: HeaderName, etag: &str) -> Self {
+        match HeaderValue::from_str(etag) {
+            Ok(header_value) => {
+                self.request_components
+                    .headers
+                    .insert(header_name, header_value);
+            }
+            Err(err) => {
+                if self.error.is_none() {
+                    self.error = Some(GraphFailure::from(http::Error::from(err)));
+                }
+            }
+        }
+        self
+    }
+
+    /// Set the `client-request-id` header of the request. By default a random UUID is
+    /// used. The same id is sent when the request is retried.
+    #[inline]
+    pub fn client_request_id<T: AsRef<str>>(mut self, client_request_id: T) -> Self {
+        match HeaderValue::from_str(client_request_id.as_ref()) {
+            Ok(header_value) => {
+                self.request_components
+                    .headers
+                    .insert(CLIENT_REQUEST_ID.clone(), header_value);
+            }
+            Err(err) => {
+                if self.error.is_none() {
+                    self.error = Some(GraphFailure::from(http::Error::from(err)));
+                }
+            }
+        }
+        self
+    }
+
     pub fn paging(self) -> Paging {
         Paging(self)
     }
@@ -167,6 +241,15 @@ impl RequestHandler {
             .client_application
             .get_token_silent_async()
             .await?;
+        let authorization = self
+            .inner
+            .client_application
+            .auth_scheme()
+            .authorization_header(
+                access_token.as_str(),
+                &self.request_components.method,
+                &self.request_components.url,
+            )?;
 
         let request_builder = self
             .inner
@@ -175,7 +258,7 @@ impl RequestHandler {
                 self.request_components.method.clone(),
                 self.request_components.url.clone(),
             )
-            .bearer_auth(access_token.as_str())
+            .header(AUTHORIZATION, authorization)
             .headers(self.request_components.headers.clone());
 
         if let Some(body) = self.body.take() {
@@ -206,6 +289,15 @@ impl RequestHandler {
             .client_application
             .get_token_silent_async()
             .await?;
+        let authorization = self
+            .inner
+            .client_application
+            .auth_scheme()
+            .authorization_header(
+                access_token.as_str(),
+                &self.request_components.method,
+                &self.request_components.url,
+            )?;
 
         let request_builder = self
             .inner
@@ -214,7 +306,7 @@ impl RequestHandler {
                 self.request_components.method.clone(),
                 self.request_components.url.clone(),
             )
-            .bearer_auth(access_token.as_str())
+            .header(AUTHORIZATION, authorization)
             .headers(self.request_components.headers.clone());
 
         if let Some(body) = self.body.take() {
@@ -243,21 +335,104 @@ impl RequestHandler {
         if let Some(err) = self.error {
             return Err(err);
         }
+        self.advanced_query()?;
         self.default_request_builder().await
     }
 
+    /// Sends the request. If the resource responds with a claims challenge, such as when
+    /// Continuous Access Evaluation (CAE) revokes a token, a new access token is requested
+    /// with the claims from the challenge and the request is retried once.
     #[inline]
-    pub async fn send(self) -> GraphResult<reqwest::Response> {
+    pub async fn send(mut self) -> GraphResult<reqwest::Response> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.advanced_query()?;
+
         let mut service = self.service.clone();
-        let request_builder = self.build().await?;
-        let request = request_builder.build()?;
-        service
-            .ready()
-            .await
-            .map_err(GraphFailure::from)?
-            .call(request)
-            .await
-            .map_err(GraphFailure::from)
+        let (mut access_token, request) = self.default_request_builder_with_token().await?;
+        send_with_claims_challenge(
+            &mut self.inner.client_application,
+            &mut service,
+            request.build()?,
+            &mut access_token,
+        )
+        .await
+    }
+
+    /// Sends the request and returns an error for responses that do not have a success
+    /// status code. The error is a [`GraphFailure::GraphApiError`] with the status, the
+    /// parsed error resource, and the diagnostics of the response, or a
+    /// [`GraphFailure::PreconditionFailed`] when the `If-Match` or `If-None-Match` header
+    /// of the request did not match the ETag of the resource.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// match client.user("id").get_user().send_checked().await {
+    ///     Ok(response) => println!("{:#?}", response.json::<serde_json::Value>().await?),
+    ///     Err(GraphFailure::GraphApiError(error))
+    ///         if error.has_code(&GraphErrorCode::RequestResourceNotFound) =>
+    ///     {
+    ///         println!("user does not exist");
+    ///     }
+    ///     Err(err) => return Err(err),
+    /// }
+    /// ```
+    pub async fn send_checked(self) -> GraphResult<reqwest::Response> {
+        let response = self.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        Err(GraphApiError::from_parts(status, &headers, &body).into())
+    }
+
+    /// Sends a request that starts a long running operation, such as copying a drive item
+    /// or creating a team, and returns a [`LongRunningOperation`] that polls the monitor
+    /// url of the `202 Accepted` response. Await the operation to wait until it completes.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let status = client
+    ///     .drive("DRIVE_ID")
+    ///     .item("ITEM_ID")
+    ///     .copy(&serde_json::json!({ "name": "copy.txt" }))
+    ///     .send_long_running()
+    ///     .await?
+    ///     .await?;
+    ///
+    /// println!("{:?}", status.resource_id);
+    /// ```
+    pub async fn send_long_running(self) -> GraphResult<LongRunningOperation> {
+        let client = self.inner.clone();
+        let service = self.service.clone();
+        let url = self.request_components.url.clone();
+        let response = self.send_checked().await?;
+        LongRunningOperation::from_response(client, service, &url, &response)
+    }
+
+    /// Adds the `ConsistencyLevel` header and `$count=true` query parameter required by
+    /// advanced queries on directory objects, or returns an error in strict mode.
+    fn advanced_query(&mut self) -> GraphResult<()> {
+        let strict = self.client_builder.is_strict_advanced_queries();
+        self.request_components
+            .advanced_query(strict, self.typed_filter.as_ref())
+    }
+
+    /// Sets the `ConsistencyLevel: eventual` header for the query methods that use advanced
+    /// query capabilities. Only directory objects support advanced queries. In strict mode
+    /// the header is not set so that a missing header is returned as an error.
+    fn advanced_query_consistency_level(self) -> Self {
+        if self.request_components.is_directory_object()
+            && !self.client_builder.is_strict_advanced_queries()
+        {
+            self.consistency_level_eventual()
+        } else {
+            self
+        }
     }
 }
 
@@ -265,6 +440,37 @@ impl ODataQuery for RequestHandler {
     fn append_query_pair<KV: AsRef<str>>(self, key: KV, value: KV) -> Self {
         self.append_query_pair(key.as_ref(), value.as_ref())
     }
+
+    fn consistency_level_eventual(self) -> Self {
+        self.header(
+            HeaderName::from_static("consistencylevel"),
+            HeaderValue::from_static("eventual"),
+        )
+    }
+
+    fn count<S: AsRef<str>>(self, value: S) -> Self {
+        match value.as_ref().eq("true") {
+            true => self.advanced_query_consistency_level(),
+            false => self,
+        }
+        .append_query_pair("$count", value.as_ref())
+    }
+
+    fn filter_by(self, filter: Filter) -> Self {
+        let typed_filter = TypedFilter::from(&filter);
+        let mut handler = match typed_filter.is_advanced_query {
+            true => self.advanced_query_consistency_level(),
+            false => self,
+        }
+        .append_query_pair("$filter", typed_filter.filter.as_str());
+        handler.typed_filter = Some(typed_filter);
+        handler
+    }
+
+    fn search<S: AsRef<str>>(self, value: S) -> Self {
+        self.advanced_query_consistency_level()
+            .append_query_pair("$search", value.as_ref())
+    }
 }
 
 impl AsRef<Url> for RequestHandler {
@@ -285,6 +491,25 @@ pub type PagingResult<T> = GraphResult<PagingResponse<T>>;
 pub struct Paging(RequestHandler);
 
 impl Paging {
+    fn authorization(
+        auth_scheme: &AuthScheme,
+        access_token: &str,
+        next_link: &str,
+    ) -> GraphResult<String> {
+        let url = Url::parse(next_link)?;
+        Ok(auth_scheme.authorization_header(access_token, &http::Method::GET, &url)?)
+    }
+
+    async fn call(
+        client_application: &mut Box<dyn ClientApplication>,
+        service: &mut GraphService,
+        request: reqwest::RequestBuilder,
+        access_token: &mut String,
+    ) -> GraphResult<reqwest::Response> {
+        send_with_claims_challenge(client_application, service, request.build()?, access_token)
+            .await
+    }
+
     async fn http_response<T: DeserializeOwned>(
         response: reqwest::Response,
     ) -> GraphResult<(Option<String>, PagingResponse<T>)> {
@@ -296,8 +521,11 @@ impl Paging {
         let body: serde_json::Value = response.json().await?;
         let next_link = body.odata_next_link();
         let json = body.clone();
-        let body_result: Result<T, ErrorMessage> = serde_json::from_value(body)
-            .map_err(|_| serde_json::from_value(json.clone()).unwrap_or(ErrorMessage::default()));
+        let body_result: Result<T, ErrorMessage> = serde_json::from_value(body).map_err(|_| {
+            serde_json::from_value::<ErrorMessage>(json.clone())
+                .unwrap_or_default()
+                .with_diagnostics(ResponseDiagnostics::from(&headers))
+        });
 
         let mut builder = http::Response::builder()
             .url(url)
@@ -359,9 +587,18 @@ impl Paging {
         if let Some(err) = self.0.error {
             return Err(err);
         }
-
-        let (access_token, request) = self.0.default_request_builder_with_token().await?;
-        let response = request.send().await?;
+        self.0.advanced_query()?;
+
+        let mut service = self.0.service.clone();
+        let mut client_application = self.0.inner.client_application.clone();
+        let (mut access_token, request) = self.0.default_request_builder_with_token().await?;
+        let response = Paging::call(
+            &mut client_application,
+            &mut service,
+            request,
+            &mut access_token,
+        )
+        .await?;
 
         let (next, http_response) = Paging::http_response(response).await?;
         let mut next_link = next;
@@ -369,12 +606,19 @@ impl Paging {
         vec.push_back(http_response);
 
         let client = self.0.inner.inner.clone();
+        let auth_scheme = client_application.auth_scheme();
         while let Some(next) = next_link {
-            let response = client
-                .get(next)
-                .bearer_auth(access_token.as_str())
-                .send()
-                .await?;
+            let authorization = Paging::authorization(&auth_scheme, &access_token, &next)?;
+            let response = Paging::call(
+                &mut client_application,
+                &mut service,
+                client
+                    .get(next)
+                    .header(AUTHORIZATION, authorization)
+                    .header(&CLIENT_REQUEST_ID, new_client_request_id()),
+                &mut access_token,
+            )
+            .await?;
 
             let (next, http_response) = Paging::http_response(response).await?;
 
@@ -389,20 +633,22 @@ impl Paging {
         mut self,
     ) -> impl Stream<Item = PagingResult<T>> + 'a {
         try_stream! {
-            let (access_token, request) = self.0.default_request_builder_with_token().await?;
-            let response = request.send().await?;
+            let mut service = self.0.service.clone();
+            let mut client_application = self.0.inner.client_application.clone();
+            let (mut access_token, request) = self.0.default_request_builder_with_token().await?;
+            let response = Paging::call(&mut client_application, &mut service, request, &mut access_token).await?;
             let (next, http_response) = Paging::http_response(response).await?;
             let mut next_link = next;
             yield http_response;
 
+            let auth_scheme = client_application.auth_scheme();
             while let Some(url) = next_link {
-                let response = self.0
-                    .inner
-                    .inner
+                let authorization = Paging::authorization(&auth_scheme, &access_token, &url)?;
+                let request = self.0.inner.inner
                     .get(url)
-                    .bearer_auth(access_token.as_str())
-                    .send()
-                    .await?;
+                    .header(AUTHORIZATION, authorization)
+                    .header(&CLIENT_REQUEST_ID, new_client_request_id());
+                let response = Paging::call(&mut client_application, &mut service, request, &mut access_token).await?;
                 let (next, http_response) = Paging::http_response(response).await?;
                 next_link = next;
                 yield http_response;
@@ -432,6 +678,7 @@ impl Paging {
         if let Some(err) = self.0.error.take() {
             return Err(err);
         }
+        self.0.advanced_query()?;
 
         Ok(Box::pin(self.try_stream()))
     }
@@ -502,10 +749,23 @@ impl Paging {
 
     async fn send_channel_request<T: DeserializeOwned>(
         client: &reqwest::Client,
+        client_application: &mut Box<dyn ClientApplication>,
+        service: &mut GraphService,
         url: &str,
-        access_token: &str,
+        access_token: &mut String,
+        auth_scheme: &AuthScheme,
     ) -> GraphResult<(Option<String>, PagingResponse<T>)> {
-        let response = client.get(url).bearer_auth(access_token).send().await?;
+        let authorization = Paging::authorization(auth_scheme, access_token, url)?;
+        let response = Paging::call(
+            client_application,
+            service,
+            client
+                .get(url)
+                .header(AUTHORIZATION, authorization)
+                .header(&CLIENT_REQUEST_ID, new_client_request_id()),
+            access_token,
+        )
+        .await?;
 
         Paging::http_response(response).await
     }
@@ -543,9 +803,18 @@ impl Paging {
         timeout: Duration,
     ) -> GraphResult<tokio::sync::mpsc::Receiver<PagingResult<T>>> {
         let (sender, receiver) = tokio::sync::mpsc::channel(buffer);
-
-        let (access_token, request) = self.0.default_request_builder_with_token().await?;
-        let response = request.send().await?;
+        self.0.advanced_query()?;
+
+        let mut service = self.0.service.clone();
+        let mut client_application = self.0.inner.client_application.clone();
+        let (mut access_token, request) = self.0.default_request_builder_with_token().await?;
+        let response = Paging::call(
+            &mut client_application,
+            &mut service,
+            request,
+            &mut access_token,
+        )
+        .await?;
         let (next, http_response) = Paging::http_response(response).await?;
         let mut next_link = next;
         sender
@@ -554,11 +823,18 @@ impl Paging {
             .unwrap();
 
         let client = self.0.inner.inner.clone();
+        let auth_scheme = client_application.auth_scheme();
         tokio::spawn(async move {
             while let Some(next) = next_link {
-                let result =
-                    Paging::send_channel_request(&client, next.as_str(), access_token.as_str())
-                        .await;
+                let result = Paging::send_channel_request(
+                    &client,
+                    &mut client_application,
+                    &mut service,
+                    next.as_str(),
+                    &mut access_token,
+                    &auth_scheme,
+                )
+                .await;
 
                 match result {
                     Ok((next, response)) => {
@@ -11,6 +11,9 @@ pub struct ResourceConfig {
     pub resource_identity: ResourceIdentity,
     pub url: Url,
     pub resource_identity_id: Option<String>,
+    /// The path of the parent resources in the url with their ids replaced
+    /// by the `{{RID}}` template, such as `/users/{{RID}}`.
+    pub path_template: String,
 }
 
 impl ResourceConfig {
@@ -23,6 +26,7 @@ impl ResourceConfig {
             resource_identity,
             url,
             resource_identity_id,
+            path_template: String::new(),
         }
     }
 }
@@ -37,6 +41,27 @@ impl ResourceConfig {
             p.extend(path);
         }
     }
+
+    /// Extends the url with the path of the current resource, and its id if one is set,
+    /// so that a child resource can be linked to it. The id is kept out of the path
+    /// template.
+    pub fn extend_resource_path<S: AsRef<str>>(&mut self, path: S) {
+        let path = path.as_ref();
+        if let Some(resource_identity_id) = self.resource_identity_id.take() {
+            self.extend_path(&[path, resource_identity_id.as_str()]);
+            self.path_template.push_str(&format!("/{path}/{{{{RID}}}}"));
+        } else {
+            self.extend_path(&[path]);
+            self.path_template.push_str(&format!("/{path}"));
+        }
+    }
+
+    /// The path template of a request to this resource. The template is the handlebars
+    /// template of the request path appended to the path of the parent resources, such as
+    /// `/users/{{RID}}/messages/{{id}}`.
+    pub fn request_path_template(&self, template: &str) -> String {
+        format!("{}{}", self.path_template, template)
+    }
 }
 
 impl AsRef<Url> for ResourceConfig {
@@ -50,3 +75,30 @@ impl AsMut<Url> for ResourceConfig {
         &mut self.url
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extend_resource_path_templates_ids() {
+        let mut resource_config = ResourceConfig::new(
+            ResourceIdentity::Users,
+            Url::parse("https://graph.microsoft.com/v1.0").unwrap(),
+            Some("user-id".into()),
+        );
+        resource_config.extend_resource_path("users");
+        resource_config.resource_identity = ResourceIdentity::MailFolders;
+        resource_config.extend_resource_path("mailFolders");
+
+        assert_eq!(
+            "https://graph.microsoft.com/v1.0/users/user-id/mailFolders",
+            resource_config.url.as_str()
+        );
+        assert_eq!("/users/{{RID}}/mailFolders", resource_config.path_template);
+        assert_eq!(
+            "/users/{{RID}}/mailFolders/{{id}}",
+            resource_config.request_path_template("/{{id}}")
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use crate::internal::{GraphService, RequestComponents};
+use graph_core::resource::ResourceIdentity;
+use http::HeaderMap;
+use reqwest::{Request, Response};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Service, ServiceExt};
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Counter of completed requests.
+pub const GRAPH_REQUESTS_TOTAL: &str = "graph_requests_total";
+/// Histogram of the latency of requests in seconds, including retries and throttling waits.
+pub const GRAPH_REQUEST_DURATION_SECONDS: &str = "graph_request_duration_seconds";
+/// Counter of retried attempts of requests.
+pub const GRAPH_REQUEST_RETRIES_TOTAL: &str = "graph_request_retries_total";
+/// Counter of `429 Too Many Requests` responses, including responses that were retried.
+pub const GRAPH_THROTTLED_RESPONSES_TOTAL: &str = "graph_throttled_responses_total";
+/// Histogram of the time in seconds spent waiting on `Retry-After` headers before retrying.
+pub const GRAPH_THROTTLE_WAIT_SECONDS: &str = "graph_throttle_wait_seconds";
+
+static REQUEST_ID: &str = "request-id";
+static CLIENT_REQUEST_ID: &str = "client-request-id";
+
+/// Tracing spans and, with the `metrics` feature, metrics of the requests sent by the client.
+#[derive(Clone, Default)]
+pub(crate) struct Telemetry {
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: bool,
+}
+
+/// The resource and path template of the request that is sent through a service.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RequestContext {
+    pub(crate) resource_identity: Option<ResourceIdentity>,
+    pub(crate) path_template: Option<String>,
+}
+
+impl From<&RequestComponents> for RequestContext {
+    fn from(value: &RequestComponents) -> Self {
+        RequestContext {
+            resource_identity: Some(value.resource_identity),
+            path_template: value.path_template.clone(),
+        }
+    }
+}
+
+/// Attempts and throttling of a single request, shared with the retry policies
+/// through a task local.
+#[derive(Default)]
+struct RequestStats {
+    attempts: AtomicUsize,
+    throttled: AtomicUsize,
+    throttle_wait: Mutex<Duration>,
+}
+
+tokio::task_local! {
+    static REQUEST_STATS: Arc<RequestStats>;
+}
+
+/// Adds the time spent waiting on a `Retry-After` header to the current request.
+pub(crate) fn record_throttle_wait(duration: Duration) {
+    let _ = REQUEST_STATS.try_with(|stats| {
+        let mut throttle_wait = stats
+            .throttle_wait
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *throttle_wait += duration;
+    });
+}
+
+/// Counts each attempt of a request and the attempts that were throttled. Sits below
+/// the retry layers so that every retry goes through it.
+pub(crate) fn count_attempts(service: GraphService) -> GraphService {
+    service
+        .map_request(|request: Request| {
+            let _ = REQUEST_STATS.try_with(|stats| stats.attempts.fetch_add(1, Ordering::Relaxed));
+            request
+        })
+        .map_response(|response: Response| {
+            if response.status() == http::StatusCode::TOO_MANY_REQUESTS {
+                let _ =
+                    REQUEST_STATS.try_with(|stats| stats.throttled.fetch_add(1, Ordering::Relaxed));
+            }
+            response
+        })
+        .boxed_clone()
+}
+
+/// Opens a `graph.request` span for each request and records the request metrics.
+#[derive(Clone)]
+pub(crate) struct TelemetryService {
+    inner: GraphService,
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    telemetry: Telemetry,
+    context: RequestContext,
+}
+
+impl TelemetryService {
+    pub(crate) fn new(
+        inner: GraphService,
+        telemetry: Telemetry,
+        context: RequestContext,
+    ) -> TelemetryService {
+        TelemetryService {
+            inner,
+            telemetry,
+            context,
+        }
+    }
+}
+
+impl Service<Request> for TelemetryService {
+    type Response = Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let method = request.method().to_string();
+        let path_template = self
+            .context
+            .path_template
+            .clone()
+            .unwrap_or_else(|| String::from("unknown"));
+        let resource = self
+            .context
+            .resource_identity
+            .map(|resource_identity| resource_identity.to_string())
+            .unwrap_or_else(|| String::from("unknown"));
+        let client_request_id = header_str(request.headers(), CLIENT_REQUEST_ID);
+
+        let span = tracing::info_span!(
+            "graph.request",
+            otel.name = %format!("{method} {path_template}"),
+            otel.kind = "client",
+            otel.status_code = Empty,
+            http.request.method = %method,
+            url.template = %path_template,
+            server.address = request.url().host_str().unwrap_or_default(),
+            graph.resource_identity = %resource,
+            http.response.status_code = Empty,
+            graph.request_id = Empty,
+            graph.client_request_id = client_request_id.as_deref(),
+            graph.retry_count = Empty,
+            graph.throttle_wait_ms = Empty,
+            graph.latency_ms = Empty,
+            error.message = Empty,
+        );
+
+        let stats = Arc::new(RequestStats::default());
+        let start = Instant::now();
+        let future = REQUEST_STATS.sync_scope(stats.clone(), || {
+            let _enter = span.enter();
+            self.inner.call(request)
+        });
+
+        #[cfg(feature = "metrics")]
+        let record_metrics = self.telemetry.metrics;
+        Box::pin(async move {
+            let result = REQUEST_STATS
+                .scope(stats.clone(), future)
+                .instrument(span.clone())
+                .await;
+
+            #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+            let status = record_span(&span, &result, client_request_id.is_none());
+            let latency = start.elapsed();
+            let retries = stats.attempts.load(Ordering::Relaxed).saturating_sub(1);
+            let throttle_wait = *stats
+                .throttle_wait
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            span.record("graph.retry_count", retries);
+            span.record("graph.throttle_wait_ms", throttle_wait.as_millis() as u64);
+            span.record("graph.latency_ms", latency.as_millis() as u64);
+
+            #[cfg(feature = "metrics")]
+            if record_metrics {
+                let labels = [
+                    ("method", method),
+                    ("path_template", path_template),
+                    ("resource", resource),
+                    ("status", status),
+                ];
+                metrics::counter!(GRAPH_REQUESTS_TOTAL, &labels).increment(1);
+                metrics::histogram!(GRAPH_REQUEST_DURATION_SECONDS, &labels)
+                    .record(latency.as_secs_f64());
+                if retries > 0 {
+                    metrics::counter!(GRAPH_REQUEST_RETRIES_TOTAL, &labels)
+                        .increment(retries as u64);
+                }
+                let throttled = stats.throttled.load(Ordering::Relaxed);
+                if throttled > 0 {
+                    metrics::counter!(GRAPH_THROTTLED_RESPONSES_TOTAL, &labels)
+                        .increment(throttled as u64);
+                }
+                if !throttle_wait.is_zero() {
+                    metrics::histogram!(GRAPH_THROTTLE_WAIT_SECONDS, &labels)
+                        .record(throttle_wait.as_secs_f64());
+                }
+            }
+
+            result
+        })
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+/// Records the outcome of the request on the span and returns the status label.
+fn record_span(
+    span: &Span,
+    result: &Result<Response, BoxError>,
+    record_client_request_id: bool,
+) -> String {
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            span.record("http.response.status_code", status.as_u16());
+            if let Some(request_id) = header_str(response.headers(), REQUEST_ID) {
+                span.record("graph.request_id", request_id.as_str());
+            }
+            if record_client_request_id {
+                if let Some(client_request_id) = header_str(response.headers(), CLIENT_REQUEST_ID) {
+                    span.record("graph.client_request_id", client_request_id.as_str());
+                }
+            }
+            if status.is_client_error() || status.is_server_error() {
+                span.record("otel.status_code", "ERROR");
+            }
+            status.as_u16().to_string()
+        }
+        Err(err) => {
+            span.record("otel.status_code", "ERROR");
+            span.record("error.message", tracing::field::display(err));
+            String::from("error")
+        }
+    }
+}
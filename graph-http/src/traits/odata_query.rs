@@ -1,13 +1,29 @@
+use crate::odata::Filter;
+
 pub trait ODataQuery<RHS = Self>
 where
     Self: Sized,
 {
     fn append_query_pair<KV: AsRef<str>>(self, key: KV, value: KV) -> Self;
 
+    /// Sets the `ConsistencyLevel: eventual` header that is required for the advanced
+    /// query capabilities of directory objects such as `$count`, `$search`, and filters
+    /// using `endswith`, `ne`, or `not`. This is called by the query methods when the
+    /// query requires it. Types that do not send requests ignore this.
+    /// [See the docs](https://learn.microsoft.com/en-us/graph/aad-advanced-queries)
+    fn consistency_level_eventual(self) -> Self {
+        self
+    }
+
     /// Retrieves the total count of matching resources.
     /// [See the docs](https://docs.microsoft.com/en-us/graph/query-parameters#count-parameter)
     fn count<S: AsRef<str>>(self, value: S) -> Self {
-        self.append_query_pair("$count", value.as_ref())
+        if value.as_ref().eq("true") {
+            self.consistency_level_eventual()
+                .append_query_pair("$count", value.as_ref())
+        } else {
+            self.append_query_pair("$count", value.as_ref())
+        }
     }
 
     /// Filters properties (columns).
@@ -17,31 +33,62 @@ where
         self.append_query_pair("$select", &s)
     }
 
-    /// Retrieves related resources.
+    /// Retrieves related resources. Accepts navigation property names or
+    /// [`Expand`](crate::odata::Expand) for nested query options such as `$select`.
     /// [See the docs](https://docs.microsoft.com/en-us/graph/query-parameters#expand-parameter)
-    fn expand(self, value: &[&str]) -> Self {
-        let s = value.join(" ");
+    fn expand<T: ToString>(self, value: &[T]) -> Self {
+        let s = join(value, ",");
         self.append_query_pair("$expand", &s)
     }
 
-    /// Filters results (rows).
+    /// Filters results (rows). Multiple expressions are combined using `and`.
+    /// Values in the expressions are not escaped, use [`filter_by`](ODataQuery::filter_by)
+    /// for expressions that include user input.
     /// [See the docs](https://docs.microsoft.com/en-us/graph/query-parameters#filter-parameter)
-    fn filter(self, value: &[&str]) -> Self {
-        let s = value.join(",");
+    fn filter<T: ToString>(self, value: &[T]) -> Self {
+        let s = {
+            if value.len() > 1 {
+                let expressions: Vec<String> = value
+                    .iter()
+                    .map(|v| format!("({})", v.to_string()))
+                    .collect();
+                expressions.join(" and ")
+            } else {
+                join(value, "")
+            }
+        };
         self.append_query_pair("$filter", &s)
     }
 
-    /// Orders results.
+    /// Filters results (rows) using a typed [`Filter`] expression. Sets the
+    /// `ConsistencyLevel: eventual` header when the expression is an advanced query.
+    /// [See the docs](https://docs.microsoft.com/en-us/graph/query-parameters#filter-parameter)
+    fn filter_by(self, filter: Filter) -> Self {
+        let s = filter.to_string();
+        if filter.is_advanced_query() {
+            self.consistency_level_eventual()
+                .append_query_pair("$filter", &s)
+        } else {
+            self.append_query_pair("$filter", &s)
+        }
+    }
+
+    /// Orders results. Accepts property names with an optional direction, such as
+    /// `"displayName desc"`, or [`OrderBy`](crate::odata::OrderBy). Multiple properties
+    /// are separated by a comma. A direction given as its own value, such as
+    /// `&["displayName", "desc"]`, is applied to the property before it.
     /// [See the docs](https://docs.microsoft.com/en-us/graph/query-parameters#orderby-parameter)
-    fn order_by(self, value: &[&str]) -> Self {
-        let s = value.join(" ");
+    fn order_by<T: ToString>(self, value: &[T]) -> Self {
+        let s = join_order_by(value);
         self.append_query_pair("$orderby", &s)
     }
 
-    /// Returns results based on search criteria.
+    /// Returns results based on search criteria. Sets the `ConsistencyLevel: eventual`
+    /// header which is required when searching directory objects.
     /// [See the docs](https://docs.microsoft.com/en-us/graph/query-parameters#search-parameter)
     fn search<S: AsRef<str>>(self, value: S) -> Self {
-        self.append_query_pair("$search", value.as_ref())
+        self.consistency_level_eventual()
+            .append_query_pair("$search", value.as_ref())
     }
 
     /// Returns the results in the specified media format.
@@ -77,3 +124,22 @@ where
         self.append_query_pair("$top", value.as_ref())
     }
 }
+
+fn join<T: ToString>(value: &[T], separator: &str) -> String {
+    let values: Vec<String> = value.iter().map(|v| v.to_string()).collect();
+    values.join(separator)
+}
+
+// Properties are separated by a comma but a bare `asc` or `desc` belongs to the
+// property before it, which keeps `&["displayName", "desc"]` working as `displayName desc`.
+fn join_order_by<T: ToString>(value: &[T]) -> String {
+    let mut s = String::new();
+    for v in value.iter().map(|v| v.to_string()) {
+        let is_direction = v.eq_ignore_ascii_case("asc") || v.eq_ignore_ascii_case("desc");
+        if !s.is_empty() {
+            s.push(if is_direction { ' ' } else { ',' });
+        }
+        s.push_str(&v);
+    }
+    s
+}
@@ -4,7 +4,7 @@ use crate::internal::{
     UploadSessionLink, MAX_FILE_NAME_LEN,
 };
 use graph_error::download::BlockingDownloadError;
-use graph_error::{ErrorMessage, ErrorType, GraphFailure, GraphResult};
+use graph_error::{ErrorMessage, ErrorType, GraphFailure, GraphResult, ResponseDiagnostics};
 use std::io::Read;
 use std::path::PathBuf;
 
@@ -216,6 +216,18 @@ pub trait ResponseBlockingExt {
     /// ```
     fn into_graph_error_message(self) -> Result<ErrorMessage, reqwest::Error>;
 
+    /// The `request-id`, `client-request-id`, `x-ms-ags-diagnostic`, `date`, and
+    /// `x-ms-throttle-scope` headers of the response. Include these when opening a
+    /// support ticket for a request. Implementations without access to the response
+    /// headers return empty diagnostics.
+    /// ```rust,ignore
+    /// let response = client.me().get_user().into_blocking().send()?;
+    /// println!("{:#?}", response.diagnostics());
+    /// ```
+    fn diagnostics(&self) -> ResponseDiagnostics {
+        ResponseDiagnostics::default()
+    }
+
     /// Microsoft Graph specific status code errors mapped from the response [StatusCode].
     /// Not all status codes map to a Microsoft Graph error.
     ///
@@ -500,7 +512,13 @@ impl ResponseBlockingExt for reqwest::blocking::Response {
     /// }
     /// ```
     fn into_graph_error_message(self) -> Result<ErrorMessage, reqwest::Error> {
-        self.json()
+        let diagnostics = self.diagnostics();
+        let error_message: ErrorMessage = self.json()?;
+        Ok(error_message.with_diagnostics(diagnostics))
+    }
+
+    fn diagnostics(&self) -> ResponseDiagnostics {
+        ResponseDiagnostics::from(self.headers())
     }
 
     /// Microsoft Graph specific status code errors mapped from the response [StatusCode].
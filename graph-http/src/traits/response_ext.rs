@@ -4,7 +4,7 @@ use crate::internal::{
 use crate::traits::UploadSessionLink;
 use async_trait::async_trait;
 use graph_error::download::AsyncDownloadError;
-use graph_error::{ErrorMessage, ErrorType, GraphFailure, GraphResult};
+use graph_error::{ErrorMessage, ErrorType, GraphFailure, GraphResult, ResponseDiagnostics};
 use reqwest::header::HeaderMap;
 use reqwest::Response;
 use std::ffi::OsString;
@@ -46,6 +46,9 @@ pub(crate) fn parse_content_disposition(headers: &HeaderMap) -> Option<OsString>
 
 #[async_trait]
 pub trait ResponseExt {
+    /// Get the status of a job from the url in the `Location` header of the response
+    /// with a single request. Use `RequestHandler::send_long_running` to poll the
+    /// status until the job completes.
     async fn job_status(&self) -> Option<GraphResult<reqwest::Response>>;
 
     /// # Begin an upload session using any [`std::io::Reader`].<br>
@@ -324,6 +327,18 @@ pub trait ResponseExt {
     /// ```
     async fn into_graph_error_message(self) -> Result<ErrorMessage, reqwest::Error>;
 
+    /// The `request-id`, `client-request-id`, `x-ms-ags-diagnostic`, `date`, and
+    /// `x-ms-throttle-scope` headers of the response. Include these when opening a
+    /// support ticket for a request. Implementations without access to the response
+    /// headers return empty diagnostics.
+    /// ```rust,ignore
+    /// let response = client.me().get_user().send().await?;
+    /// println!("{:#?}", response.diagnostics());
+    /// ```
+    fn diagnostics(&self) -> ResponseDiagnostics {
+        ResponseDiagnostics::default()
+    }
+
     /// Microsoft Graph specific status code errors mapped from the response [StatusCode].
     /// Not all status codes map to a Microsoft Graph error.
     ///
@@ -695,7 +710,13 @@ impl ResponseExt for reqwest::Response {
     /// }
     /// ```
     async fn into_graph_error_message(self) -> Result<ErrorMessage, reqwest::Error> {
-        self.json().await
+        let diagnostics = self.diagnostics();
+        let error_message: ErrorMessage = self.json().await?;
+        Ok(error_message.with_diagnostics(diagnostics))
+    }
+
+    fn diagnostics(&self) -> ResponseDiagnostics {
+        ResponseDiagnostics::from(self.headers())
     }
 
     /// Microsoft Graph specific status code errors mapped from the response [StatusCode].
@@ -0,0 +1,272 @@
+use crate::blocking::TypedBlockingRequestHandler;
+use crate::internal::{HttpResponseBuilderExt, ODataQuery, Paging, RequestHandler};
+use crate::odata::Filter;
+use graph_error::{ErrorMessage, GraphFailure, GraphResult, ResponseDiagnostics};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use url::Url;
+
+/// Response of a typed request. The body is the deserialized `T` for successful
+/// responses or the [`ErrorMessage`] returned by the Graph Api.
+pub type TypedResponse<T> = http::Response<Result<T, ErrorMessage>>;
+pub type TypedResult<T> = GraphResult<TypedResponse<T>>;
+
+/// Request handler that deserializes the response body into `T`. Wraps the
+/// [`RequestHandler`] returned by an Api method.
+///
+/// # Example
+/// ```rust,ignore
+/// #[derive(serde::Deserialize)]
+/// struct Users {
+///     value: Vec<serde_json::Value>,
+/// }
+///
+/// let response = TypedRequestHandler::<Users>::new(client.users().list_user())
+///     .select(&["id", "displayName"])
+///     .send()
+///     .await?;
+///
+/// let users: Users = response.into_body()?;
+/// ```
+pub struct TypedRequestHandler<T> {
+    handler: RequestHandler,
+    response_type: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> TypedRequestHandler<T> {
+    pub fn new(handler: RequestHandler) -> TypedRequestHandler<T> {
+        TypedRequestHandler {
+            handler,
+            response_type: PhantomData,
+        }
+    }
+
+    /// Returns the untyped [`RequestHandler`].
+    pub fn into_inner(self) -> RequestHandler {
+        self.handler
+    }
+
+    pub fn into_blocking(self) -> TypedBlockingRequestHandler<T> {
+        TypedBlockingRequestHandler::new(self.handler.into_blocking())
+    }
+
+    /// See [`RequestHandler::try_into_blocking`].
+    pub fn try_into_blocking(self) -> GraphResult<TypedBlockingRequestHandler<T>> {
+        Ok(TypedBlockingRequestHandler::new(
+            self.handler.try_into_blocking()?,
+        ))
+    }
+
+    /// Returns true if any errors occurred prior to sending the request.
+    pub fn is_err(&self) -> bool {
+        self.handler.is_err()
+    }
+
+    /// Returns any error wrapped in an Option that occurred prior to sending a request
+    pub fn err(&self) -> Option<&GraphFailure> {
+        self.handler.err()
+    }
+
+    #[inline]
+    pub fn url(&self) -> Url {
+        self.handler.url()
+    }
+
+    #[inline]
+    pub fn query<Q: serde::Serialize + ?Sized>(mut self, query: &Q) -> Self {
+        self.handler = self.handler.query(query);
+        self
+    }
+
+    #[inline]
+    pub fn append_query_pair<KV: AsRef<str>>(mut self, key: KV, value: KV) -> Self {
+        self.handler = self.handler.append_query_pair(key, value);
+        self
+    }
+
+    /// Insert a header for the request.
+    #[inline]
+    pub fn header<K: Into<HeaderName>, V: Into<HeaderValue>>(
+        mut self,
+        header_name: K,
+        header_value: V,
+    ) -> Self {
+        self.handler = self.handler.header(header_name, header_value);
+        self
+    }
+
+    /// Set the headers for the request using reqwest::HeaderMap
+    #[inline]
+    pub fn headers(mut self, header_map: HeaderMap) -> Self {
+        self.handler = self.handler.headers(header_map);
+        self
+    }
+
+    /// Get a mutable reference to the headers.
+    #[inline]
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        self.handler.headers_mut()
+    }
+
+    pub fn paging(self) -> Paging {
+        self.handler.paging()
+    }
+
+    /// Sends the request and deserializes the response body.
+    ///
+    /// The body of successful responses is deserialized into `T`, returning an error if
+    /// the body does not match `T`, and the body of error responses is deserialized
+    /// into an [`ErrorMessage`]. Empty response bodies are deserialized from `null` so that
+    /// requests without a response body can use `()`.
+    pub async fn send(self) -> TypedResult<T> {
+        let response = self.handler.send().await?;
+        let status = response.status();
+        let url = response.url().clone();
+        let headers = response.headers().clone();
+        let version = response.version();
+        let bytes = response.bytes().await?;
+
+        typed_response(status, url, headers, version, bytes.as_ref())
+    }
+}
+
+impl<T: DeserializeOwned> ODataQuery for TypedRequestHandler<T> {
+    fn append_query_pair<KV: AsRef<str>>(self, key: KV, value: KV) -> Self {
+        self.append_query_pair(key.as_ref(), value.as_ref())
+    }
+
+    fn consistency_level_eventual(mut self) -> Self {
+        self.handler = self.handler.consistency_level_eventual();
+        self
+    }
+
+    fn count<S: AsRef<str>>(mut self, value: S) -> Self {
+        self.handler = self.handler.count(value);
+        self
+    }
+
+    fn filter_by(mut self, filter: Filter) -> Self {
+        self.handler = self.handler.filter_by(filter);
+        self
+    }
+
+    fn search<S: AsRef<str>>(mut self, value: S) -> Self {
+        self.handler = self.handler.search(value);
+        self
+    }
+}
+
+impl<T> AsRef<Url> for TypedRequestHandler<T> {
+    fn as_ref(&self) -> &Url {
+        self.handler.as_ref()
+    }
+}
+
+impl<T> AsMut<Url> for TypedRequestHandler<T> {
+    fn as_mut(&mut self) -> &mut Url {
+        self.handler.as_mut()
+    }
+}
+
+pub(crate) fn typed_response<T: DeserializeOwned>(
+    status: http::StatusCode,
+    url: Url,
+    headers: HeaderMap,
+    version: http::Version,
+    bytes: &[u8],
+) -> TypedResult<T> {
+    let json: serde_json::Value = {
+        if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(bytes)?
+        }
+    };
+
+    let body = {
+        if status.is_success() {
+            Ok(serde_json::from_value(json.clone())?)
+        } else {
+            Err(serde_json::from_value::<ErrorMessage>(json.clone())
+                .unwrap_or_default()
+                .with_diagnostics(ResponseDiagnostics::from(&headers)))
+        }
+    };
+
+    let mut builder = http::Response::builder()
+        .url(url)
+        .json(&json)
+        .status(status)
+        .version(version);
+
+    if let Some(builder_headers) = builder.headers_mut() {
+        builder_headers.extend(headers);
+    }
+
+    Ok(builder.body(body)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        id: String,
+    }
+
+    fn url() -> Url {
+        Url::parse("https://graph.microsoft.com/v1.0/users").unwrap()
+    }
+
+    #[test]
+    fn typed_response_body() {
+        let response: TypedResponse<User> = typed_response(
+            http::StatusCode::OK,
+            url(),
+            HeaderMap::new(),
+            http::Version::HTTP_11,
+            br#"{"id":"1"}"#,
+        )
+        .unwrap();
+        assert_eq!(response.into_body().unwrap(), User { id: "1".into() });
+
+        let response: TypedResponse<()> = typed_response(
+            http::StatusCode::NO_CONTENT,
+            url(),
+            HeaderMap::new(),
+            http::Version::HTTP_11,
+            b"",
+        )
+        .unwrap();
+        assert!(response.into_body().is_ok());
+    }
+
+    #[test]
+    fn typed_response_error_message() {
+        let response: TypedResponse<User> = typed_response(
+            http::StatusCode::NOT_FOUND,
+            url(),
+            HeaderMap::new(),
+            http::Version::HTTP_11,
+            br#"{"error":{"code":"Request_ResourceNotFound","message":"Resource does not exist."}}"#,
+        )
+        .unwrap();
+        let error = response.into_body().unwrap_err();
+        assert_eq!(
+            error.code_property().as_deref(),
+            Some("Request_ResourceNotFound")
+        );
+
+        let result: TypedResult<User> = typed_response(
+            http::StatusCode::OK,
+            url(),
+            HeaderMap::new(),
+            http::Version::HTTP_11,
+            br#"{"displayName":"Adele Vance"}"#,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Record and replay requests for tests.
+//!
+//! In record mode the requests sent by a client and the responses returned are written
+//! to a json cassette, with tokens and personal information removed by a [`Redactor`].
+//! In replay mode responses are returned from the cassette and no requests are sent, so
+//! tests can run in CI without credentials.
+//!
+//! ```rust,ignore
+//! use graph_rs_sdk::vcr::Vcr;
+//! use graph_rs_sdk::*;
+//!
+//! // Records when GRAPH_VCR_MODE=record and replays otherwise.
+//! let vcr = Vcr::from_env("./tests/cassettes/list_users.json")?;
+//! let client = GraphClient::from(vcr.configure(GraphClientConfiguration::new().access_token("TOKEN")));
+//!
+//! let response = client.users().list_user().send().await?;
+//!
+//! // Writes the cassette in record mode. The cassette is also written when the Vcr is dropped.
+//! vcr.save()?;
+//! ```
+//!
+//! Only requests sent by the async client are recorded.
+
+mod cassette;
+mod redactor;
+
+pub use cassette::*;
+pub use redactor::*;
+
+use crate::internal::{GraphClientConfiguration, GraphService};
+use graph_error::GraphResult;
+use http::header::{CONTENT_LENGTH, TRANSFER_ENCODING};
+use reqwest::{Request, Response, ResponseBuilderExt};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tower::{Service, ServiceExt};
+
+/// Environment variable that sets the mode of [`Vcr::from_env`]. Set to `record` to
+/// record a new cassette.
+pub const VCR_MODE_ENV: &str = "GRAPH_VCR_MODE";
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VcrMode {
+    /// Send requests and write the interactions to the cassette.
+    Record,
+    /// Return the responses of the cassette without sending requests.
+    Replay,
+}
+
+#[derive(Debug, Default)]
+struct VcrState {
+    cassette: Cassette,
+    replayed: Vec<bool>,
+}
+
+impl VcrState {
+    /// The response of the first interaction with the same method and url that has
+    /// not been replayed yet.
+    fn replay(&mut self, method: &str, url: &str) -> Option<RecordedResponse> {
+        let index =
+            self.cassette
+                .interactions
+                .iter()
+                .enumerate()
+                .position(|(index, interaction)| {
+                    !self.replayed[index]
+                        && interaction.request.method.eq(method)
+                        && interaction.request.url.eq(url)
+                })?;
+        self.replayed[index] = true;
+        Some(self.cassette.interactions[index].response.clone())
+    }
+}
+
+/// Records requests and responses to a cassette or replays them from the cassette.
+/// Use [`Vcr::configure`] to route the requests of a client through the vcr.
+#[derive(Debug)]
+pub struct Vcr {
+    mode: VcrMode,
+    path: PathBuf,
+    redactor: Arc<Redactor>,
+    state: Arc<Mutex<VcrState>>,
+}
+
+impl Vcr {
+    /// Record a new cassette. An existing cassette at the path is overwritten when the
+    /// cassette is saved.
+    pub fn record<P: AsRef<Path>>(path: P) -> Vcr {
+        Vcr {
+            mode: VcrMode::Record,
+            path: path.as_ref().to_path_buf(),
+            redactor: Arc::new(Redactor::default()),
+            state: Default::default(),
+        }
+    }
+
+    /// Replay the cassette at the path.
+    pub fn replay<P: AsRef<Path>>(path: P) -> GraphResult<Vcr> {
+        let cassette: Cassette = serde_json::from_slice(&std::fs::read(path.as_ref())?)?;
+        let replayed = vec![false; cassette.interactions.len()];
+        Ok(Vcr {
+            mode: VcrMode::Replay,
+            path: path.as_ref().to_path_buf(),
+            redactor: Arc::new(Redactor::default()),
+            state: Arc::new(Mutex::new(VcrState { cassette, replayed })),
+        })
+    }
+
+    /// Record when the `GRAPH_VCR_MODE` environment variable is set to `record`,
+    /// otherwise replay the cassette at the path.
+    pub fn from_env<P: AsRef<Path>>(path: P) -> GraphResult<Vcr> {
+        match std::env::var(VCR_MODE_ENV) {
+            Ok(mode) if mode.eq_ignore_ascii_case("record") => Ok(Vcr::record(path)),
+            _ => Vcr::replay(path),
+        }
+    }
+
+    /// Set the [`Redactor`] used for the interactions. Must be set before calling
+    /// [`Vcr::configure`].
+    pub fn redactor(mut self, redactor: Redactor) -> Vcr {
+        self.redactor = Arc::new(redactor);
+        self
+    }
+
+    pub fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// The interactions recorded, or the interactions of the cassette being replayed.
+    pub fn cassette(&self) -> Cassette {
+        self.state.lock().unwrap().cassette.clone()
+    }
+
+    /// Routes the requests of clients built from the configuration through the vcr.
+    pub fn configure(&self, config: GraphClientConfiguration) -> GraphClientConfiguration {
+        let redactor = self.redactor.clone();
+        let state = self.state.clone();
+        match self.mode {
+            VcrMode::Record => config.wrap_service(move |service| {
+                record_service(service, redactor.clone(), state.clone())
+            }),
+            VcrMode::Replay => {
+                let path = self.path.clone();
+                config.wrap_service(move |_| {
+                    replay_service(path.clone(), redactor.clone(), state.clone())
+                })
+            }
+        }
+    }
+
+    /// Writes the recorded interactions to the cassette. Does nothing in replay mode.
+    pub fn save(&self) -> GraphResult<()> {
+        if self.mode == VcrMode::Replay {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let cassette = self.cassette();
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&cassette)?)?;
+        Ok(())
+    }
+}
+
+impl Drop for Vcr {
+    fn drop(&mut self) {
+        let _ = self.save();
+    }
+}
+
+fn record_service(
+    service: GraphService,
+    redactor: Arc<Redactor>,
+    state: Arc<Mutex<VcrState>>,
+) -> GraphService {
+    tower::service_fn(move |request: Request| {
+        let mut service = service.clone();
+        let redactor = redactor.clone();
+        let state = state.clone();
+        async move {
+            let recorded_request = RecordedRequest {
+                method: request.method().to_string(),
+                url: redactor.redact_str(request.url().as_str()),
+                headers: redactor.redact_headers(request.headers()),
+                body: request
+                    .body()
+                    .and_then(|body| body.as_bytes())
+                    .map(|body| redactor.redact_body(body)),
+            };
+
+            let response = service.ready().await?.call(request).await?;
+            let status = response.status();
+            let version = response.version();
+            let url = response.url().clone();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?;
+
+            let recorded_response = RecordedResponse {
+                status: status.as_u16(),
+                headers: redactor.redact_headers(&headers),
+                body: (!body.is_empty()).then(|| redactor.redact_body(&body)),
+            };
+            let mut state = state.lock().unwrap();
+            state.cassette.interactions.push(Interaction {
+                request: recorded_request,
+                response: recorded_response,
+            });
+            state.replayed.push(false);
+
+            let mut builder = http::Response::builder()
+                .status(status)
+                .version(version)
+                .url(url);
+            if let Some(response_headers) = builder.headers_mut() {
+                response_headers.extend(headers);
+            }
+            Ok::<Response, BoxError>(Response::from(builder.body(body)?))
+        }
+    })
+    .boxed_clone()
+}
+
+fn replay_service(
+    path: PathBuf,
+    redactor: Arc<Redactor>,
+    state: Arc<Mutex<VcrState>>,
+) -> GraphService {
+    tower::service_fn(move |request: Request| {
+        let method = request.method().to_string();
+        let url = redactor.redact_str(request.url().as_str());
+        let recorded_response = state.lock().unwrap().replay(&method, &url);
+        let path = path.clone();
+
+        async move {
+            let recorded_response = recorded_response.ok_or_else(|| {
+                std::io::Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "No interaction for {method} {url} in cassette {}",
+                        path.display()
+                    ),
+                )
+            })?;
+
+            let mut builder = http::Response::builder()
+                .status(recorded_response.status)
+                .url(request.url().clone());
+            // The body may have changed length when it was redacted.
+            for (name, value) in recorded_response.headers.iter().filter(|(name, _)| {
+                !name.eq(&CONTENT_LENGTH.as_str()) && !name.eq(&TRANSFER_ENCODING.as_str())
+            }) {
+                builder = builder.header(name.as_str(), value.as_str());
+            }
+            let body = recorded_response
+                .body
+                .map(|body| body.as_bytes().to_vec())
+                .unwrap_or_default();
+            Ok::<Response, BoxError>(Response::from(builder.body(body)?))
+        }
+    })
+    .boxed_clone()
+}
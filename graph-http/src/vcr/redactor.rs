@@ -0,0 +1,226 @@
+use crate::vcr::RecordedBody;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The value that redacted headers and json fields are replaced with.
+pub const REDACTED: &str = "REDACTED";
+
+static DEFAULT_HEADERS: [&str; 5] = [
+    "authorization",
+    "cookie",
+    "set-cookie",
+    "x-ms-ags-diagnostic",
+    "x-ms-resource-unit",
+];
+
+static DEFAULT_JSON_FIELDS: [&str; 18] = [
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "client_secret",
+    "password",
+    "displayName",
+    "givenName",
+    "surname",
+    "mail",
+    "mailNickname",
+    "userPrincipalName",
+    "otherMails",
+    "proxyAddresses",
+    "imAddresses",
+    "mobilePhone",
+    "businessPhones",
+    "streetAddress",
+    "postalCode",
+];
+
+/// Removes secrets and personal information from requests and responses before they
+/// are written to a cassette.
+///
+/// By default the `Authorization` and cookie headers are redacted along with tokens,
+/// secrets, and the names, addresses, and phone numbers of users in json bodies. Use
+/// [`Redactor::replace`] for values that are part of urls, such as a user id or tenant id.
+/// Replacements are applied when recording and when replaying so that requests still
+/// match the cassette.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Redactor {
+    headers: BTreeSet<String>,
+    json_fields: BTreeSet<String>,
+    replacements: Vec<(String, String)>,
+}
+
+impl Redactor {
+    pub fn new() -> Redactor {
+        Redactor {
+            headers: DEFAULT_HEADERS.iter().map(|h| h.to_string()).collect(),
+            json_fields: DEFAULT_JSON_FIELDS.iter().map(|f| f.to_string()).collect(),
+            replacements: Vec::new(),
+        }
+    }
+
+    /// A redactor that only applies the headers, json fields, and replacements that are added.
+    pub fn empty() -> Redactor {
+        Redactor {
+            headers: BTreeSet::new(),
+            json_fields: BTreeSet::new(),
+            replacements: Vec::new(),
+        }
+    }
+
+    /// Redact the value of a header.
+    pub fn header<T: AsRef<str>>(mut self, name: T) -> Redactor {
+        self.headers.insert(name.as_ref().to_lowercase());
+        self
+    }
+
+    /// Redact the value of a field, at any depth, in json bodies.
+    pub fn json_field<T: AsRef<str>>(mut self, name: T) -> Redactor {
+        self.json_fields.insert(name.as_ref().to_string());
+        self
+    }
+
+    /// Replace every occurrence of a value in urls, header values, and bodies.
+    pub fn replace<T: AsRef<str>, U: AsRef<str>>(mut self, value: T, replacement: U) -> Redactor {
+        if !value.as_ref().is_empty() {
+            self.replacements
+                .push((value.as_ref().to_string(), replacement.as_ref().to_string()));
+        }
+        self
+    }
+
+    pub fn redact_str(&self, value: &str) -> String {
+        self.replacements
+            .iter()
+            .fold(value.to_string(), |value, (from, to)| {
+                value.replace(from, to)
+            })
+    }
+
+    pub fn redact_headers(&self, headers: &http::HeaderMap) -> BTreeMap<String, String> {
+        let mut redacted = BTreeMap::new();
+        for (name, value) in headers.iter() {
+            let value = if self.headers.contains(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                self.redact_str(&String::from_utf8_lossy(value.as_bytes()))
+            };
+            redacted
+                .entry(name.as_str().to_string())
+                .and_modify(|existing: &mut String| {
+                    existing.push_str(", ");
+                    existing.push_str(&value);
+                })
+                .or_insert(value);
+        }
+        redacted
+    }
+
+    pub fn redact_body(&self, body: &[u8]) -> RecordedBody {
+        match RecordedBody::from_bytes(body) {
+            RecordedBody::Text(text) => {
+                let text = match serde_json::from_str::<serde_json::Value>(&text) {
+                    Ok(mut value) if !self.json_fields.is_empty() => {
+                        self.redact_json(&mut value);
+                        serde_json::to_string(&value).unwrap_or(text)
+                    }
+                    _ => text,
+                };
+                RecordedBody::Text(self.redact_str(&text))
+            }
+            bytes => bytes,
+        }
+    }
+
+    fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.json_fields.contains(key) {
+                        redact_json_value(value);
+                    } else {
+                        self.redact_json(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for value in values.iter_mut() {
+                    self.redact_json(value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn redact_json_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(_) => *value = serde_json::Value::String(REDACTED.into()),
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_json_value),
+        _ => {}
+    }
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Redactor::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn redact_headers() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("Authorization", HeaderValue::from_static("Bearer token"));
+        headers.insert("client-request-id", HeaderValue::from_static("1234"));
+
+        let redactor = Redactor::new().replace("1234", "CLIENT_REQUEST_ID");
+        let redacted = redactor.redact_headers(&headers);
+        assert_eq!(Some(&REDACTED.to_string()), redacted.get("authorization"));
+        assert_eq!(
+            Some(&"CLIENT_REQUEST_ID".to_string()),
+            redacted.get("client-request-id")
+        );
+    }
+
+    #[test]
+    fn redact_json_body() {
+        let redactor = Redactor::new().replace("user-id", "USER_ID");
+        let body = serde_json::json!({
+            "value": [{
+                "id": "user-id",
+                "displayName": "Adele Vance",
+                "businessPhones": ["+1 425 555 0109"],
+                "accountEnabled": true
+            }],
+            "access_token": "token"
+        });
+
+        let redacted = redactor.redact_body(body.to_string().as_bytes());
+        let value: serde_json::Value = serde_json::from_slice(redacted.as_bytes()).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "value": [{
+                    "id": "USER_ID",
+                    "displayName": "REDACTED",
+                    "businessPhones": ["REDACTED"],
+                    "accountEnabled": true
+                }],
+                "access_token": "REDACTED"
+            }),
+            value
+        );
+    }
+
+    #[test]
+    fn binary_body_is_not_changed() {
+        let redactor = Redactor::new();
+        let body = [0, 159, 146, 150];
+        assert_eq!(
+            RecordedBody::Bytes(body.to_vec()),
+            redactor.redact_body(&body)
+        );
+    }
+}
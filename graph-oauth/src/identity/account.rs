@@ -0,0 +1,100 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use graph_error::{AuthorizationFailure, AF};
+
+use crate::identity::{IdToken, Token};
+
+/// Tenant id of personal Microsoft accounts such as outlook.com and hotmail.com accounts.
+pub const PERSONAL_ACCOUNT_TENANT_ID: &str = "9188040d-6c67-4c5b-b112-36a304b66dad";
+
+/// The signed in user of a token. Created from the claims of the id token returned
+/// alongside the access token.
+#[derive(Default, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    /// Unique identifier of the account across tenants in the form `{oid}.{tid}`.
+    pub home_account_id: String,
+    /// The object id (`oid` claim) of the user in the tenant.
+    pub local_account_id: Option<String>,
+    /// The tenant (`tid` claim) the user signed in to.
+    pub tenant_id: Option<String>,
+    /// The `preferred_username` claim, usually the email address or phone number of the user.
+    pub username: Option<String>,
+}
+
+impl Account {
+    /// Returns true if the account is a personal Microsoft account.
+    pub fn is_personal_account(&self) -> bool {
+        self.tenant_id.as_deref() == Some(PERSONAL_ACCOUNT_TENANT_ID)
+    }
+}
+
+impl TryFrom<&IdToken> for Account {
+    type Error = AuthorizationFailure;
+
+    fn try_from(value: &IdToken) -> Result<Self, Self::Error> {
+        let payload = value
+            .id_token
+            .split('.')
+            .nth(1)
+            .ok_or_else(|| AF::msg_err("id_token", "id_token is not a valid JWT"))?;
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload.trim_end_matches('='))
+            .map_err(|err| AF::msg_err("id_token", err.to_string().as_str()))?;
+        let claims: serde_json::Value = serde_json::from_slice(&payload)?;
+
+        let claim = |name: &str| claims[name].as_str().map(|value| value.to_owned());
+        let local_account_id = claim("oid").or_else(|| claim("sub"));
+        let tenant_id = claim("tid");
+        let home_account_id = match (local_account_id.as_ref(), tenant_id.as_ref()) {
+            (Some(oid), Some(tid)) => format!("{oid}.{tid}"),
+            (Some(oid), None) => oid.clone(),
+            _ => return AF::msg_result("id_token", "id_token is missing the oid and sub claims"),
+        };
+
+        Ok(Account {
+            home_account_id,
+            local_account_id,
+            tenant_id,
+            username: claim("preferred_username"),
+        })
+    }
+}
+
+impl TryFrom<&Token> for Account {
+    type Error = AuthorizationFailure;
+
+    fn try_from(value: &Token) -> Result<Self, Self::Error> {
+        let id_token = value
+            .id_token
+            .as_ref()
+            .ok_or_else(|| AF::required("id_token"))?;
+        Account::try_from(id_token)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn account_from_id_token() {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(
+            serde_json::json!({
+                "oid": "00000000-0000-0000-66f3-3332eca7ea81",
+                "tid": PERSONAL_ACCOUNT_TENANT_ID,
+                "preferred_username": "user@outlook.com"
+            })
+            .to_string(),
+        );
+        let id_token = IdToken::new(&format!("{header}.{payload}."), None, None, None);
+
+        let account = Account::try_from(&id_token).unwrap();
+        assert_eq!(
+            account.home_account_id,
+            format!("00000000-0000-0000-66f3-3332eca7ea81.{PERSONAL_ACCOUNT_TENANT_ID}")
+        );
+        assert_eq!(account.username.as_deref(), Some("user@outlook.com"));
+        assert!(account.is_personal_account());
+    }
+}
@@ -75,6 +75,22 @@ impl AllowedHostValidator {
         AllowedHostValidator { allowed_hosts }
     }
 
+    /// Allow an additional host. Only the scheme, host, and port of the url are used.
+    ///
+    /// # Panics
+    /// Panics if the url does not use the https scheme.
+    pub fn allow_host(&mut self, url: &Url) {
+        if !url.scheme().eq("https") {
+            panic!("Requires https scheme");
+        }
+
+        let mut host = url.clone();
+        host.set_path("");
+        host.set_query(None);
+        host.set_fragment(None);
+        self.allowed_hosts.insert(host);
+    }
+
     pub fn validate_str(&self, url_str: &str) -> HostIs {
         if let Ok(url) = Url::parse(url_str) {
             return self.validate_hosts(&[url]);
@@ -197,6 +213,20 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_allow_host() {
+        let mut allowed_host_validator = AllowedHostValidator::default();
+        let url = Url::parse("https://graph.corp.internal/v1.0").unwrap();
+        assert_eq!(HostIs::Invalid, allowed_host_validator.validate_url(&url));
+
+        allowed_host_validator.allow_host(&Url::parse("https://graph.corp.internal").unwrap());
+        assert_eq!(HostIs::Valid, allowed_host_validator.validate_url(&url));
+        assert_eq!(
+            HostIs::Valid,
+            allowed_host_validator.validate_str("https://graph.microsoft.com/v1.0")
+        );
+    }
+
     #[test]
     fn test_allowed_host_validator() {
         let valid_hosts: Vec<String> = [
@@ -41,6 +41,18 @@ impl AzureCloudInstance {
     pub fn get_open_id_configuration_url(&self, authority: Authority) -> String {
         format!("{}/v2.0/{}", self.as_ref(), authority.as_ref())
     }
+
+    /// The Azure cloud instance with the host of the url, if any.
+    pub fn from_authority_host(url: &Url) -> Option<AzureCloudInstance> {
+        [
+            AzureCloudInstance::AzurePublic,
+            AzureCloudInstance::AzureChina,
+            AzureCloudInstance::AzureGermany,
+            AzureCloudInstance::AzureUsGovernment,
+        ]
+        .into_iter()
+        .find(|azure_cloud_instance| Url::from(azure_cloud_instance).host() == url.host())
+    }
 }
 
 impl AsRef<str> for AzureCloudInstance {
@@ -109,6 +121,14 @@ impl AzureCloudInstance {
         ))
     }
 
+    pub fn end_session_uri(&self, authority: &Authority) -> Result<Url, ParseError> {
+        Url::parse(&format!(
+            "{}/{}/oauth2/v2.0/logout",
+            self.as_ref(),
+            authority.as_ref()
+        ))
+    }
+
     pub fn openid_configuration_uri(&self, authority: &Authority) -> Result<Url, ParseError> {
         Url::parse(&format!(
             "{}/{}/v2.0/.well-known/openid-configuration",
@@ -5,7 +5,7 @@ use std::fmt::{Debug, Formatter};
 
 use crate::identity::{Authority, AzureCloudInstance, IdToken};
 use crate::ApplicationOptions;
-use graph_core::identity::ForceTokenRefresh;
+use graph_core::identity::{AuthScheme, CloudEnvironment, ForceTokenRefresh};
 use graph_error::AF;
 use graph_http::api_impl::GraphClientConfiguration;
 use reqwest::header::HeaderMap;
@@ -67,6 +67,22 @@ pub struct AppConfig {
     pub(crate) id_token: Option<IdToken>,
     pub(crate) log_pii: bool,
     pub(crate) config: GraphClientConfiguration,
+    /// The scheme used when calling resources with the access token. When using
+    /// [AuthScheme::ProofOfPossession] the token request binds the access token to the key.
+    pub(crate) auth_scheme: AuthScheme,
+    /// Claims requested by a resource in a claims challenge. Sent in the `claims`
+    /// parameter on the next token request which bypasses the token cache.
+    pub(crate) claims: Option<String>,
+    /// Client capabilities sent in the `xms_cc` claim of the `claims` parameter.
+    /// Defaults to `cp1` which tells the identity platform the client can handle
+    /// claims challenges and enables long-lived Continuous Access Evaluation (CAE) tokens.
+    pub(crate) client_capabilities: BTreeSet<String>,
+    /// Token revocation endpoint used to revoke the refresh token when signing out
+    /// a personal Microsoft account. Revocation is skipped when not set.
+    pub(crate) revocation_uri: Option<Url>,
+    /// The national cloud the credential was configured for. Sets the Azure cloud instance
+    /// and the default scope of the credential.
+    pub(crate) cloud_environment: Option<CloudEnvironment>,
 }
 
 impl TryFrom<ApplicationOptions> for AppConfig {
@@ -96,6 +112,11 @@ impl TryFrom<ApplicationOptions> for AppConfig {
             id_token: Default::default(),
             log_pii: false,
             config: Default::default(),
+            auth_scheme: Default::default(),
+            claims: None,
+            client_capabilities: AppConfig::default_client_capabilities(),
+            revocation_uri: None,
+            cloud_environment: None,
         })
     }
 }
@@ -112,6 +133,11 @@ impl Debug for AppConfig {
                 .field("extra_header_parameters", &self.extra_header_parameters)
                 .field("scope", &self.scope)
                 .field("force_token_refresh", &self.force_token_refresh)
+                .field("auth_scheme", &self.auth_scheme)
+                .field("claims", &self.claims)
+                .field("client_capabilities", &self.client_capabilities)
+                .field("revocation_uri", &self.revocation_uri)
+                .field("cloud_environment", &self.cloud_environment)
                 .finish()
         } else {
             f.debug_struct("AppConfig")
@@ -132,6 +158,11 @@ impl Debug for AppConfig {
                 )
                 .field("scope", &self.scope)
                 .field("force_token_refresh", &self.force_token_refresh)
+                .field("auth_scheme", &self.auth_scheme)
+                .field("claims", &self.claims)
+                .field("client_capabilities", &self.client_capabilities)
+                .field("revocation_uri", &self.revocation_uri)
+                .field("cloud_environment", &self.cloud_environment)
                 .finish()
         }
     }
@@ -147,6 +178,10 @@ impl AppConfig {
         }
     }
 
+    fn default_client_capabilities() -> BTreeSet<String> {
+        BTreeSet::from_iter(vec!["cp1".to_owned()])
+    }
+
     pub(crate) fn builder(client_id: impl TryInto<Uuid>) -> AppConfigBuilder {
         AppConfigBuilder::new(client_id)
     }
@@ -173,6 +208,11 @@ impl AppConfig {
             id_token: Default::default(),
             log_pii: Default::default(),
             config: Default::default(),
+            auth_scheme: Default::default(),
+            claims: None,
+            client_capabilities: AppConfig::default_client_capabilities(),
+            revocation_uri: None,
+            cloud_environment: None,
         }
     }
 
@@ -180,8 +220,15 @@ impl AppConfig {
         self.log_pii = log_pii;
     }
 
+    /// Tokens are cached for each client id and tenant so that credentials built from
+    /// clones of the same builder for different tenants do not share tokens.
+    fn update_cache_id(&mut self) {
+        self.cache_id = AppConfig::generate_cache_id(self.client_id, self.tenant_id.as_ref());
+    }
+
     pub(crate) fn with_client_id(&mut self, client_id: impl TryInto<Uuid>) {
         self.client_id = client_id.try_into().unwrap_or_default();
+        self.update_cache_id();
     }
 
     pub(crate) fn with_authority(&mut self, authority: Authority) {
@@ -189,6 +236,24 @@ impl AppConfig {
             self.tenant_id = Some(tenant_id.clone());
         }
         self.authority = authority;
+        self.update_cache_id();
+    }
+
+    /// Use the authority host and default scope of the cloud environment. The Azure cloud
+    /// instance is only changed when the authority host of the environment is the host of
+    /// one of the Azure cloud instances.
+    pub(crate) fn with_cloud_environment(&mut self, cloud_environment: CloudEnvironment) {
+        if let Some(azure_cloud_instance) =
+            AzureCloudInstance::from_authority_host(&cloud_environment.authority_host())
+        {
+            self.azure_cloud_instance = azure_cloud_instance;
+        }
+
+        let public_default_scope = CloudEnvironment::Public.default_scope();
+        if self.scope.remove(&public_default_scope) {
+            self.scope.insert(cloud_environment.default_scope());
+        }
+        self.cloud_environment = Some(cloud_environment);
     }
 
     pub(crate) fn with_azure_cloud_instance(&mut self, azure_cloud_instance: AzureCloudInstance) {
@@ -199,6 +264,7 @@ impl AppConfig {
         let tenant = tenant_id.as_ref().to_string();
         self.tenant_id = Some(tenant.clone());
         self.authority = Authority::TenantId(tenant);
+        self.update_cache_id();
     }
 
     /// Extends the query parameters of both the default query params and user defined params.
@@ -245,6 +311,71 @@ impl AppConfig {
     pub(crate) fn with_config(&mut self, config: GraphClientConfiguration) {
         self.config = config;
     }
+
+    pub(crate) fn with_auth_scheme(&mut self, auth_scheme: AuthScheme) {
+        self.auth_scheme = auth_scheme;
+    }
+
+    pub(crate) fn with_client_capabilities<T: ToString, I: IntoIterator<Item = T>>(
+        &mut self,
+        client_capabilities: I,
+    ) {
+        self.client_capabilities = client_capabilities
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+    }
+
+    pub(crate) fn with_revocation_uri(&mut self, revocation_uri: Url) {
+        self.revocation_uri = Some(revocation_uri);
+    }
+
+    /// Set the claims from a claims challenge. The next token request bypasses
+    /// the token cache and sends the claims to the identity platform.
+    pub(crate) fn with_claims_challenge(&mut self, claims: String) {
+        self.claims = Some(claims);
+        self.force_token_refresh = ForceTokenRefresh::Once;
+    }
+
+    /// Called once a token request that bypassed the token cache completes. Resets
+    /// [ForceTokenRefresh::Once] and clears the claims from a claims challenge so that
+    /// later token requests do not send them again.
+    pub(crate) fn complete_forced_token_refresh(&mut self) {
+        if self.force_token_refresh == ForceTokenRefresh::Once {
+            self.force_token_refresh = ForceTokenRefresh::Never;
+        }
+        self.claims = None;
+    }
+
+    /// The `claims` parameter for a token request. Merges the claims from a claims challenge,
+    /// if a token refresh is pending, with the client capabilities in `xms_cc`.
+    pub(crate) fn claims_parameter(&self) -> Option<String> {
+        let mut claims = self
+            .claims
+            .as_ref()
+            .filter(|_| self.force_token_refresh != ForceTokenRefresh::Never)
+            .and_then(|claims| serde_json::from_str::<serde_json::Value>(claims).ok())
+            .filter(|value| value.is_object())
+            .unwrap_or_else(|| serde_json::json!({}));
+
+        if !self.client_capabilities.is_empty() {
+            let access_token = claims
+                .as_object_mut()?
+                .entry("access_token")
+                .or_insert_with(|| serde_json::json!({}));
+            if let Some(access_token) = access_token.as_object_mut() {
+                access_token.insert(
+                    "xms_cc".into(),
+                    serde_json::json!({ "values": self.client_capabilities }),
+                );
+            }
+        }
+
+        claims
+            .as_object()
+            .filter(|claims| !claims.is_empty())
+            .map(|_| claims.to_string())
+    }
 }
 
 #[derive(Clone, Default, PartialEq)]
@@ -290,7 +421,22 @@ impl AppConfigBuilder {
         self
     }
 
+    pub fn auth_scheme(mut self, auth_scheme: AuthScheme) -> Self {
+        self.app_config.auth_scheme = auth_scheme;
+        self
+    }
+
+    pub fn client_capabilities<T: ToString, I: IntoIterator<Item = T>>(
+        mut self,
+        client_capabilities: I,
+    ) -> Self {
+        self.app_config
+            .with_client_capabilities(client_capabilities);
+        self
+    }
+
     pub fn build(mut self) -> AppConfig {
+        self.app_config.update_cache_id();
         if self.app_config.redirect_uri.is_none() {
             self.app_config.redirect_uri = Some(
                 Url::parse("http://localhost")
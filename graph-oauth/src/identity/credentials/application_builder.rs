@@ -14,12 +14,13 @@ use crate::identity::{
     X509Certificate,
 };
 use crate::AuthorizationCodeSpaCredentialBuilder;
+use graph_core::identity::AuthScheme;
 use graph_error::{IdentityResult, AF};
+use graph_http::api_impl::GraphClientConfiguration;
 use http::{HeaderMap, HeaderName, HeaderValue};
 use std::collections::HashMap;
 use std::env::VarError;
 use uuid::Uuid;
-use graph_http::api_impl::GraphClientConfiguration;
 
 pub struct ConfidentialClientApplicationBuilder {
     pub(crate) app_config: AppConfig,
@@ -101,11 +102,33 @@ impl ConfidentialClientApplicationBuilder {
     }
 
     /// Configure http client settings using GraphClientConfiguration
-    pub fn with_config(&mut self, graph_client_configuration: GraphClientConfiguration) -> &mut Self {
+    pub fn with_config(
+        &mut self,
+        graph_client_configuration: GraphClientConfiguration,
+    ) -> &mut Self {
         self.app_config.with_config(graph_client_configuration);
         self
     }
 
+    /// Set the scheme used when calling resources with the access token.
+    /// Use [AuthScheme::ProofOfPossession] to request Proof-of-Possession tokens
+    /// that are bound to a key and sign each request.
+    pub fn with_auth_scheme(&mut self, auth_scheme: AuthScheme) -> &mut Self {
+        self.app_config.with_auth_scheme(auth_scheme);
+        self
+    }
+
+    /// Set the client capabilities sent in the `xms_cc` claim of token requests.
+    /// Defaults to `cp1` which enables Continuous Access Evaluation (CAE).
+    pub fn with_client_capabilities<T: ToString, I: IntoIterator<Item = T>>(
+        &mut self,
+        client_capabilities: I,
+    ) -> &mut Self {
+        self.app_config
+            .with_client_capabilities(client_capabilities);
+        self
+    }
+
     /// Auth Code Authorization Url Builder
     pub fn auth_code_url_builder(&mut self) -> AuthCodeAuthorizationUrlParameterBuilder {
         AuthCodeAuthorizationUrlParameterBuilder::new_with_app_config(self.app_config.clone())
@@ -328,11 +351,33 @@ impl PublicClientApplicationBuilder {
     }
 
     /// Configure http client settings using GraphClientConfiguration
-    pub fn with_config(&mut self, graph_client_configuration: GraphClientConfiguration) -> &mut Self {
+    pub fn with_config(
+        &mut self,
+        graph_client_configuration: GraphClientConfiguration,
+    ) -> &mut Self {
         self.app_config.with_config(graph_client_configuration);
         self
     }
 
+    /// Set the scheme used when calling resources with the access token.
+    /// Use [AuthScheme::ProofOfPossession] to request Proof-of-Possession tokens
+    /// that are bound to a key and sign each request.
+    pub fn with_auth_scheme(&mut self, auth_scheme: AuthScheme) -> &mut Self {
+        self.app_config.with_auth_scheme(auth_scheme);
+        self
+    }
+
+    /// Set the client capabilities sent in the `xms_cc` claim of token requests.
+    /// Defaults to `cp1` which enables Continuous Access Evaluation (CAE).
+    pub fn with_client_capabilities<T: ToString, I: IntoIterator<Item = T>>(
+        &mut self,
+        client_capabilities: I,
+    ) -> &mut Self {
+        self.app_config
+            .with_client_capabilities(client_capabilities);
+        self
+    }
+
     pub fn with_device_code_executor(&mut self) -> DeviceCodePollingExecutor {
         DeviceCodePollingExecutor::new_with_app_config(self.app_config.clone())
     }
@@ -148,6 +148,8 @@ impl AuthorizationCodeAssertionCredential {
     }
 }
 
+credential_sign_out_impl!(AuthorizationCodeAssertionCredential, client_assertion);
+
 #[async_trait]
 impl TokenCache for AuthorizationCodeAssertionCredential {
     type Token = Token;
@@ -181,9 +183,7 @@ impl TokenCache for AuthorizationCodeAssertionCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh(cache_id);
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -221,9 +221,7 @@ impl TokenCache for AuthorizationCodeAssertionCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh_async(cache_id).await;
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -232,6 +230,10 @@ impl TokenCache for AuthorizationCodeAssertionCredential {
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh) {
         self.app_config.force_token_refresh = force_token_refresh;
     }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.app_config.with_claims_challenge(claims);
+    }
 }
 
 #[async_trait]
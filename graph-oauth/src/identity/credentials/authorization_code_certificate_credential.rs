@@ -196,6 +196,8 @@ impl AuthorizationCodeCertificateCredential {
     }
 }
 
+credential_sign_out_impl!(AuthorizationCodeCertificateCredential, client_assertion);
+
 #[async_trait]
 impl TokenCache for AuthorizationCodeCertificateCredential {
     type Token = Token;
@@ -229,9 +231,7 @@ impl TokenCache for AuthorizationCodeCertificateCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh(cache_id);
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -269,9 +269,7 @@ impl TokenCache for AuthorizationCodeCertificateCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh_async(cache_id).await;
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -280,6 +278,10 @@ impl TokenCache for AuthorizationCodeCertificateCredential {
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh) {
         self.app_config.force_token_refresh = force_token_refresh;
     }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.app_config.with_claims_challenge(claims);
+    }
 }
 
 #[async_trait]
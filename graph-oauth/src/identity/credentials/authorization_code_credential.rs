@@ -166,6 +166,8 @@ impl AuthorizationCodeCredential {
     }
 }
 
+credential_sign_out_impl!(AuthorizationCodeCredential, client_secret);
+
 #[async_trait]
 impl TokenCache for AuthorizationCodeCredential {
     type Token = Token;
@@ -205,9 +207,7 @@ impl TokenCache for AuthorizationCodeCredential {
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; refresh_token=None");
                 let token_result = self.execute_cached_token_refresh(cache_id);
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -249,9 +249,7 @@ impl TokenCache for AuthorizationCodeCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh_async(cache_id).await;
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -260,6 +258,10 @@ impl TokenCache for AuthorizationCodeCredential {
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh) {
         self.app_config.force_token_refresh = force_token_refresh;
     }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.app_config.with_claims_challenge(claims);
+    }
 }
 
 #[derive(Clone)]
@@ -152,6 +152,8 @@ impl AuthorizationCodeSpaCredential {
     }
 }
 
+credential_sign_out_impl!(AuthorizationCodeSpaCredential);
+
 #[async_trait]
 impl TokenCache for AuthorizationCodeSpaCredential {
     type Token = Token;
@@ -191,9 +193,7 @@ impl TokenCache for AuthorizationCodeSpaCredential {
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; refresh_token=None");
                 let token_result = self.execute_cached_token_refresh(cache_id);
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -235,9 +235,7 @@ impl TokenCache for AuthorizationCodeSpaCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh_async(cache_id).await;
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -246,6 +244,10 @@ impl TokenCache for AuthorizationCodeSpaCredential {
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh) {
         self.app_config.force_token_refresh = force_token_refresh;
     }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.app_config.with_claims_challenge(claims);
+    }
 }
 
 #[derive(Clone)]
@@ -0,0 +1,436 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use graph_core::identity::{Claims, DecodedJwt, JwksKeySet};
+use graph_error::{AuthExecutionError, AuthExecutionResult, IdentityResult, AF};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use url::Url;
+use uuid::Uuid;
+
+use crate::identity::{
+    tracing_targets::CREDENTIAL_EXECUTOR, Authority, AuthorizationResponse,
+    OpenIdAuthorizationUrlParameters,
+};
+
+/// Validate the authorization response of the OpenID Connect flow before the
+/// authorization code is redeemed.
+///
+/// The keys used to verify the id token signature are fetched from the `jwks_uri`
+/// of the authority's OpenID configuration.
+///
+/// The following checks are done:
+/// - The response is not an error response.
+/// - The state matches the state sent in the authorization request.
+/// - The id token signature is verified using the JSON Web Key Set of the authority.
+/// - The id token audience is the client id and the issuer is the authority of the tenant.
+/// - The nonce claim of the id token matches the nonce sent in the authorization request.
+/// - The `c_hash` claim matches the authorization code and the `at_hash` claim matches the
+///   access token when they are returned alongside the id token (hybrid flow).
+///
+/// Returns the decoded id token, or `None` when the response does not include an id token
+/// because it was not requested.
+pub fn validate_authorization_response(
+    url_params: &OpenIdAuthorizationUrlParameters,
+    response: &AuthorizationResponse,
+) -> AuthExecutionResult<Option<DecodedJwt>> {
+    if response.id_token.is_none() {
+        return Ok(validate_authorization_response_with_jwks(
+            url_params,
+            response,
+            &JwksKeySet::default(),
+        )?);
+    }
+
+    let http_client = url_params
+        .app_config
+        .config
+        .clone()
+        .try_build_minimal_blocking_client()?;
+    let openid_configuration: serde_json::Value = http_client
+        .inner
+        .get(openid_configuration_uri(url_params)?)
+        .send()?
+        .json()?;
+    let jwks: JwksKeySet = http_client
+        .inner
+        .get(jwks_uri(&openid_configuration)?)
+        .send()?
+        .json()?;
+
+    Ok(validate_authorization_response_with_jwks(
+        url_params, response, &jwks,
+    )?)
+}
+
+/// Async version of [validate_authorization_response].
+pub async fn validate_authorization_response_async(
+    url_params: &OpenIdAuthorizationUrlParameters,
+    response: &AuthorizationResponse,
+) -> AuthExecutionResult<Option<DecodedJwt>> {
+    if response.id_token.is_none() {
+        return Ok(validate_authorization_response_with_jwks(
+            url_params,
+            response,
+            &JwksKeySet::default(),
+        )?);
+    }
+
+    let mut http_client = url_params
+        .app_config
+        .config
+        .clone()
+        .try_build_minimal_async_client()?;
+    let request = http_client
+        .inner
+        .get(openid_configuration_uri(url_params)?)
+        .build()?;
+    let openid_configuration: serde_json::Value = http_client
+        .execute(request)
+        .await
+        .map_err(AuthExecutionError::from)?
+        .json()
+        .await?;
+    let request = http_client
+        .inner
+        .get(jwks_uri(&openid_configuration)?)
+        .build()?;
+    let jwks: JwksKeySet = http_client
+        .execute(request)
+        .await
+        .map_err(AuthExecutionError::from)?
+        .json()
+        .await?;
+
+    Ok(validate_authorization_response_with_jwks(
+        url_params, response, &jwks,
+    )?)
+}
+
+/// Same as [validate_authorization_response] but uses the given JSON Web Key Set
+/// to verify the id token signature instead of fetching it from the authority.
+pub fn validate_authorization_response_with_jwks(
+    url_params: &OpenIdAuthorizationUrlParameters,
+    response: &AuthorizationResponse,
+    jwks: &JwksKeySet,
+) -> IdentityResult<Option<DecodedJwt>> {
+    if response.is_err() {
+        return AF::msg_result(
+            "authorization_response",
+            format!(
+                "error: {:#?}, error_description: {:#?}",
+                response.error, response.error_description
+            ),
+        );
+    }
+
+    if url_params.state != response.state {
+        return AF::msg_result(
+            "state",
+            "state in the authorization response does not match the state sent in the authorization request",
+        );
+    }
+
+    let Some(id_token) = response.id_token.as_ref() else {
+        if url_params.response_type.iter().any(|response_type| {
+            response_type
+                .to_string()
+                .split(' ')
+                .any(|value| value == "id_token")
+        }) {
+            return AF::result("id_token");
+        }
+        return Ok(None);
+    };
+
+    let header = jsonwebtoken::decode_header(id_token)
+        .map_err(|err| AF::msg_err("id_token", err.to_string().as_str()))?;
+    let kid = header
+        .kid
+        .as_ref()
+        .ok_or_else(|| AF::msg_err("id_token", "id token header is missing kid"))?;
+    let key = jwks
+        .keys
+        .iter()
+        .find(|key| key.kid.eq(kid))
+        .ok_or_else(|| AF::msg_err("id_token", "no key in the JSON Web Key Set matches kid"))?;
+
+    if !matches!(
+        header.alg,
+        Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512
+    ) {
+        return AF::msg_result("id_token", "unsupported id token signing algorithm");
+    }
+
+    let decoding_key = DecodingKey::from_rsa_components(&key.modulus, &key.exponent)
+        .map_err(|err| AF::msg_err("id_token", err.to_string().as_str()))?;
+    let mut validation = Validation::new(header.alg);
+    validation.set_audience(&[url_params.app_config.client_id.to_string()]);
+
+    let token_data = jsonwebtoken::decode::<Claims>(id_token, &decoding_key, &validation)
+        .map_err(|err| AF::msg_err("id_token", err.to_string().as_str()))?;
+
+    validate_issuer(url_params, &token_data.claims)?;
+
+    if token_data.claims.nonce.as_ref() != Some(&url_params.nonce) {
+        return AF::msg_result(
+            "nonce",
+            "nonce in the id token does not match the nonce sent in the authorization request",
+        );
+    }
+
+    if let Some(code) = response.code.as_ref() {
+        let c_hash = token_data
+            .claims
+            .c_hash
+            .as_ref()
+            .ok_or_else(|| AF::msg_err("c_hash", "id token is missing c_hash"))?;
+        validate_hash("c_hash", header.alg, code, c_hash)?;
+    }
+
+    if let Some(access_token) = response.access_token.as_ref() {
+        let at_hash = token_data
+            .claims
+            .additional_fields
+            .get("at_hash")
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| AF::msg_err("at_hash", "id token is missing at_hash"))?;
+        validate_hash("at_hash", header.alg, access_token, at_hash)?;
+    }
+
+    tracing::debug!(target: CREDENTIAL_EXECUTOR, "authorization response validated");
+    Ok(Some(token_data))
+}
+
+fn openid_configuration_uri(url_params: &OpenIdAuthorizationUrlParameters) -> IdentityResult<Url> {
+    Ok(url_params
+        .app_config
+        .azure_cloud_instance
+        .openid_configuration_uri(&url_params.app_config.authority)?)
+}
+
+fn jwks_uri(openid_configuration: &serde_json::Value) -> IdentityResult<Url> {
+    let jwks_uri = openid_configuration["jwks_uri"]
+        .as_str()
+        .ok_or_else(|| AF::required("jwks_uri"))?;
+    Ok(Url::parse(jwks_uri)?)
+}
+
+/// The issuer must be the authority of the tenant the user signed in to. When the
+/// authority is a tenant id the tenant of the id token must also match.
+fn validate_issuer(
+    url_params: &OpenIdAuthorizationUrlParameters,
+    claims: &Claims,
+) -> IdentityResult<()> {
+    let tid = claims
+        .tid
+        .as_ref()
+        .ok_or_else(|| AF::msg_err("id_token", "id token is missing tid"))?;
+
+    if let Authority::TenantId(tenant_id) = &url_params.app_config.authority {
+        if Uuid::try_parse(tenant_id).is_ok() && !tenant_id.eq_ignore_ascii_case(tid) {
+            return AF::msg_result("tid", "id token was issued for a different tenant");
+        }
+    }
+
+    let issuer = url_params
+        .app_config
+        .azure_cloud_instance
+        .issuer(&Authority::TenantId(tid.clone()))?;
+    if claims.iss.trim_end_matches('/') != issuer.as_str().trim_end_matches('/') {
+        return AF::msg_result("iss", "id token issuer does not match the authority");
+    }
+
+    Ok(())
+}
+
+/// Hash claims are the base64url encoding of the left-most half of the hash of the value
+/// using the hash algorithm of the id token signing algorithm.
+fn validate_hash(name: &str, alg: Algorithm, value: &str, expected: &str) -> IdentityResult<()> {
+    let algorithm = match alg {
+        Algorithm::RS384 => &ring::digest::SHA384,
+        Algorithm::RS512 => &ring::digest::SHA512,
+        _ => &ring::digest::SHA256,
+    };
+    let digest = ring::digest::digest(algorithm, value.as_bytes());
+    let half = &digest.as_ref()[..digest.as_ref().len() / 2];
+
+    if URL_SAFE_NO_PAD.encode(half) != expected {
+        return AF::msg_result(
+            name,
+            format!("{name} in the id token does not match the authorization response"),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::identity::ResponseType;
+    use graph_core::identity::JwksKey;
+
+    fn url_params() -> OpenIdAuthorizationUrlParameters {
+        OpenIdAuthorizationUrlParameters::builder(Uuid::new_v4())
+            .with_response_type(vec![ResponseType::Code])
+            .with_state("state")
+            .build()
+    }
+
+    #[test]
+    fn state_mismatch() {
+        let response: AuthorizationResponse =
+            serde_urlencoded::from_str("code=code&state=other_state").unwrap();
+
+        let result = validate_authorization_response_with_jwks(
+            &url_params(),
+            &response,
+            &Default::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn code_response_without_id_token() {
+        let response: AuthorizationResponse =
+            serde_urlencoded::from_str("code=code&state=state").unwrap();
+
+        let result = validate_authorization_response_with_jwks(
+            &url_params(),
+            &response,
+            &Default::default(),
+        );
+        assert!(result.unwrap().is_none());
+    }
+
+    #[test]
+    fn missing_requested_id_token() {
+        let url_params = OpenIdAuthorizationUrlParameters::builder(Uuid::new_v4())
+            .with_response_type(vec![ResponseType::Code, ResponseType::IdToken])
+            .build();
+        let response: AuthorizationResponse = serde_urlencoded::from_str("code=code").unwrap();
+
+        let result =
+            validate_authorization_response_with_jwks(&url_params, &response, &Default::default());
+        assert!(result.is_err());
+    }
+
+    // Signs the claims with the test key and returns the id token and the JSON Web Key Set
+    // containing the public key of the test key.
+    fn signed_id_token(claims: serde_json::Value) -> (String, JwksKeySet) {
+        let private_key_pem = include_bytes!("test/key.pem");
+        let private_key_der: String = std::str::from_utf8(private_key_pem)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        let public_key = ring::rsa::PublicKeyComponents::<Vec<u8>>::from(
+            ring::signature::RsaKeyPair::from_pkcs8(
+                &base64::engine::general_purpose::STANDARD
+                    .decode(private_key_der)
+                    .unwrap(),
+            )
+            .unwrap()
+            .public(),
+        );
+
+        let mut header = jsonwebtoken::Header::new(Algorithm::RS256);
+        header.kid = Some("test_kid".into());
+        let id_token = jsonwebtoken::encode(
+            &header,
+            &claims,
+            &jsonwebtoken::EncodingKey::from_rsa_pem(private_key_pem).unwrap(),
+        )
+        .unwrap();
+
+        let jwks = JwksKeySet {
+            keys: [JwksKey::new(
+                "test_kid",
+                URL_SAFE_NO_PAD.encode(public_key.n),
+                URL_SAFE_NO_PAD.encode(public_key.e),
+            )]
+            .into(),
+        };
+        (id_token, jwks)
+    }
+
+    #[test]
+    fn valid_id_token() {
+        let tenant_id = Uuid::new_v4().to_string();
+        let url_params = OpenIdAuthorizationUrlParameters::builder(Uuid::new_v4())
+            .with_tenant(tenant_id.as_str())
+            .with_response_type(vec![ResponseType::Code, ResponseType::IdToken])
+            .with_state("state")
+            .build();
+        let code = "authorization_code";
+        let c_hash = URL_SAFE_NO_PAD
+            .encode(&ring::digest::digest(&ring::digest::SHA256, code.as_bytes()).as_ref()[..16]);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let (id_token, jwks) = signed_id_token(serde_json::json!({
+            "aud": url_params.app_config.client_id.to_string(),
+            "iss": format!("https://login.microsoftonline.com/{tenant_id}/v2.0"),
+            "iat": now,
+            "nbf": now,
+            "exp": now + 3600,
+            "tid": tenant_id,
+            "nonce": url_params.nonce(),
+            "c_hash": c_hash,
+        }));
+        let response: AuthorizationResponse =
+            serde_urlencoded::from_str(&format!("code={code}&id_token={id_token}&state=state"))
+                .unwrap();
+
+        let token_data = validate_authorization_response_with_jwks(&url_params, &response, &jwks)
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_data.claims.nonce.as_ref(), Some(url_params.nonce()));
+
+        let mut wrong_nonce = response.clone();
+        wrong_nonce.id_token = Some(
+            signed_id_token(serde_json::json!({
+                "aud": url_params.app_config.client_id.to_string(),
+                "iss": format!("https://login.microsoftonline.com/{tenant_id}/v2.0"),
+                "iat": now,
+                "nbf": now,
+                "exp": now + 3600,
+                "tid": tenant_id,
+                "nonce": "other_nonce",
+                "c_hash": c_hash,
+            }))
+            .0,
+        );
+        assert!(
+            validate_authorization_response_with_jwks(&url_params, &wrong_nonce, &jwks).is_err()
+        );
+
+        let mut wrong_code = response;
+        wrong_code.code = Some("other_code".into());
+        assert!(
+            validate_authorization_response_with_jwks(&url_params, &wrong_code, &jwks).is_err()
+        );
+    }
+
+    #[test]
+    fn hash_claims() {
+        // Examples from OpenID Connect Core 1.0 Appendix A.
+        validate_hash(
+            "at_hash",
+            Algorithm::RS256,
+            "jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y",
+            "77QmUPtjPfzWtF2AnpK9RQ",
+        )
+        .unwrap();
+        validate_hash(
+            "c_hash",
+            Algorithm::RS256,
+            "Qcb0Orv1zh30vL1MPRsbm-diHiMwcLyZvn1arpZv-Jxf_11jnpEX3Tgfvk",
+            "LDktKdoQak3Pk0cnXxCltA",
+        )
+        .unwrap();
+        assert!(
+            validate_hash("c_hash", Algorithm::RS256, "code", "LDktKdoQak3Pk0cnXxCltA").is_err()
+        );
+    }
+}
@@ -30,6 +30,20 @@ macro_rules! credential_builder_base {
                 self
             }
 
+            /// Use the authority host of a national cloud and replace the default
+            /// `https://graph.microsoft.com/.default` scope with the default scope of the cloud.
+            /// Give the same environment to the client with
+            /// [GraphClientConfiguration::cloud_environment](graph_http::api_impl::GraphClientConfiguration::cloud_environment).
+            pub fn with_cloud_environment(
+                &mut self,
+                cloud_environment: graph_core::identity::CloudEnvironment,
+            ) -> &mut Self {
+                self.credential
+                    .app_config
+                    .with_cloud_environment(cloud_environment);
+                self
+            }
+
             /// Extends the query parameters of both the default query params and user defined params.
             /// Does not overwrite default params.
             pub fn with_extra_query_param(&mut self, query_param: (String, String)) -> &mut Self {
@@ -91,6 +105,38 @@ macro_rules! credential_builder_base {
                 self.credential.app_config.with_config(config.clone());
                 self
             }
+
+            /// Set the scheme used when calling resources with the access token.
+            /// Use [AuthScheme::ProofOfPossession](graph_core::identity::AuthScheme::ProofOfPossession)
+            /// to request Proof-of-Possession tokens that are bound to a key and sign each request.
+            pub fn with_auth_scheme(
+                &mut self,
+                auth_scheme: graph_core::identity::AuthScheme,
+            ) -> &mut Self {
+                self.credential.app_config.with_auth_scheme(auth_scheme);
+                self
+            }
+
+            /// Set the client capabilities sent in the `xms_cc` claim of token requests.
+            /// Defaults to `cp1` which enables Continuous Access Evaluation (CAE).
+            pub fn with_client_capabilities<T: ToString, I: IntoIterator<Item = T>>(
+                &mut self,
+                client_capabilities: I,
+            ) -> &mut Self {
+                self.credential
+                    .app_config
+                    .with_client_capabilities(client_capabilities);
+                self
+            }
+
+            /// Set the token revocation endpoint used to revoke the refresh token
+            /// when signing out a personal Microsoft account.
+            pub fn with_revocation_uri(&mut self, revocation_uri: url::Url) -> &mut Self {
+                self.credential
+                    .app_config
+                    .with_revocation_uri(revocation_uri);
+                self
+            }
         }
     };
 }
@@ -123,6 +123,8 @@ impl Debug for ClientCertificateCredential {
     }
 }
 
+credential_sign_out_impl!(ClientCertificateCredential, client_assertion);
+
 #[async_trait]
 impl TokenCache for ClientCertificateCredential {
     type Token = Token;
@@ -130,6 +132,13 @@ impl TokenCache for ClientCertificateCredential {
     #[tracing::instrument]
     fn get_token_silent(&mut self) -> Result<Self::Token, AuthExecutionError> {
         let cache_id = self.app_config.cache_id.to_string();
+        if self.app_config.force_token_refresh != ForceTokenRefresh::Never {
+            tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; force_token_refresh=true");
+            let token_result = self.execute_cached_token_refresh(cache_id);
+            self.app_config.complete_forced_token_refresh();
+            return token_result;
+        }
+
         if let Some(token) = self.token_cache.get(cache_id.as_str()) {
             if token.is_expired_sub(time::Duration::minutes(5)) {
                 tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; refresh_token=None");
@@ -147,6 +156,13 @@ impl TokenCache for ClientCertificateCredential {
     #[tracing::instrument]
     async fn get_token_silent_async(&mut self) -> Result<Self::Token, AuthExecutionError> {
         let cache_id = self.app_config.cache_id.to_string();
+        if self.app_config.force_token_refresh != ForceTokenRefresh::Never {
+            tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; force_token_refresh=true");
+            let token_result = self.execute_cached_token_refresh_async(cache_id).await;
+            self.app_config.complete_forced_token_refresh();
+            return token_result;
+        }
+
         if let Some(token) = self.token_cache.get(cache_id.as_str()) {
             if token.is_expired_sub(time::Duration::minutes(5)) {
                 tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token refresh");
@@ -164,6 +180,10 @@ impl TokenCache for ClientCertificateCredential {
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh) {
         self.app_config.force_token_refresh = force_token_refresh;
     }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.app_config.with_claims_challenge(claims);
+    }
 }
 
 #[async_trait]
@@ -8,14 +8,15 @@ use url::Url;
 use uuid::Uuid;
 
 use graph_core::cache::{AsBearer, TokenCache};
-use graph_core::identity::{ClientApplication, ForceTokenRefresh};
+use graph_core::identity::{AuthScheme, ClientApplication, CloudEnvironment, ForceTokenRefresh};
 use graph_error::{AuthExecutionResult, IdentityResult};
 
 use crate::identity::{
-    AppConfig, Authority, AuthorizationCodeAssertionCredential,
+    Account, AppConfig, Authority, AuthorizationCodeAssertionCredential,
     AuthorizationCodeCertificateCredential, AuthorizationCodeCredential, AzureCloudInstance,
     ClientAssertionCredential, ClientCertificateCredential, ClientSecretCredential,
-    ConfidentialClientApplicationBuilder, OpenIdCredential, TokenCredentialExecutor,
+    ConfidentialClientApplicationBuilder, OpenIdCredential, SignOut, Token,
+    TokenCredentialExecutor,
 };
 
 /// Clients capable of maintaining the confidentiality of their credentials
@@ -75,6 +76,24 @@ impl<Credential: Clone + Debug + Send + Sync + TokenCache + TokenCredentialExecu
         self.credential
             .with_force_token_refresh(force_token_refresh);
     }
+
+    fn auth_scheme(&self) -> AuthScheme {
+        self.credential.app_config().auth_scheme.clone()
+    }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.credential.with_claims_challenge(claims);
+    }
+
+    fn authority_host(&self) -> Option<Url> {
+        Some(Url::from(
+            &self.credential.app_config().azure_cloud_instance,
+        ))
+    }
+
+    fn cloud_environment(&self) -> Option<CloudEnvironment> {
+        self.credential.app_config().cloud_environment.clone()
+    }
 }
 
 #[async_trait]
@@ -118,6 +137,30 @@ impl<Credential: Clone + Debug + Send + Sync + TokenCredentialExecutor> TokenCre
     }
 }
 
+impl<Credential: Clone + Debug + Send + Sync + SignOut> SignOut
+    for ConfidentialClientApplication<Credential>
+{
+    fn account(&self) -> Option<Account> {
+        self.credential.account()
+    }
+
+    fn account_token(&self, account: &Account) -> Option<Token> {
+        self.credential.account_token(account)
+    }
+
+    fn evict_account(&mut self, account: &Account) -> Option<Token> {
+        self.credential.evict_account(account)
+    }
+
+    fn evict_token(&mut self) -> Option<Token> {
+        self.credential.evict_token()
+    }
+
+    fn client_authentication(&self) -> HashMap<String, String> {
+        self.credential.client_authentication()
+    }
+}
+
 impl From<AuthorizationCodeCredential>
     for ConfidentialClientApplication<AuthorizationCodeCredential>
 {
@@ -235,4 +278,76 @@ mod test {
             credential_uri.as_str()
         );
     }
+
+    #[test]
+    fn proof_of_possession_token_request() {
+        let auth_scheme = AuthScheme::proof_of_possession().unwrap();
+        let req_cnf = match &auth_scheme {
+            AuthScheme::ProofOfPossession(key) => key.req_cnf(),
+            AuthScheme::Bearer => panic!("expected proof of possession auth scheme"),
+        };
+
+        let confidential_client = ConfidentialClientApplication::builder(Uuid::new_v4())
+            .with_client_secret("ALDSKFJLKERLKJALSDKJF2209LAKJGFL")
+            .with_tenant("tenant")
+            .with_auth_scheme(auth_scheme.clone())
+            .build();
+
+        assert_eq!(confidential_client.auth_scheme(), auth_scheme);
+
+        let mut credential = confidential_client.into_inner();
+        let request_parts = credential.request_parts().unwrap();
+        assert_eq!(
+            request_parts.form_urlencoded.get("token_type"),
+            Some(&"pop".to_string())
+        );
+        assert_eq!(request_parts.form_urlencoded.get("req_cnf"), Some(&req_cnf));
+    }
+
+    #[test]
+    fn claims_challenge_token_request() {
+        let mut confidential_client = ConfidentialClientApplication::builder(Uuid::new_v4())
+            .with_client_secret("ALDSKFJLKERLKJALSDKJF2209LAKJGFL")
+            .with_tenant("tenant")
+            .build();
+
+        let request_parts = confidential_client.credential.request_parts().unwrap();
+        let claims: serde_json::Value =
+            serde_json::from_str(request_parts.form_urlencoded.get("claims").unwrap()).unwrap();
+        assert_eq!(
+            claims,
+            serde_json::json!({"access_token": {"xms_cc": {"values": ["cp1"]}}})
+        );
+
+        confidential_client.with_claims_challenge(
+            r#"{"access_token":{"nbf":{"essential":true,"value":"1604106651"}}}"#.to_string(),
+        );
+
+        let request_parts = confidential_client.credential.request_parts().unwrap();
+        let claims: serde_json::Value =
+            serde_json::from_str(request_parts.form_urlencoded.get("claims").unwrap()).unwrap();
+        assert_eq!(
+            claims,
+            serde_json::json!({
+                "access_token": {
+                    "nbf": {"essential": true, "value": "1604106651"},
+                    "xms_cc": {"values": ["cp1"]}
+                }
+            })
+        );
+
+        confidential_client
+            .credential
+            .app_config
+            .complete_forced_token_refresh();
+        confidential_client.with_force_token_refresh(ForceTokenRefresh::Always);
+
+        let request_parts = confidential_client.credential.request_parts().unwrap();
+        let claims: serde_json::Value =
+            serde_json::from_str(request_parts.form_urlencoded.get("claims").unwrap()).unwrap();
+        assert_eq!(
+            claims,
+            serde_json::json!({"access_token": {"xms_cc": {"values": ["cp1"]}}})
+        );
+    }
 }
@@ -141,6 +141,8 @@ impl Debug for DeviceCodeCredential {
     }
 }
 
+credential_sign_out_impl!(DeviceCodeCredential);
+
 #[async_trait]
 impl TokenCache for DeviceCodeCredential {
     type Token = Token;
@@ -174,9 +176,7 @@ impl TokenCache for DeviceCodeCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh(cache_id);
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.with_force_token_refresh(ForceTokenRefresh::Never);
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -214,9 +214,7 @@ impl TokenCache for DeviceCodeCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh_async(cache_id).await;
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.with_force_token_refresh(ForceTokenRefresh::Never);
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -225,6 +223,10 @@ impl TokenCache for DeviceCodeCredential {
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh) {
         self.app_config.force_token_refresh = force_token_refresh;
     }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.app_config.with_claims_challenge(claims);
+    }
 }
 
 impl TokenCredentialExecutor for DeviceCodeCredential {
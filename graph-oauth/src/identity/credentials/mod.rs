@@ -6,6 +6,7 @@ pub use authorization_code_assertion_credential::*;
 pub use authorization_code_certificate_credential::*;
 pub use authorization_code_credential::*;
 pub use authorization_code_spa_credential::*;
+pub use authorization_response_validation::*;
 pub use bearer_token_credential::*;
 pub use client_assertion_credential::*;
 pub use client_certificate_credential::*;
@@ -21,12 +22,15 @@ pub use public_client_application::*;
 pub use resource_owner_password_credential::*;
 pub use response_mode::*;
 pub use response_type::*;
+pub use sign_out::*;
 pub use token_credential_executor::*;
 #[cfg(feature = "openssl")]
 pub use x509_certificate::*;
 
 #[macro_use]
 mod client_builder_impl;
+#[macro_use]
+mod sign_out;
 
 pub mod legacy;
 
@@ -38,6 +42,7 @@ mod authorization_code_assertion_credential;
 mod authorization_code_certificate_credential;
 mod authorization_code_credential;
 mod authorization_code_spa_credential;
+mod authorization_response_validation;
 mod bearer_token_credential;
 mod client_assertion_credential;
 mod client_certificate_credential;
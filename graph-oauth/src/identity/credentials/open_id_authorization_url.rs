@@ -11,8 +11,8 @@ use graph_error::{AuthorizationFailure, IdentityResult, AF};
 
 use crate::identity::credentials::app_config::AppConfig;
 use crate::identity::{
-    AsQuery, Authority, AuthorizationUrl, AzureCloudInstance, OpenIdCredentialBuilder, Prompt,
-    ResponseMode, ResponseType,
+    AsQuery, Authority, AuthorizationResponse, AuthorizationUrl, AzureCloudInstance,
+    OpenIdCredentialBuilder, Prompt, ResponseMode, ResponseType,
 };
 use crate::oauth_serializer::{AuthParameter, AuthSerializer};
 
@@ -20,7 +20,6 @@ use crate::identity::tracing_targets::CREDENTIAL_EXECUTOR;
 
 #[cfg(feature = "interactive-auth")]
 use {
-    crate::identity::AuthorizationResponse,
     crate::interactive::{
         HostOptions, InteractiveAuthEvent, UserEvents, WebViewAuth, WebViewAuthorizationEvent,
         WebViewHostValidator, WebViewOptions,
@@ -174,7 +173,28 @@ impl OpenIdAuthorizationUrlParameters {
         OpenIdAuthorizationUrlParameterBuilder::new(client_id)
     }
 
-    pub fn into_credential(self, authorization_code: impl AsRef<str>) -> OpenIdCredentialBuilder {
+    /// Create the credential from the authorization response returned to the redirect uri.
+    ///
+    /// The state, id token signature, nonce and `c_hash` of the response are validated before
+    /// the authorization code is redeemed and the code is not sent if validation fails.
+    /// See [`validate_authorization_response`](crate::identity::validate_authorization_response).
+    pub fn into_credential(
+        self,
+        authorization_response: AuthorizationResponse,
+    ) -> OpenIdCredentialBuilder {
+        OpenIdCredentialBuilder::from((self, authorization_response))
+    }
+
+    /// Create the credential from an authorization code without validating the
+    /// authorization response.
+    ///
+    /// Only use this when the authorization response has already been validated, such as
+    /// with [`validate_authorization_response`](crate::identity::validate_authorization_response).
+    /// Otherwise use [`into_credential`](Self::into_credential).
+    pub fn into_credential_unvalidated(
+        self,
+        authorization_code: impl AsRef<str>,
+    ) -> OpenIdCredentialBuilder {
         OpenIdCredentialBuilder::new_with_auth_code(self.app_config, authorization_code)
     }
 
@@ -260,10 +280,14 @@ impl OpenIdAuthorizationUrlParameters {
 
                     tracing::debug!(target: "graph_rs_sdk::interactive_auth", "parsed authorization query or fragment from redirect uri");
 
-                    let mut credential_builder = OpenIdCredentialBuilder::from((
-                        self.app_config.clone(),
-                        authorization_response.clone(),
-                    ));
+                    crate::identity::validate_authorization_response(self, &authorization_response)
+                        .map_err(Box::new)?;
+
+                    let mut credential_builder =
+                        OpenIdCredentialBuilder::new_with_authorization_response(
+                            self.app_config.clone(),
+                            authorization_response.clone(),
+                        );
 
                     credential_builder.with_client_secret(client_secret);
 
@@ -633,7 +657,7 @@ mod test {
             .with_response_type([ResponseType::Code])
             .with_scope(["user.read"])
             .build();
-        let mut credential = url_builder.into_credential("code");
+        let mut credential = url_builder.into_credential_unvalidated("code");
         let confidential_client = credential
             .with_client_secret("secret")
             .with_tenant("tenant")
@@ -644,4 +668,21 @@ mod test {
             client_id.to_string()
         );
     }
+
+    #[test]
+    fn into_credential_validates_authorization_response() {
+        let url_builder = OpenIdAuthorizationUrlParameters::builder(Uuid::new_v4())
+            .with_response_type([ResponseType::Code])
+            .with_state("state")
+            .build();
+        let response: AuthorizationResponse =
+            serde_urlencoded::from_str("code=code&state=other_state").unwrap();
+        let mut credential = url_builder
+            .into_credential(response)
+            .with_client_secret("secret")
+            .credential()
+            .clone();
+
+        assert!(credential.execute().is_err());
+    }
 }
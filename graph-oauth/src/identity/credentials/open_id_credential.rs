@@ -19,9 +19,9 @@ use graph_error::{AuthExecutionError, AuthExecutionResult, IdentityResult, AF};
 
 use crate::identity::credentials::app_config::{AppConfig, AppConfigBuilder};
 use crate::identity::{
-    Authority, AuthorizationResponse, AzureCloudInstance, ConfidentialClientApplication, IdToken,
-    OpenIdAuthorizationUrlParameterBuilder, OpenIdAuthorizationUrlParameters, Token,
-    TokenCredentialExecutor,
+    tracing_targets::CREDENTIAL_EXECUTOR, Authority, AuthorizationResponse, AzureCloudInstance,
+    ConfidentialClientApplication, IdToken, OpenIdAuthorizationUrlParameterBuilder,
+    OpenIdAuthorizationUrlParameters, Token, TokenCredentialExecutor,
 };
 use crate::internal::{AuthParameter, AuthSerializer};
 
@@ -63,6 +63,10 @@ pub struct OpenIdCredential {
     /// Used only when the client generates the pkce itself when the generate method
     /// is called.
     pub(crate) pkce: Option<ProofKeyCodeExchange>,
+    /// The authorization request and the response it returned. The response is validated
+    /// before the authorization code is redeemed.
+    pub(crate) authorization_response:
+        Option<(OpenIdAuthorizationUrlParameters, AuthorizationResponse)>,
     serializer: AuthSerializer,
     token_cache: InMemoryCacheStore<Token>,
 }
@@ -93,6 +97,7 @@ impl OpenIdCredential {
             client_secret: client_secret.as_ref().to_owned(),
             code_verifier: None,
             pkce: None,
+            authorization_response: None,
             serializer: Default::default(),
             token_cache: Default::default(),
         })
@@ -119,6 +124,29 @@ impl OpenIdCredential {
         self.pkce.as_ref()
     }
 
+    /// Validates the authorization response before its authorization code is redeemed.
+    /// See [`validate_authorization_response`](crate::identity::validate_authorization_response).
+    fn validate_authorization_response(&mut self) -> AuthExecutionResult<()> {
+        if self.refresh_token.is_none() && self.authorization_code.is_some() {
+            if let Some((url_params, response)) = self.authorization_response.as_ref() {
+                crate::identity::validate_authorization_response(url_params, response)?;
+                self.authorization_response = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn validate_authorization_response_async(&mut self) -> AuthExecutionResult<()> {
+        if self.refresh_token.is_none() && self.authorization_code.is_some() {
+            if let Some((url_params, response)) = self.authorization_response.as_ref() {
+                crate::identity::validate_authorization_response_async(url_params, response)
+                    .await?;
+                self.authorization_response = None;
+            }
+        }
+        Ok(())
+    }
+
     fn execute_cached_token_refresh(&mut self, cache_id: String) -> AuthExecutionResult<Token> {
         let response = self.execute()?;
 
@@ -161,6 +189,8 @@ impl OpenIdCredential {
     }
 }
 
+credential_sign_out_impl!(OpenIdCredential, client_secret);
+
 #[async_trait]
 impl TokenCache for OpenIdCredential {
     type Token = Token;
@@ -194,9 +224,7 @@ impl TokenCache for OpenIdCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh(cache_id);
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -234,9 +262,7 @@ impl TokenCache for OpenIdCredential {
             }
             ForceTokenRefresh::Once | ForceTokenRefresh::Always => {
                 let token_result = self.execute_cached_token_refresh_async(cache_id).await;
-                if self.app_config.force_token_refresh == ForceTokenRefresh::Once {
-                    self.app_config.force_token_refresh = ForceTokenRefresh::Never;
-                }
+                self.app_config.complete_forced_token_refresh();
                 token_result
             }
         }
@@ -245,6 +271,10 @@ impl TokenCache for OpenIdCredential {
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh) {
         self.app_config.force_token_refresh = force_token_refresh;
     }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.app_config.with_claims_challenge(claims);
+    }
 }
 
 #[async_trait]
@@ -349,6 +379,29 @@ impl TokenCredentialExecutor for OpenIdCredential {
     fn app_config(&self) -> &AppConfig {
         &self.app_config
     }
+
+    fn execute(&mut self) -> AuthExecutionResult<reqwest::blocking::Response> {
+        self.validate_authorization_response()?;
+        let (request_builder, minimal_blocking_client) = self.build_request()?;
+        let request = request_builder.build()?;
+        let response = minimal_blocking_client.inner.execute(request)?;
+        let status = response.status();
+        tracing::debug!(target: CREDENTIAL_EXECUTOR, "authorization response received; status={status:#?}");
+        Ok(response)
+    }
+
+    async fn execute_async(&mut self) -> AuthExecutionResult<reqwest::Response> {
+        self.validate_authorization_response_async().await?;
+        let (request_builder, mut minimal_async_client) = self.build_request_async().await?;
+        let request = request_builder.build()?;
+        let response = minimal_async_client
+            .execute(request)
+            .await
+            .map_err(AuthExecutionError::from)?;
+        let status = response.status();
+        tracing::debug!(target: CREDENTIAL_EXECUTOR, "authorization response received; status={status:#?}");
+        Ok(response)
+    }
 }
 
 #[derive(Clone)]
@@ -377,6 +430,7 @@ impl OpenIdCredentialBuilder {
                 client_secret: String::new(),
                 code_verifier: None,
                 pkce: None,
+                authorization_response: None,
                 serializer: Default::default(),
                 token_cache: Default::default(),
             },
@@ -393,6 +447,7 @@ impl OpenIdCredentialBuilder {
                 client_secret: String::new(),
                 code_verifier: None,
                 pkce: None,
+                authorization_response: None,
                 serializer: Default::default(),
                 token_cache: Default::default(),
             },
@@ -412,6 +467,7 @@ impl OpenIdCredentialBuilder {
                 client_secret: Default::default(),
                 code_verifier: None,
                 pkce: None,
+                authorization_response: None,
                 serializer: Default::default(),
                 token_cache: Default::default(),
             },
@@ -432,6 +488,7 @@ impl OpenIdCredentialBuilder {
                 client_secret: client_secret.as_ref().to_owned(),
                 code_verifier: None,
                 pkce: None,
+                authorization_response: None,
                 serializer: Default::default(),
                 token_cache: Default::default(),
             },
@@ -451,21 +508,49 @@ impl OpenIdCredentialBuilder {
                 client_secret: Default::default(),
                 code_verifier: None,
                 pkce: None,
+                authorization_response: None,
                 serializer: Default::default(),
                 token_cache,
             },
         }
     }
 
+    /// Creates the credential from an authorization response that has already been validated.
+    pub(crate) fn new_with_authorization_response(
+        mut app_config: AppConfig,
+        authorization_response: AuthorizationResponse,
+    ) -> OpenIdCredentialBuilder {
+        if let Some(authorization_code) = authorization_response.code.as_ref() {
+            if let Some(id_token) = authorization_response.id_token.as_ref() {
+                app_config.with_id_token(IdToken::new(
+                    id_token.as_ref(),
+                    None,
+                    Some(authorization_code.as_ref()),
+                    None,
+                ));
+                OpenIdCredentialBuilder::new_with_auth_code(app_config, authorization_code)
+            } else {
+                OpenIdCredentialBuilder::new_with_auth_code(app_config, authorization_code)
+            }
+        } else {
+            OpenIdCredentialBuilder::new_with_token(
+                app_config,
+                Token::try_from(authorization_response.clone()).unwrap_or_default(),
+            )
+        }
+    }
+
     pub fn with_authorization_code<T: AsRef<str>>(&mut self, authorization_code: T) -> &mut Self {
         self.credential.authorization_code = Some(authorization_code.as_ref().to_owned());
         self.credential.refresh_token = None;
+        self.credential.authorization_response = None;
         self
     }
 
     pub fn with_refresh_token<T: AsRef<str>>(&mut self, refresh_token: T) -> &mut Self {
         self.credential.authorization_code = None;
         self.credential.refresh_token = Some(refresh_token.as_ref().to_owned());
+        self.credential.authorization_response = None;
         self
     }
 
@@ -514,27 +599,23 @@ impl From<OpenIdCredential> for OpenIdCredentialBuilder {
     }
 }
 
-impl From<(AppConfig, AuthorizationResponse)> for OpenIdCredentialBuilder {
-    fn from(value: (AppConfig, AuthorizationResponse)) -> Self {
-        let (mut app_config, authorization_response) = value;
-        if let Some(authorization_code) = authorization_response.code.as_ref() {
-            if let Some(id_token) = authorization_response.id_token.as_ref() {
-                app_config.with_id_token(IdToken::new(
-                    id_token.as_ref(),
-                    None,
-                    Some(authorization_code.as_ref()),
-                    None,
-                ));
-                OpenIdCredentialBuilder::new_with_auth_code(app_config, authorization_code)
-            } else {
-                OpenIdCredentialBuilder::new_with_auth_code(app_config, authorization_code)
-            }
-        } else {
-            OpenIdCredentialBuilder::new_with_token(
-                app_config,
-                Token::try_from(authorization_response.clone()).unwrap_or_default(),
-            )
+/// Creates the credential from the authorization request and the response it returned.
+///
+/// The response is validated, including the state, the id token signature, the nonce and the
+/// `c_hash` claim, when the authorization code is redeemed. The code is not sent if validation
+/// fails. See [`validate_authorization_response`](crate::identity::validate_authorization_response).
+impl From<(OpenIdAuthorizationUrlParameters, AuthorizationResponse)> for OpenIdCredentialBuilder {
+    fn from(value: (OpenIdAuthorizationUrlParameters, AuthorizationResponse)) -> Self {
+        let (url_params, authorization_response) = value;
+        let mut credential_builder = OpenIdCredentialBuilder::new_with_authorization_response(
+            url_params.app_config.clone(),
+            authorization_response.clone(),
+        );
+        if authorization_response.code.is_some() {
+            credential_builder.credential.authorization_response =
+                Some((url_params, authorization_response));
         }
+        credential_builder
     }
 }
 
@@ -559,4 +640,57 @@ mod test {
 
         assert_eq!(credential.authority().as_ref(), "adfs");
     }
+
+    fn url_params() -> OpenIdAuthorizationUrlParameters {
+        OpenIdAuthorizationUrlParameters::builder(Uuid::new_v4())
+            .with_response_type(vec![crate::identity::ResponseType::Code])
+            .with_state("state")
+            .build()
+    }
+
+    #[test]
+    fn authorization_response_state_mismatch() {
+        let response: AuthorizationResponse =
+            serde_urlencoded::from_str("code=code&state=other_state").unwrap();
+        let mut credential = OpenIdCredentialBuilder::from((url_params(), response))
+            .with_client_secret("secret")
+            .credential
+            .clone();
+
+        let result = credential.execute();
+        assert!(matches!(
+            result,
+            Err(AuthExecutionError::Authorization(AF::RequiredValue { ref name, .. })) if name == "state"
+        ));
+        assert!(credential.authorization_response.is_some());
+    }
+
+    #[tokio::test]
+    async fn authorization_response_state_mismatch_async() {
+        let response: AuthorizationResponse =
+            serde_urlencoded::from_str("code=code&state=other_state").unwrap();
+        let mut credential = OpenIdCredentialBuilder::from((url_params(), response))
+            .with_client_secret("secret")
+            .credential
+            .clone();
+
+        let result = credential.execute_async().await;
+        assert!(matches!(
+            result,
+            Err(AuthExecutionError::Authorization(AF::RequiredValue { ref name, .. })) if name == "state"
+        ));
+    }
+
+    #[test]
+    fn authorization_response_validated_once() {
+        let response: AuthorizationResponse =
+            serde_urlencoded::from_str("code=code&state=state").unwrap();
+        let mut credential = OpenIdCredentialBuilder::from((url_params(), response))
+            .credential
+            .clone();
+        assert_eq!(credential.authorization_code.as_deref(), Some("code"));
+
+        credential.validate_authorization_response().unwrap();
+        assert!(credential.authorization_response.is_none());
+    }
 }
@@ -1,12 +1,12 @@
 use crate::identity::credentials::app_config::AppConfig;
 use crate::identity::credentials::application_builder::PublicClientApplicationBuilder;
 use crate::identity::{
-    Authority, AuthorizationCodeSpaCredential, AzureCloudInstance, DeviceCodeCredential,
-    ResourceOwnerPasswordCredential, TokenCredentialExecutor,
+    Account, Authority, AuthorizationCodeSpaCredential, AzureCloudInstance, DeviceCodeCredential,
+    ResourceOwnerPasswordCredential, SignOut, Token, TokenCredentialExecutor,
 };
 use async_trait::async_trait;
 use graph_core::cache::{AsBearer, TokenCache};
-use graph_core::identity::{ClientApplication, ForceTokenRefresh};
+use graph_core::identity::{AuthScheme, ClientApplication, CloudEnvironment, ForceTokenRefresh};
 use graph_error::{AuthExecutionResult, IdentityResult};
 use reqwest::Response;
 use std::collections::HashMap;
@@ -44,8 +44,8 @@ impl<Credential: Clone + Debug + Send + Sync + TokenCredentialExecutor>
 }
 
 #[async_trait]
-impl<Credential: Clone + Debug + Send + Sync + TokenCache> ClientApplication
-    for PublicClientApplication<Credential>
+impl<Credential: Clone + Debug + Send + Sync + TokenCache + TokenCredentialExecutor>
+    ClientApplication for PublicClientApplication<Credential>
 {
     fn get_token_silent(&mut self) -> AuthExecutionResult<String> {
         let token = self.credential.get_token_silent()?;
@@ -61,6 +61,24 @@ impl<Credential: Clone + Debug + Send + Sync + TokenCache> ClientApplication
         self.credential
             .with_force_token_refresh(force_token_refresh);
     }
+
+    fn auth_scheme(&self) -> AuthScheme {
+        self.credential.app_config().auth_scheme.clone()
+    }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.credential.with_claims_challenge(claims);
+    }
+
+    fn authority_host(&self) -> Option<Url> {
+        Some(Url::from(
+            &self.credential.app_config().azure_cloud_instance,
+        ))
+    }
+
+    fn cloud_environment(&self) -> Option<CloudEnvironment> {
+        self.credential.app_config().cloud_environment.clone()
+    }
 }
 
 #[async_trait]
@@ -100,6 +118,26 @@ impl<Credential: Clone + Debug + Send + Sync + TokenCredentialExecutor> TokenCre
     }
 }
 
+impl<Credential: Clone + Debug + Send + Sync + SignOut> SignOut
+    for PublicClientApplication<Credential>
+{
+    fn account(&self) -> Option<Account> {
+        self.credential.account()
+    }
+
+    fn account_token(&self, account: &Account) -> Option<Token> {
+        self.credential.account_token(account)
+    }
+
+    fn evict_account(&mut self, account: &Account) -> Option<Token> {
+        self.credential.evict_account(account)
+    }
+
+    fn evict_token(&mut self) -> Option<Token> {
+        self.credential.evict_token()
+    }
+}
+
 impl From<ResourceOwnerPasswordCredential>
     for PublicClientApplication<ResourceOwnerPasswordCredential>
 {
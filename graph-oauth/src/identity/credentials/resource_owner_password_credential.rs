@@ -110,12 +110,21 @@ impl ResourceOwnerPasswordCredential {
     }
 }
 
+credential_sign_out_impl!(ResourceOwnerPasswordCredential);
+
 #[async_trait]
 impl TokenCache for ResourceOwnerPasswordCredential {
     type Token = Token;
 
     fn get_token_silent(&mut self) -> Result<Self::Token, AuthExecutionError> {
         let cache_id = self.app_config.cache_id.to_string();
+        if self.app_config.force_token_refresh != ForceTokenRefresh::Never {
+            tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; force_token_refresh=true");
+            let token_result = self.execute_cached_token_refresh(cache_id);
+            self.app_config.complete_forced_token_refresh();
+            return token_result;
+        }
+
         if let Some(token) = self.token_cache.get(cache_id.as_str()) {
             if token.is_expired_sub(time::Duration::minutes(5)) {
                 tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; refresh_token=None");
@@ -132,6 +141,13 @@ impl TokenCache for ResourceOwnerPasswordCredential {
 
     async fn get_token_silent_async(&mut self) -> Result<Self::Token, AuthExecutionError> {
         let cache_id = self.app_config.cache_id.to_string();
+        if self.app_config.force_token_refresh != ForceTokenRefresh::Never {
+            tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; force_token_refresh=true");
+            let token_result = self.execute_cached_token_refresh_async(cache_id).await;
+            self.app_config.complete_forced_token_refresh();
+            return token_result;
+        }
+
         if let Some(token) = self.token_cache.get(cache_id.as_str()) {
             if token.is_expired_sub(time::Duration::minutes(5)) {
                 tracing::debug!(target: CREDENTIAL_EXECUTOR, "executing silent token request; refresh_token=None");
@@ -149,6 +165,10 @@ impl TokenCache for ResourceOwnerPasswordCredential {
     fn with_force_token_refresh(&mut self, force_token_refresh: ForceTokenRefresh) {
         self.app_config.force_token_refresh = force_token_refresh;
     }
+
+    fn with_claims_challenge(&mut self, claims: String) {
+        self.app_config.with_claims_challenge(claims);
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,420 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use graph_error::{AuthExecutionError, AuthExecutionResult, IdentityResult};
+use url::Url;
+
+use crate::identity::{
+    tracing_targets::CREDENTIAL_EXECUTOR, Account, Token, TokenCredentialExecutor,
+};
+use crate::oauth_serializer::AuthParameter;
+
+/// Implements [SignOut] for a credential with `app_config` and `token_cache` fields.
+/// Confidential clients pass `client_secret` or `client_assertion` so that the revocation
+/// request is sent with the client authentication of the credential.
+macro_rules! credential_sign_out_impl {
+    ($name:ident) => {
+        credential_sign_out_impl!(@impl $name,);
+    };
+    ($name:ident, client_secret) => {
+        credential_sign_out_impl!(@impl $name,
+            fn client_authentication(&self) -> std::collections::HashMap<String, String> {
+                std::collections::HashMap::from([(
+                    $crate::oauth_serializer::AuthParameter::ClientSecret
+                        .alias()
+                        .to_owned(),
+                    self.client_secret.clone(),
+                )])
+            }
+        );
+    };
+    ($name:ident, client_assertion) => {
+        credential_sign_out_impl!(@impl $name,
+            fn client_authentication(&self) -> std::collections::HashMap<String, String> {
+                let client_assertion_type = match self.client_assertion_type.trim() {
+                    "" => $crate::identity::CLIENT_ASSERTION_TYPE.to_owned(),
+                    client_assertion_type => client_assertion_type.to_owned(),
+                };
+                std::collections::HashMap::from([
+                    (
+                        $crate::oauth_serializer::AuthParameter::ClientAssertion
+                            .alias()
+                            .to_owned(),
+                        self.client_assertion.clone(),
+                    ),
+                    (
+                        $crate::oauth_serializer::AuthParameter::ClientAssertionType
+                            .alias()
+                            .to_owned(),
+                        client_assertion_type,
+                    ),
+                ])
+            }
+        );
+    };
+    (@impl $name:ident, $($client_authentication:item)?) => {
+        impl $crate::identity::SignOut for $name {
+            $($client_authentication)?
+
+            fn account(&self) -> Option<$crate::identity::Account> {
+                let token = self.token_cache.get(self.app_config.cache_id.as_str())?;
+                $crate::identity::Account::try_from(&token).ok()
+            }
+
+            fn account_token(
+                &self,
+                account: &$crate::identity::Account,
+            ) -> Option<$crate::identity::Token> {
+                let token = self.token_cache.get(self.app_config.cache_id.as_str())?;
+                if $crate::identity::Account::try_from(&token).ok().as_ref() != Some(account) {
+                    return None;
+                }
+                Some(token)
+            }
+
+            fn evict_account(
+                &mut self,
+                account: &$crate::identity::Account,
+            ) -> Option<$crate::identity::Token> {
+                if self.account().as_ref() != Some(account) {
+                    return None;
+                }
+                self.token_cache.evict(self.app_config.cache_id.as_str())
+            }
+
+            fn evict_token(&mut self) -> Option<$crate::identity::Token> {
+                self.token_cache.evict(self.app_config.cache_id.as_str())
+            }
+        }
+    };
+}
+
+/// Sign out users of a credential.
+///
+/// Signing out removes the tokens of the account from the credential's token cache and,
+/// for personal Microsoft accounts, revokes the refresh token when a revocation endpoint
+/// has been set using `with_revocation_uri`. The refresh token is revoked before the
+/// token is removed from the cache so a failed sign out can be retried. The user's session with the identity platform
+/// is ended by sending the user to the [end_session_url](SignOut::end_session_url).
+#[async_trait]
+pub trait SignOut: TokenCredentialExecutor {
+    /// The account of the token in the token cache, if any.
+    fn account(&self) -> Option<Account>;
+
+    /// The token of the account in the token cache, if any, without removing it.
+    fn account_token(&self, account: &Account) -> Option<Token>;
+
+    /// Remove the token of the account from the token cache and return it.
+    fn evict_account(&mut self, account: &Account) -> Option<Token>;
+
+    /// Remove the token of the credential from the token cache and return it, whether or
+    /// not the token has an account. Used for tokens of the client credentials flow.
+    fn evict_token(&mut self) -> Option<Token>;
+
+    /// Url of the end session endpoint of the authority. Sending the user to this url
+    /// signs the user out of the identity platform.
+    fn end_session_url(
+        &self,
+        post_logout_redirect_uri: Option<&Url>,
+        id_token_hint: Option<&str>,
+        state: Option<&str>,
+    ) -> IdentityResult<Url> {
+        let mut uri = self
+            .azure_cloud_instance()
+            .end_session_uri(&self.authority())?;
+
+        {
+            let mut query = uri.query_pairs_mut();
+            if let Some(post_logout_redirect_uri) = post_logout_redirect_uri {
+                query.append_pair(
+                    AuthParameter::PostLogoutRedirectUri.alias(),
+                    post_logout_redirect_uri.as_str(),
+                );
+            }
+            if let Some(id_token_hint) = id_token_hint {
+                query.append_pair(AuthParameter::IdTokenHint.alias(), id_token_hint);
+            }
+            if let Some(state) = state {
+                query.append_pair(AuthParameter::State.alias(), state);
+            }
+        }
+
+        if uri.query() == Some("") {
+            uri.set_query(None);
+        }
+
+        Ok(uri)
+    }
+
+    /// Parameters that authenticate a confidential client in the revocation request,
+    /// such as `client_secret`. Public clients only send their `client_id`.
+    fn client_authentication(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// Form for the revocation request of the refresh token as defined in
+    /// [RFC 7009](https://datatracker.ietf.org/doc/html/rfc7009#section-2.1)
+    fn revocation_form(&self, refresh_token: &str) -> HashMap<String, String> {
+        let mut form = HashMap::from([
+            (
+                AuthParameter::Token.alias().to_owned(),
+                refresh_token.to_owned(),
+            ),
+            (
+                AuthParameter::TokenTypeHint.alias().to_owned(),
+                AuthParameter::RefreshToken.alias().to_owned(),
+            ),
+            (
+                AuthParameter::ClientId.alias().to_owned(),
+                self.client_id().to_string(),
+            ),
+        ]);
+        form.extend(self.client_authentication());
+        form
+    }
+
+    /// Revocation endpoint and refresh token when the refresh token of the account
+    /// should be revoked.
+    fn revocation_parts(&self, account: &Account, token: &Token) -> Option<(Url, String)> {
+        if !account.is_personal_account() {
+            return None;
+        }
+        let revocation_uri = self.app_config().revocation_uri.clone()?;
+        let refresh_token = token.refresh_token.clone()?;
+        Some((revocation_uri, refresh_token))
+    }
+
+    /// Sign out the account. Returns true if the account had a token in the token cache.
+    ///
+    /// If revoking the refresh token fails the error is returned and the token stays in
+    /// the token cache.
+    fn sign_out(&mut self, account: &Account) -> AuthExecutionResult<bool> {
+        let Some(token) = self.account_token(account) else {
+            return Ok(false);
+        };
+
+        if let Some((revocation_uri, refresh_token)) = self.revocation_parts(account, &token) {
+            let http_client = self.config().clone().try_build_minimal_blocking_client()?;
+            let response = http_client
+                .inner
+                .post(revocation_uri)
+                .form(&self.revocation_form(refresh_token.as_str()))
+                .send()?;
+
+            if !response.status().is_success() {
+                return Err(AuthExecutionError::revocation(
+                    graph_core::http::ResponseConverterExt::into_http_response(response)?,
+                ));
+            }
+            tracing::debug!(target: CREDENTIAL_EXECUTOR, "refresh token revoked");
+        }
+
+        self.evict_account(account);
+        Ok(true)
+    }
+
+    /// Sign out the account. Returns true if the account had a token in the token cache.
+    ///
+    /// If revoking the refresh token fails the error is returned and the token stays in
+    /// the token cache.
+    async fn sign_out_async(&mut self, account: &Account) -> AuthExecutionResult<bool> {
+        let Some(token) = self.account_token(account) else {
+            return Ok(false);
+        };
+
+        if let Some((revocation_uri, refresh_token)) = self.revocation_parts(account, &token) {
+            let mut http_client = self.config().clone().try_build_minimal_async_client()?;
+            let request = http_client
+                .inner
+                .post(revocation_uri)
+                .form(&self.revocation_form(refresh_token.as_str()))
+                .build()?;
+            let response = http_client
+                .execute(request)
+                .await
+                .map_err(AuthExecutionError::from)?;
+
+            if !response.status().is_success() {
+                return Err(AuthExecutionError::revocation(
+                    graph_core::http::AsyncResponseConverterExt::into_http_response_async(response)
+                        .await?,
+                ));
+            }
+            tracing::debug!(target: CREDENTIAL_EXECUTOR, "refresh token revoked");
+        }
+
+        self.evict_account(account);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::identity::{
+        AppConfig, AuthorizationCodeCredentialBuilder, IdToken, PERSONAL_ACCOUNT_TENANT_ID,
+    };
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use graph_http::api_impl::GraphClientConfiguration;
+    use uuid::Uuid;
+
+    fn id_token(oid: &str, tid: &str) -> IdToken {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(
+            serde_json::json!({
+                "oid": oid,
+                "tid": tid,
+                "preferred_username": "user@contoso.com"
+            })
+            .to_string(),
+        );
+        IdToken::new(&format!("{header}.{payload}."), None, None, None)
+    }
+
+    #[test]
+    fn end_session_url() {
+        let confidential_client = AuthorizationCodeCredentialBuilder::new_with_token(
+            AppConfig::builder(Uuid::new_v4()).tenant("tenant").build(),
+            Token::default(),
+        )
+        .build();
+
+        let url = confidential_client
+            .end_session_url(
+                Some(&Url::parse("http://localhost:8000/signed-out").unwrap()),
+                None,
+                Some("state"),
+            )
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://login.microsoftonline.com/tenant/oauth2/v2.0/logout?post_logout_redirect_uri=http%3A%2F%2Flocalhost%3A8000%2Fsigned-out&state=state"
+        );
+    }
+
+    #[test]
+    fn sign_out_evicts_account_token() {
+        let mut token = Token::new("Bearer", 3600, "access_token", vec!["User.Read"]);
+        token.with_id_token(id_token("oid", "tenant"));
+
+        let mut confidential_client = AuthorizationCodeCredentialBuilder::new_with_token(
+            AppConfig::builder(Uuid::new_v4()).tenant("tenant").build(),
+            token,
+        )
+        .build();
+
+        let account = confidential_client.account().unwrap();
+        assert_eq!(account.home_account_id, "oid.tenant");
+
+        let mut other_account = account.clone();
+        other_account.home_account_id = "other.tenant".into();
+        assert!(!confidential_client.sign_out(&other_account).unwrap());
+        assert!(confidential_client.account().is_some());
+
+        assert!(confidential_client.sign_out(&account).unwrap());
+        assert!(confidential_client.account().is_none());
+    }
+
+    #[test]
+    fn failed_revocation_keeps_account_token() {
+        let mut token = Token::new("Bearer", 3600, "access_token", vec!["User.Read"]);
+        token.with_id_token(id_token("oid", PERSONAL_ACCOUNT_TENANT_ID));
+        token.with_refresh_token("refresh_token");
+
+        let mut builder = AuthorizationCodeCredentialBuilder::new_with_token(
+            AppConfig::builder(Uuid::new_v4())
+                .tenant("consumers")
+                .build(),
+            token,
+        );
+        builder.with_revocation_uri(Url::parse("http://127.0.0.1:1/revoke").unwrap());
+        let mut confidential_client = builder.build();
+
+        let account = confidential_client.account().unwrap();
+        assert!(account.is_personal_account());
+        assert!(confidential_client.sign_out(&account).is_err());
+        assert_eq!(Some(account), confidential_client.account());
+    }
+
+    /// Serves one request with a `400 Bad Request` and returns the request that was sent.
+    fn revocation_server() -> (Url, std::thread::JoinHandle<String>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/revoke", listener.local_addr().unwrap())).unwrap();
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.push_str(&String::from_utf8_lossy(&buf[..n]));
+                let complete = request.split_once("\r\n\r\n").map(|(headers, body)| {
+                    let content_length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .and_then(|value| value.trim().parse::<usize>().ok())
+                        })
+                        .unwrap_or_default();
+                    body.len() >= content_length
+                });
+                if n == 0 || complete.unwrap_or_default() {
+                    break;
+                }
+            }
+
+            let body = r#"{"error":"invalid_grant","error_description":"AADSTS70000: The provided value for the 'refresh_token' is not valid."}"#;
+            write!(
+                stream,
+                "HTTP/1.1 400 Bad Request\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            request
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn revocation_error_authenticates_client() {
+        let mut token = Token::new("Bearer", 3600, "access_token", vec!["User.Read"]);
+        token.with_id_token(id_token("oid", PERSONAL_ACCOUNT_TENANT_ID));
+        token.with_refresh_token("refresh_token");
+
+        let (revocation_uri, server) = revocation_server();
+        let mut builder = AuthorizationCodeCredentialBuilder::new_with_token(
+            AppConfig::builder(Uuid::new_v4())
+                .tenant("consumers")
+                .build(),
+            token,
+        );
+        builder
+            .with_client_secret("client_secret")
+            .with_config(&GraphClientConfiguration::new().https_only(false))
+            .with_revocation_uri(revocation_uri);
+        let mut confidential_client = builder.build();
+
+        let account = confidential_client.account().unwrap();
+        let error = confidential_client.sign_out(&account).unwrap_err();
+        assert!(matches!(error, AuthExecutionError::Revocation { .. }));
+        assert!(matches!(
+            error.identity_error(),
+            Some(graph_error::IdentityError::InvalidGrant(_))
+        ));
+        assert_eq!(Some(account), confidential_client.account());
+
+        let request = server.join().unwrap();
+        let form: HashMap<String, String> =
+            url::form_urlencoded::parse(request.split_once("\r\n\r\n").unwrap().1.as_bytes())
+                .into_owned()
+                .collect();
+        assert_eq!(
+            Some("client_secret"),
+            form.get("client_secret").map(String::as_str)
+        );
+        assert_eq!(Some("refresh_token"), form.get("token").map(String::as_str));
+    }
+}
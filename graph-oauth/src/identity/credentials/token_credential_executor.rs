@@ -2,15 +2,16 @@ use crate::identity::credentials::app_config::AppConfig;
 use crate::identity::{
     tracing_targets::CREDENTIAL_EXECUTOR, Authority, AuthorizationRequestParts, AzureCloudInstance,
 };
+use crate::oauth_serializer::AuthParameter;
 use async_trait::async_trait;
 use dyn_clone::DynClone;
+use graph_core::identity::AuthScheme;
 use graph_error::AuthExecutionError;
 use graph_error::{AuthExecutionResult, IdentityResult};
 use graph_http::api_impl::{GraphClientConfiguration, MinimalAsyncClient, MinimalBlockingClient};
 use reqwest::header::HeaderMap;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use tower::{Service, ServiceExt};
 use url::{ParseError, Url};
 use uuid::Uuid;
 
@@ -26,7 +27,19 @@ pub trait TokenCredentialExecutor: DynClone + Debug {
 
     fn request_parts(&mut self) -> IdentityResult<AuthorizationRequestParts> {
         let uri = self.uri()?;
-        let form = self.form_urlencode()?;
+        let mut form = self.form_urlencode()?;
+        if let AuthScheme::ProofOfPossession(key) = &self.app_config().auth_scheme {
+            form.insert(
+                AuthParameter::TokenType.alias().to_owned(),
+                self.app_config().auth_scheme.token_type().to_owned(),
+            );
+            form.insert(AuthParameter::ReqCnf.alias().to_owned(), key.req_cnf());
+        }
+
+        if let Some(claims) = self.app_config().claims_parameter() {
+            form.insert(AuthParameter::Claims.alias().to_owned(), claims);
+        }
+
         let basic_auth = self.basic_auth();
         let extra_headers = self.extra_header_parameters();
         let extra_query_params = self.extra_query_parameters();
@@ -41,7 +54,7 @@ pub trait TokenCredentialExecutor: DynClone + Debug {
     fn build_request(
         &mut self,
     ) -> AuthExecutionResult<(reqwest::blocking::RequestBuilder, MinimalBlockingClient)> {
-        let http_client = MinimalBlockingClient::from(self.config().clone());
+        let http_client = self.config().clone().try_build_minimal_blocking_client()?;
         let auth_request = self.request_parts()?;
         let basic_auth = auth_request.basic_auth;
 
@@ -76,7 +89,7 @@ pub trait TokenCredentialExecutor: DynClone + Debug {
     async fn build_request_async(
         &mut self,
     ) -> AuthExecutionResult<(reqwest::RequestBuilder, MinimalAsyncClient)> {
-        let http_client = MinimalAsyncClient::from(self.config().clone());
+        let http_client = self.config().clone().try_build_minimal_async_client()?;
         let auth_request = self.request_parts()?;
         let basic_auth = auth_request.basic_auth;
 
@@ -155,11 +168,7 @@ pub trait TokenCredentialExecutor: DynClone + Debug {
         let (request_builder, mut minimal_async_client) = self.build_request_async().await?;
         let request = request_builder.build()?;
         let response = minimal_async_client
-            .service
-            .ready()
-            .await
-            .map_err(AuthExecutionError::from)?
-            .call(request)
+            .execute(request)
             .await
             .map_err(AuthExecutionError::from)?;
         let status = response.status();
@@ -1,3 +1,4 @@
+mod account;
 mod allowed_host_validator;
 mod application_options;
 mod authority;
@@ -16,6 +17,7 @@ pub use openssl::{
     x509::X509,
 };
 
+pub use account::*;
 pub use allowed_host_validator::*;
 pub use application_options::*;
 pub use authority::*;
@@ -62,5 +62,8 @@ pub mod extensions {
 }
 
 pub use crate::identity::*;
-pub use graph_core::{crypto::GenPkce, crypto::ProofKeyCodeExchange};
+pub use graph_core::{
+    crypto::GenPkce, crypto::ProofKeyCodeExchange, crypto::ProofOfPossessionKey,
+    identity::AuthScheme,
+};
 pub use jsonwebtoken::{Header, TokenData};
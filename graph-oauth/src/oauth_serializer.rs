@@ -43,6 +43,13 @@ pub enum AuthParameter {
     Username,
     Password,
     DeviceCode,
+    TokenType,
+    ReqCnf,
+    Claims,
+    Token,
+    TokenTypeHint,
+    PostLogoutRedirectUri,
+    IdTokenHint,
 }
 
 impl AuthParameter {
@@ -75,6 +82,13 @@ impl AuthParameter {
             AuthParameter::Username => "username",
             AuthParameter::Password => "password",
             AuthParameter::DeviceCode => "device_code",
+            AuthParameter::TokenType => "token_type",
+            AuthParameter::ReqCnf => "req_cnf",
+            AuthParameter::Claims => "claims",
+            AuthParameter::Token => "token",
+            AuthParameter::TokenTypeHint => "token_type_hint",
+            AuthParameter::PostLogoutRedirectUri => "post_logout_redirect_uri",
+            AuthParameter::IdTokenHint => "id_token_hint",
         }
     }
 
@@ -89,6 +103,8 @@ impl AuthParameter {
                 | AuthParameter::CodeVerifier
                 | AuthParameter::CodeChallenge
                 | AuthParameter::Password
+                | AuthParameter::Token
+                | AuthParameter::IdTokenHint
         )
     }
 }
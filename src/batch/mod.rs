@@ -16,6 +16,8 @@ impl BatchApiClient {
 
         match rc_result {
             Ok(rc) => {
+                let rc =
+                    rc.with_path_template(self.resource_config.request_path_template("/$batch"));
                 let mut header_map = HeaderMap::new();
                 header_map
                     .entry(CONTENT_TYPE)
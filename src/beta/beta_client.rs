@@ -0,0 +1,29 @@
+use crate::api_default_imports::*;
+use crate::beta::identity_protection::IdentityProtectionApiClient;
+use crate::beta::users::{UsersApiClient, UsersIdApiClient};
+
+/// Client for the Microsoft Graph beta Api. Created using [`GraphClient::beta_client`](crate::GraphClient::beta_client).
+///
+/// Requests are sent to the `/beta` path of the host used by the [`GraphClient`](crate::GraphClient)
+/// that created it, and use the same http client and credentials.
+#[derive(Debug, Clone)]
+pub struct BetaGraphClient {
+    client: Client,
+    endpoint: Url,
+}
+
+impl BetaGraphClient {
+    pub(crate) fn new(client: Client, endpoint: &Url) -> BetaGraphClient {
+        let mut endpoint = endpoint.clone();
+        endpoint.set_path("/beta");
+        BetaGraphClient { client, endpoint }
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.endpoint
+    }
+
+    api_client_impl!(identity_protection, IdentityProtectionApiClient);
+
+    api_client_impl!(users, UsersApiClient, user, UsersIdApiClient);
+}
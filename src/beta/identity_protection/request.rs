@@ -0,0 +1,109 @@
+use crate::api_default_imports::*;
+
+api_client!(
+    IdentityProtectionApiClient,
+    ResourceIdentity::IdentityProtection
+);
+
+impl IdentityProtectionApiClient {
+    get!(
+        doc: "Get identityProtection",
+        name: get_identity_protection_root,
+        path: "/identityProtection"
+    );
+    get!(
+        doc: "List riskDetections",
+        name: list_risk_detections,
+        path: "/identityProtection/riskDetections"
+    );
+    get!(
+        doc: "Get the number of the resource",
+        name: get_risk_detections_count,
+        path: "/identityProtection/riskDetections/$count"
+    );
+    get!(
+        doc: "Get riskDetection",
+        name: get_risk_detections,
+        path: "/identityProtection/riskDetections/{{id}}",
+        params: risk_detection_id
+    );
+    get!(
+        doc: "List riskyServicePrincipals",
+        name: list_risky_service_principals,
+        path: "/identityProtection/riskyServicePrincipals"
+    );
+    get!(
+        doc: "Get the number of the resource",
+        name: get_risky_service_principals_count,
+        path: "/identityProtection/riskyServicePrincipals/$count"
+    );
+    post!(
+        doc: "Invoke action confirmCompromised",
+        name: confirm_compromised_risky_service_principals,
+        path: "/identityProtection/riskyServicePrincipals/confirmCompromised",
+        body: true
+    );
+    post!(
+        doc: "Invoke action dismiss",
+        name: dismiss_risky_service_principals,
+        path: "/identityProtection/riskyServicePrincipals/dismiss",
+        body: true
+    );
+    get!(
+        doc: "Get riskyServicePrincipal",
+        name: get_risky_service_principals,
+        path: "/identityProtection/riskyServicePrincipals/{{id}}",
+        params: risky_service_principal_id
+    );
+    get!(
+        doc: "List history of riskyServicePrincipal",
+        name: list_risky_service_principal_history,
+        path: "/identityProtection/riskyServicePrincipals/{{id}}/history",
+        params: risky_service_principal_id
+    );
+    get!(
+        doc: "List riskyUsers",
+        name: list_risky_users,
+        path: "/identityProtection/riskyUsers"
+    );
+    get!(
+        doc: "Get the number of the resource",
+        name: get_risky_users_count,
+        path: "/identityProtection/riskyUsers/$count"
+    );
+    post!(
+        doc: "Invoke action confirmCompromised",
+        name: confirm_compromised_risky_users,
+        path: "/identityProtection/riskyUsers/confirmCompromised",
+        body: true
+    );
+    post!(
+        doc: "Invoke action dismiss",
+        name: dismiss_risky_users,
+        path: "/identityProtection/riskyUsers/dismiss",
+        body: true
+    );
+    get!(
+        doc: "Get riskyUser",
+        name: get_risky_users,
+        path: "/identityProtection/riskyUsers/{{id}}",
+        params: risky_user_id
+    );
+    get!(
+        doc: "List history of riskyUser",
+        name: list_risky_user_history,
+        path: "/identityProtection/riskyUsers/{{id}}/history",
+        params: risky_user_id
+    );
+    get!(
+        doc: "List servicePrincipalRiskDetections",
+        name: list_service_principal_risk_detections,
+        path: "/identityProtection/servicePrincipalRiskDetections"
+    );
+    get!(
+        doc: "Get servicePrincipalRiskDetection",
+        name: get_service_principal_risk_detections,
+        path: "/identityProtection/servicePrincipalRiskDetections/{{id}}",
+        params: service_principal_risk_detection_id
+    );
+}
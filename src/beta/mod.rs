@@ -0,0 +1,33 @@
+//! Api clients for the Microsoft Graph beta Api.
+//!
+//! The beta Api includes requests that are not available in v1.0, such as many
+//! Intune and Identity Protection Apis. The beta clients are separate types from
+//! the v1.0 clients so that a beta only request can't be sent to the v1.0 endpoint.
+//! Requires the `beta` feature.
+//!
+//! This module is a scaffold. The `users` and `identity_protection` clients are written
+//! by hand in the format of the generated clients and only cover a few beta requests.
+//! The full set of beta clients is written here by running codegen with the beta
+//! metadata: `cargo run -p graph-codegen --bin codegen -- ./openapi-beta.yaml --beta`.
+//!
+//! APIs under the beta endpoint are subject to change. Use of these APIs in
+//! production applications is not supported by Microsoft.
+//!
+//! ```rust
+//! use graph_rs_sdk::*;
+//!
+//! let client = GraphClient::new("ACCESS_TOKEN");
+//! let beta = client.beta_client();
+//!
+//! let request = beta.identity_protection().list_risky_service_principals();
+//! assert_eq!(
+//!     request.url().as_str(),
+//!     "https://graph.microsoft.com/beta/identityProtection/riskyServicePrincipals"
+//! );
+//! ```
+
+mod beta_client;
+pub mod identity_protection;
+pub mod users;
+
+pub use beta_client::*;
@@ -0,0 +1,101 @@
+use crate::api_default_imports::*;
+
+api_client!(UsersApiClient, UsersIdApiClient, ResourceIdentity::Users);
+
+impl UsersApiClient {
+    post!(
+        doc: "Create User",
+        name: create_user,
+        path: "/users",
+        body: true
+    );
+    get!(
+        doc: "List users",
+        name: list_user,
+        path: "/users"
+    );
+    get!(
+        doc: "Get the number of the resource",
+        name: get_users_count,
+        path: "/users/$count"
+    );
+    get!(
+        doc: "Invoke function delta",
+        name: delta,
+        path: "/users/delta()"
+    );
+    post!(
+        doc: "Invoke action getByIds",
+        name: get_by_ids,
+        path: "/users/getByIds",
+        body: true
+    );
+    post!(
+        doc: "Invoke action validateProperties",
+        name: validate_properties,
+        path: "/users/validateProperties",
+        body: true
+    );
+}
+
+impl UsersIdApiClient {
+    delete!(
+        doc: "Delete a user",
+        name: delete_user,
+        path: "/users/{{RID}}"
+    );
+    get!(
+        doc: "Get user",
+        name: get_user,
+        path: "/users/{{RID}}"
+    );
+    patch!(
+        doc: "Update user",
+        name: update_user,
+        path: "/users/{{RID}}",
+        body: true
+    );
+    get!(
+        doc: "Get userAnalytics",
+        name: get_analytics,
+        path: "/users/{{RID}}/analytics"
+    );
+    patch!(
+        doc: "Update the navigation property analytics in users",
+        name: update_analytics,
+        path: "/users/{{RID}}/analytics",
+        body: true
+    );
+    get!(
+        doc: "List activityStatistics",
+        name: list_activity_statistics,
+        path: "/users/{{RID}}/analytics/activityStatistics"
+    );
+    get!(
+        doc: "Get activityStatistics from users",
+        name: get_activity_statistics,
+        path: "/users/{{RID}}/analytics/activityStatistics/{{id}}",
+        params: activity_statistics_id
+    );
+    post!(
+        doc: "Invoke action exportDeviceAndAppManagementData",
+        name: export_device_and_app_management_data,
+        path: "/users/{{RID}}/exportDeviceAndAppManagementData()"
+    );
+    get!(
+        doc: "Invoke function getEffectiveDeviceEnrollmentConfigurations",
+        name: get_effective_device_enrollment_configurations,
+        path: "/users/{{RID}}/getEffectiveDeviceEnrollmentConfigurations()"
+    );
+    get!(
+        doc: "List usageRights",
+        name: list_usage_rights,
+        path: "/users/{{RID}}/usageRights"
+    );
+    get!(
+        doc: "Get usageRights from users",
+        name: get_usage_rights,
+        path: "/users/{{RID}}/usageRights/{{id}}",
+        params: usage_right_id
+    );
+}
@@ -5,7 +5,10 @@ macro_rules! into_handler {
         let url_result = $inner.build_url($template, &json);
 
         match RequestComponents::try_from(($inner.resource_config.resource_identity, $method, url_result)) {
-            Ok(rc) => return RequestHandler::new($inner.client.clone(), rc, None, None),
+            Ok(rc) => {
+                let rc = rc.with_path_template($inner.resource_config.request_path_template($template));
+                return RequestHandler::new($inner.client.clone(), rc, None, None);
+            }
             Err(err) => return RequestHandler::new(
                 $inner.client.clone(),
                 RequestComponents::new($inner.resource_config.resource_identity, $inner.resource_config.url.clone(), $method),
@@ -22,7 +25,10 @@ macro_rules! into_handler {
         let body_result = $body.into_body();
 
         match map_errors(RequestComponents::try_from(($inner.resource_config.resource_identity, $method, url_result)), body_result) {
-            Ok((rc, body)) => return RequestHandler::new($inner.client.clone(), rc, None, Some(body)),
+            Ok((rc, body)) => {
+                let rc = rc.with_path_template($inner.resource_config.request_path_template($template));
+                return RequestHandler::new($inner.client.clone(), rc, None, Some(body));
+            }
             Err(err) => {
                 let rc = RequestComponents::new($inner.resource_config.resource_identity, $inner.resource_config.url.clone(), $method);
                 return RequestHandler::new($inner.client.clone(), rc, Some(err), None);
@@ -1,42 +1,74 @@
+#[cfg(feature = "admin")]
 use crate::admin::AdminApiClient;
+#[cfg(feature = "agreement-acceptances")]
 use crate::agreement_acceptances::{
     AgreementAcceptancesApiClient, AgreementAcceptancesIdApiClient,
 };
+#[cfg(feature = "agreements")]
 use crate::agreements::{AgreementsApiClient, AgreementsIdApiClient};
 use crate::api_default_imports::*;
+#[cfg(feature = "app-catalogs")]
 use crate::app_catalogs::AppCatalogsApiClient;
+#[cfg(feature = "applications")]
 use crate::applications::{ApplicationsApiClient, ApplicationsIdApiClient};
+#[cfg(feature = "audit-logs")]
 use crate::audit_logs::AuditLogsApiClient;
+#[cfg(feature = "authentication-method-configurations")]
 use crate::authentication_method_configurations::{
     AuthenticationMethodConfigurationsApiClient, AuthenticationMethodConfigurationsIdApiClient,
 };
+#[cfg(feature = "authentication-methods-policy")]
 use crate::authentication_methods_policy::AuthenticationMethodsPolicyApiClient;
+#[cfg(feature = "batch")]
 use crate::batch::BatchApiClient;
+#[cfg(feature = "beta")]
+use crate::beta::BetaGraphClient;
+#[cfg(feature = "branding")]
 use crate::branding::BrandingApiClient;
+#[cfg(feature = "certificate-based-auth-configuration")]
 use crate::certificate_based_auth_configuration::{
     CertificateBasedAuthConfigurationApiClient, CertificateBasedAuthConfigurationIdApiClient,
 };
+#[cfg(feature = "chats")]
 use crate::chats::{ChatsApiClient, ChatsIdApiClient};
+use crate::client::GraphClientBuilder;
+#[cfg(feature = "communications")]
 use crate::communications::CommunicationsApiClient;
+#[cfg(feature = "contracts")]
 use crate::contracts::{ContractsApiClient, ContractsIdApiClient};
+#[cfg(feature = "data-policy-operations")]
 use crate::data_policy_operations::DataPolicyOperationsApiClient;
+#[cfg(feature = "default-drive")]
 use crate::default_drive::DefaultDriveApiClient;
+#[cfg(feature = "device-app-management")]
 use crate::device_app_management::DeviceAppManagementApiClient;
+#[cfg(feature = "device-management")]
 use crate::device_management::DeviceManagementApiClient;
+#[cfg(feature = "devices")]
 use crate::devices::{DevicesApiClient, DevicesIdApiClient};
+#[cfg(feature = "directory")]
 use crate::directory::DirectoryApiClient;
+#[cfg(feature = "directory-objects")]
 use crate::directory_objects::{DirectoryObjectsApiClient, DirectoryObjectsIdApiClient};
+#[cfg(feature = "directory-role-templates")]
 use crate::directory_role_templates::{
     DirectoryRoleTemplatesApiClient, DirectoryRoleTemplatesIdApiClient,
 };
+#[cfg(feature = "directory-roles")]
 use crate::directory_roles::{DirectoryRolesApiClient, DirectoryRolesIdApiClient};
+#[cfg(feature = "domain-dns-records")]
 use crate::domain_dns_records::{DomainDnsRecordsApiClient, DomainDnsRecordsIdApiClient};
+#[cfg(feature = "domains")]
 use crate::domains::{DomainsApiClient, DomainsIdApiClient};
+#[cfg(feature = "drives")]
 use crate::drives::{DrivesApiClient, DrivesIdApiClient};
+#[cfg(feature = "education")]
 use crate::education::EducationApiClient;
+#[cfg(feature = "group-lifecycle-policies")]
 use crate::group_lifecycle_policies::{
     GroupLifecyclePoliciesApiClient, GroupLifecyclePoliciesIdApiClient,
 };
+#[cfg(feature = "groups")]
 use crate::groups::{GroupsApiClient, GroupsIdApiClient};
 use crate::identity::{
     AllowedHostValidator, AuthorizationCodeAssertionCredential,
@@ -45,32 +77,55 @@ use crate::identity::{
     ConfidentialClientApplication, DeviceCodeCredential, HostIs, OpenIdCredential,
     PublicClientApplication, ResourceOwnerPasswordCredential, Token,
 };
+#[cfg(feature = "identity-access")]
 use crate::identity_access::IdentityApiClient;
+#[cfg(feature = "identity-governance")]
 use crate::identity_governance::IdentityGovernanceApiClient;
+#[cfg(feature = "identity-providers")]
 use crate::identity_providers::{IdentityProvidersApiClient, IdentityProvidersIdApiClient};
+#[cfg(feature = "invitations")]
 use crate::invitations::InvitationsApiClient;
+#[cfg(feature = "me")]
 use crate::me::MeApiClient;
+#[cfg(feature = "oauth2-permission-grants")]
 use crate::oauth2_permission_grants::{
     Oauth2PermissionGrantsApiClient, Oauth2PermissionGrantsIdApiClient,
 };
+#[cfg(feature = "organization")]
 use crate::organization::{OrganizationApiClient, OrganizationIdApiClient};
+#[cfg(feature = "permission-grants")]
 use crate::permission_grants::{PermissionGrantsApiClient, PermissionGrantsIdApiClient};
+#[cfg(feature = "places")]
 use crate::places::PlacesApiClient;
+#[cfg(feature = "planner")]
 use crate::planner::PlannerApiClient;
+#[cfg(feature = "policies")]
 use crate::policies::PoliciesApiClient;
+#[cfg(feature = "reports")]
 use crate::reports::ReportsApiClient;
+#[cfg(feature = "schema-extensions")]
 use crate::schema_extensions::{SchemaExtensionsApiClient, SchemaExtensionsIdApiClient};
+#[cfg(feature = "service-principals")]
 use crate::service_principals::{ServicePrincipalsApiClient, ServicePrincipalsIdApiClient};
+#[cfg(feature = "sites")]
 use crate::sites::{SitesApiClient, SitesIdApiClient};
+#[cfg(feature = "solutions")]
 use crate::solutions::SolutionsApiClient;
-use crate::subscribed_skus::SubscribedSkusApiClient;
+#[cfg(feature = "subscribed-skus")]
+use crate::subscribed_skus::{SubscribedSkusApiClient, SubscribedSkusIdApiClient};
+#[cfg(feature = "subscriptions")]
 use crate::subscriptions::{SubscriptionsApiClient, SubscriptionsIdApiClient};
+#[cfg(feature = "teams")]
 use crate::teams::{TeamsApiClient, TeamsIdApiClient};
+#[cfg(feature = "teams-templates")]
 use crate::teams_templates::{TeamsTemplatesApiClient, TeamsTemplatesIdApiClient};
+#[cfg(feature = "teamwork")]
 use crate::teamwork::TeamworkApiClient;
+#[cfg(feature = "users")]
 use crate::users::{UsersApiClient, UsersIdApiClient};
 use crate::{GRAPH_URL, GRAPH_URL_BETA};
-use graph_core::identity::ForceTokenRefresh;
+use graph_core::identity::{CloudEnvironment, ForceTokenRefresh};
+use graph_error::ConfigurationError;
 use graph_oauth::AuthorizationCodeSpaCredential;
 use lazy_static::lazy_static;
 
@@ -92,18 +147,39 @@ pub struct GraphClient {
 
 impl GraphClient {
     pub fn new<AT: ToString>(access_token: AT) -> GraphClient {
-        GraphClient {
-            client: Client::new(BearerTokenCredential::from(access_token.to_string())),
-            endpoint: PARSED_GRAPH_URL.clone(),
-            allowed_host_validator: AllowedHostValidator::default(),
-        }
+        GraphClient::from_client(Client::new(BearerTokenCredential::from(
+            access_token.to_string(),
+        )))
     }
 
+    /// Create a client that uses the client application for access tokens. The client
+    /// uses the [CloudEnvironment] of the client application when one was set on the
+    /// credential.
+    ///
+    /// # Panics
+    /// Panics if the cloud environment of the client application does not match the
+    /// authority host that it requests tokens from.
     pub fn from_client_app<CA: ClientApplication + 'static>(client_app: CA) -> GraphClient {
+        GraphClient::from_client(Client::new(client_app))
+    }
+
+    pub(crate) fn from_client(client: Client) -> GraphClient {
+        let (endpoint, mut allowed_host_validator) = match client.cloud_environment() {
+            Some(cloud_environment) => (
+                cloud_environment.graph_endpoint(),
+                AllowedHostValidator::from(cloud_environment.allowed_hosts().as_slice()),
+            ),
+            None => (PARSED_GRAPH_URL.clone(), AllowedHostValidator::default()),
+        };
+
+        for host in client.trusted_graph_hosts() {
+            allowed_host_validator.allow_host(host);
+        }
+
         GraphClient {
-            client: Client::new(client_app),
-            endpoint: PARSED_GRAPH_URL.clone(),
-            allowed_host_validator: AllowedHostValidator::default(),
+            client,
+            endpoint,
+            allowed_host_validator,
         }
     }
 
@@ -124,7 +200,7 @@ impl GraphClient {
     ///     .await?;
     /// ```
     pub fn v1(&mut self) -> &mut GraphClient {
-        self.endpoint = PARSED_GRAPH_URL.clone();
+        self.use_v1();
         self
     }
 
@@ -143,7 +219,11 @@ impl GraphClient {
     /// assert_eq!(client.url().to_string(), GRAPH_URL.to_string())
     /// ```
     pub fn use_v1(&mut self) {
-        self.endpoint = PARSED_GRAPH_URL.clone();
+        self.endpoint = self
+            .client
+            .cloud_environment()
+            .map(CloudEnvironment::graph_endpoint)
+            .unwrap_or_else(|| PARSED_GRAPH_URL.clone());
     }
 
     /// Use the beta endpoint for the Microsoft Graph API
@@ -162,7 +242,7 @@ impl GraphClient {
     ///     .await?;
     /// ```
     pub fn beta(&mut self) -> &mut GraphClient {
-        self.endpoint = PARSED_GRAPH_URL_BETA.clone();
+        self.use_beta();
         self
     }
 
@@ -181,7 +261,11 @@ impl GraphClient {
     /// assert_eq!(client.url().to_string(), GRAPH_URL_BETA.to_string())
     /// ```
     pub fn use_beta(&mut self) {
-        self.endpoint = PARSED_GRAPH_URL_BETA.clone();
+        self.endpoint = self
+            .client
+            .cloud_environment()
+            .map(CloudEnvironment::graph_beta_endpoint)
+            .unwrap_or_else(|| PARSED_GRAPH_URL_BETA.clone());
     }
 
     pub fn url(&self) -> &Url {
@@ -208,6 +292,7 @@ impl GraphClient {
     ///
     /// Attempting to use an invalid host will cause the client to panic. This is done
     /// for increased security.
+    /// Use [try_use_endpoint](GraphClient::try_use_endpoint) to return an error instead.
     ///
     /// Do not use a government host endpoint without authorization and any necessary clearances.
     /// Using any government host endpoint means you should expect every API call will be monitored
@@ -227,6 +312,9 @@ impl GraphClient {
     /// * microsoftgraph.chinacloudapi.cn (operated by 21Vianet)
     /// * canary.graph.microsoft.com
     ///
+    /// When the client has a [CloudEnvironment] only the hosts of the environment are valid.
+    /// Hosts trusted with [GraphClientConfiguration::trust_graph_host] are valid as well.
+    ///
     /// Example
     /// ```rust,ignore
     /// use graph_rs_sdk::Graph;
@@ -252,6 +340,7 @@ impl GraphClient {
     ///
     /// Attempting to use an invalid host will cause the client to panic. This is done
     /// for increased security.
+    /// Use [try_use_endpoint](GraphClient::try_use_endpoint) to return an error instead.
     ///
     /// Do not use a government host endpoint without authorization and any necessary clearances.
     /// Using any government host endpoint means you should expect every API call will be monitored
@@ -271,6 +360,9 @@ impl GraphClient {
     /// * microsoftgraph.chinacloudapi.cn (operated by 21Vianet)
     /// * canary.graph.microsoft.com
     ///
+    /// When the client has a [CloudEnvironment] only the hosts of the environment are valid.
+    /// Hosts trusted with [GraphClientConfiguration::trust_graph_host] are valid as well.
+    ///
     /// Example
     /// ```rust
     /// use url::Url;
@@ -282,29 +374,80 @@ impl GraphClient {
     /// assert_eq!(client.url().to_string(), "https://graph.microsoft.com/v1.0".to_string())
     /// ```
     pub fn use_endpoint(&mut self, url: &Url) {
+        self.set_endpoint(url).unwrap_or_else(|err| panic!("{err}"));
+    }
+
+    /// Set a custom endpoint for the Microsoft Graph API. Same as
+    /// [use_endpoint](GraphClient::use_endpoint) but returns an error instead of
+    /// panicking when the endpoint has a query or the host is not one of the valid hosts.
+    ///
+    /// Unlike [use_endpoint](GraphClient::use_endpoint), an endpoint that does not use
+    /// the https scheme is also rejected.
+    ///
+    /// Example
+    /// ```rust
+    /// use url::Url;
+    /// use graph_rs_sdk::Graph;
+    ///
+    /// let mut client = Graph::new("ACCESS_TOKEN");
+    /// let result = client.try_use_endpoint(&Url::parse("https://example.org/v1.0").unwrap());
+    ///
+    /// assert!(result.is_err());
+    /// assert_eq!(client.url().to_string(), "https://graph.microsoft.com/v1.0".to_string())
+    /// ```
+    pub fn try_use_endpoint(&mut self, url: &Url) -> Result<(), ConfigurationError> {
+        if url.scheme() != "https" {
+            return Err(ConfigurationError::InsecureEndpoint(url.to_string()));
+        }
+
+        self.set_endpoint(url)
+    }
+
+    fn set_endpoint(&mut self, url: &Url) -> Result<(), ConfigurationError> {
         if url.query().is_some() {
-            panic!(
-                "Invalid query - provide only the scheme, host, and optional path of the Uri such as https://graph.microsoft.com/v1.0"
-            );
+            return Err(ConfigurationError::EndpointQuery(url.to_string()));
         }
 
         match self.allowed_host_validator.validate_url(url) {
             HostIs::Valid => {
                 self.endpoint = url.clone();
+                Ok(())
             }
-            HostIs::Invalid => panic!("Invalid host"),
+            HostIs::Invalid => Err(ConfigurationError::HostNotAllowed(url.to_string())),
         }
     }
 
+    /// Start building a client with a [GraphClientBuilder], which returns an error
+    /// instead of panicking when the configuration is invalid.
+    pub fn builder() -> GraphClientBuilder {
+        GraphClientBuilder::new()
+    }
+
+    /// Create a client from the configuration, returning a [`ConfigurationError`] if the
+    /// client application requests tokens from the authority of a different
+    /// [cloud environment](GraphClientConfiguration::cloud_environment) or the http client
+    /// cannot be built. `From<GraphClientConfiguration>` panics with this error.
+    ///
+    /// Applies the same checks as `From<GraphClientConfiguration>`. Use
+    /// [`GraphClient::builder`] to also require credentials and TLS 1.2.
+    pub fn try_from_config(
+        config: GraphClientConfiguration,
+    ) -> Result<GraphClient, ConfigurationError> {
+        Ok(GraphClient::from_client(config.try_build_lenient()?))
+    }
+
     #[cfg(feature = "test-util")]
     pub fn use_test_endpoint(&mut self, url: &Url) {
         self.endpoint = url.clone();
     }
 
+    #[cfg(feature = "admin")]
     api_client_impl!(admin, AdminApiClient);
 
+    #[cfg(feature = "app-catalogs")]
     api_client_impl!(app_catalogs, AppCatalogsApiClient);
 
+    #[cfg(feature = "agreement-acceptances")]
     api_client_impl!(
         agreement_acceptances,
         AgreementAcceptancesApiClient,
@@ -312,6 +455,7 @@ impl GraphClient {
         AgreementAcceptancesIdApiClient
     );
 
+    #[cfg(feature = "agreements")]
     api_client_impl!(
         agreements,
         AgreementsApiClient,
@@ -319,6 +463,7 @@ impl GraphClient {
         AgreementsIdApiClient
     );
 
+    #[cfg(feature = "applications")]
     api_client_impl!(
         applications,
         ApplicationsApiClient,
@@ -326,8 +471,10 @@ impl GraphClient {
         ApplicationsIdApiClient
     );
 
+    #[cfg(feature = "audit-logs")]
     api_client_impl!(audit_logs, AuditLogsApiClient);
 
+    #[cfg(feature = "authentication-method-configurations")]
     api_client_impl!(
         authentication_method_configurations,
         AuthenticationMethodConfigurationsApiClient,
@@ -335,13 +482,16 @@ impl GraphClient {
         AuthenticationMethodConfigurationsIdApiClient
     );
 
+    #[cfg(feature = "authentication-methods-policy")]
     api_client_impl!(
         authentication_methods_policy,
         AuthenticationMethodsPolicyApiClient
     );
 
+    #[cfg(feature = "branding")]
     api_client_impl!(branding, BrandingApiClient);
 
+    #[cfg(feature = "certificate-based-auth-configuration")]
     api_client_impl!(
         certificate_based_auth_configurations,
         CertificateBasedAuthConfigurationApiClient,
@@ -349,10 +499,13 @@ impl GraphClient {
         CertificateBasedAuthConfigurationIdApiClient
     );
 
+    #[cfg(feature = "chats")]
     api_client_impl!(chats, ChatsApiClient, chat, ChatsIdApiClient);
 
+    #[cfg(feature = "communications")]
     api_client_impl!(communications, CommunicationsApiClient);
 
+    #[cfg(feature = "contracts")]
     api_client_impl!(
         contracts,
         ContractsApiClient,
@@ -360,16 +513,22 @@ impl GraphClient {
         ContractsIdApiClient
     );
 
+    #[cfg(feature = "data-policy-operations")]
     api_client_impl!(data_policy_operations, DataPolicyOperationsApiClient);
 
+    #[cfg(feature = "device-app-management")]
     api_client_impl!(device_app_management, DeviceAppManagementApiClient);
 
+    #[cfg(feature = "device-management")]
     api_client_impl!(device_management, DeviceManagementApiClient);
 
+    #[cfg(feature = "devices")]
     api_client_impl!(devices, DevicesApiClient, device, DevicesIdApiClient);
 
+    #[cfg(feature = "directory")]
     api_client_impl!(directory, DirectoryApiClient);
 
+    #[cfg(feature = "directory-objects")]
     api_client_impl!(
         directory_objects,
         DirectoryObjectsApiClient,
@@ -377,6 +536,7 @@ impl GraphClient {
         DirectoryObjectsIdApiClient
     );
 
+    #[cfg(feature = "directory-role-templates")]
     api_client_impl!(
         directory_role_templates,
         DirectoryRoleTemplatesApiClient,
@@ -384,6 +544,7 @@ impl GraphClient {
         DirectoryRoleTemplatesIdApiClient
     );
 
+    #[cfg(feature = "directory-roles")]
     api_client_impl!(
         directory_roles,
         DirectoryRolesApiClient,
@@ -391,6 +552,7 @@ impl GraphClient {
         DirectoryRolesIdApiClient
     );
 
+    #[cfg(feature = "domain-dns-records")]
     api_client_impl!(
         domain_dns_records,
         DomainDnsRecordsApiClient,
@@ -398,16 +560,22 @@ impl GraphClient {
         DomainDnsRecordsIdApiClient
     );
 
+    #[cfg(feature = "domains")]
     api_client_impl!(domains, DomainsApiClient, domain, DomainsIdApiClient);
 
+    #[cfg(feature = "drives")]
     api_client_impl!(drives, DrivesApiClient, drive, DrivesIdApiClient);
 
+    #[cfg(feature = "default-drive")]
     api_client_impl_link!(default_drive, DefaultDriveApiClient);
 
+    #[cfg(feature = "education")]
     api_client_impl_link!(education, EducationApiClient);
 
+    #[cfg(feature = "groups")]
     api_client_impl!(groups, GroupsApiClient, group, GroupsIdApiClient);
 
+    #[cfg(feature = "group-lifecycle-policies")]
     api_client_impl!(
         group_lifecycle_policies,
         GroupLifecyclePoliciesApiClient,
@@ -415,10 +583,13 @@ impl GraphClient {
         GroupLifecyclePoliciesIdApiClient
     );
 
+    #[cfg(feature = "identity-access")]
     api_client_impl_link!(identity, IdentityApiClient);
 
+    #[cfg(feature = "identity-governance")]
     api_client_impl!(identity_governance, IdentityGovernanceApiClient);
 
+    #[cfg(feature = "identity-providers")]
     api_client_impl!(
         identity_providers,
         IdentityProvidersApiClient,
@@ -426,10 +597,13 @@ impl GraphClient {
         IdentityProvidersIdApiClient
     );
 
+    #[cfg(feature = "invitations")]
     api_client_impl!(invitations, InvitationsApiClient);
 
+    #[cfg(feature = "me")]
     api_client_impl_link!(me, MeApiClient);
 
+    #[cfg(feature = "oauth2-permission-grants")]
     api_client_impl!(
         oauth2_permission_grants,
         Oauth2PermissionGrantsApiClient,
@@ -437,6 +611,7 @@ impl GraphClient {
         Oauth2PermissionGrantsIdApiClient
     );
 
+    #[cfg(feature = "organization")]
     api_client_impl!(
         organizations,
         OrganizationApiClient,
@@ -444,8 +619,10 @@ impl GraphClient {
         OrganizationIdApiClient
     );
 
+    #[cfg(feature = "places")]
     api_client_impl!(places, PlacesApiClient);
 
+    #[cfg(feature = "permission-grants")]
     api_client_impl!(
         permission_grants,
         PermissionGrantsApiClient,
@@ -453,12 +630,16 @@ impl GraphClient {
         PermissionGrantsIdApiClient
     );
 
+    #[cfg(feature = "planner")]
     api_client_impl!(planner, PlannerApiClient);
 
+    #[cfg(feature = "policies")]
     api_client_impl!(policies, PoliciesApiClient);
 
+    #[cfg(feature = "reports")]
     api_client_impl!(reports, ReportsApiClient);
 
+    #[cfg(feature = "schema-extensions")]
     api_client_impl!(
         schema_extensions,
         SchemaExtensionsApiClient,
@@ -466,6 +647,7 @@ impl GraphClient {
         SchemaExtensionsIdApiClient
     );
 
+    #[cfg(feature = "service-principals")]
     api_client_impl!(
         service_principals,
         ServicePrincipalsApiClient,
@@ -473,17 +655,21 @@ impl GraphClient {
         ServicePrincipalsIdApiClient
     );
 
+    #[cfg(feature = "sites")]
     api_client_impl!(sites, SitesApiClient, site, SitesIdApiClient);
 
+    #[cfg(feature = "solutions")]
     api_client_impl!(solutions, SolutionsApiClient);
 
+    #[cfg(feature = "subscribed-skus")]
     api_client_impl!(
         subscribed_skus,
         SubscribedSkusApiClient,
         subscribed_sku,
-        SubscriptionsIdApiClient
+        SubscribedSkusIdApiClient
     );
 
+    #[cfg(feature = "subscriptions")]
     api_client_impl!(
         subscriptions,
         SubscriptionsApiClient,
@@ -491,8 +677,10 @@ impl GraphClient {
         SubscriptionsIdApiClient
     );
 
+    #[cfg(feature = "teams")]
     api_client_impl!(teams, TeamsApiClient, team, TeamsIdApiClient);
 
+    #[cfg(feature = "teams-templates")]
     api_client_impl!(
         teams_templates,
         TeamsTemplatesApiClient,
@@ -500,10 +688,31 @@ impl GraphClient {
         TeamsTemplatesIdApiClient
     );
 
+    #[cfg(feature = "teamwork")]
     api_client_impl_link!(teamwork, TeamworkApiClient);
 
+    #[cfg(feature = "users")]
     api_client_impl!(users, UsersApiClient, user, UsersIdApiClient);
 
+    /// Client for the Api clients generated from the beta metadata. The beta clients are
+    /// separate types from the v1.0 clients and include requests that are only available
+    /// in the beta Api. Requests use the host of this client with the `/beta` path.
+    /// Requires the `beta` feature.
+    ///
+    /// # Example
+    /// ```rust
+    /// use graph_rs_sdk::*;
+    ///
+    /// let client = GraphClient::new("ACCESS_TOKEN");
+    ///
+    /// let request = client.beta_client().user("user-id").get_analytics();
+    /// assert_eq!(request.url().path(), "/beta/users/user-id/analytics");
+    /// ```
+    #[cfg(feature = "beta")]
+    pub fn beta_client(&self) -> BetaGraphClient {
+        BetaGraphClient::new(self.client.clone(), &self.endpoint)
+    }
+
     pub fn custom(&self, method: Method, body: Option<BodyRead>) -> RequestHandler {
         let body_result = body.map(|body| body.into_body());
         if let Some(b) = body_result {
@@ -532,6 +741,7 @@ impl GraphClient {
         )
     }
 
+    #[cfg(feature = "batch")]
     pub fn batch<B: serde::Serialize>(&self, batch: &B) -> RequestHandler {
         BatchApiClient::new(
             self.client.clone(),
@@ -563,13 +773,16 @@ impl From<&Token> for GraphClient {
     }
 }
 
+/// Uses the Graph endpoint and allowed hosts of the
+/// [cloud environment](GraphClientConfiguration::cloud_environment) of the configuration.
+///
+/// # Panics
+/// Panics with the error of [`GraphClient::try_from_config`], such as when the client
+/// application of the configuration requests tokens from the authority of a different
+/// cloud environment.
 impl From<GraphClientConfiguration> for GraphClient {
     fn from(graph_client_builder: GraphClientConfiguration) -> Self {
-        GraphClient {
-            client: Client::from(graph_client_builder),
-            endpoint: PARSED_GRAPH_URL.clone(),
-            allowed_host_validator: AllowedHostValidator::default(),
-        }
+        GraphClient::try_from_config(graph_client_builder).unwrap_or_else(|err| panic!("{err}"))
     }
 }
 
@@ -636,6 +849,8 @@ impl From<&PublicClientApplication<AuthorizationCodeSpaCredential>> for GraphCli
 #[cfg(test)]
 mod test {
     use super::*;
+    use graph_oauth::{AzureCloudInstance, TokenCredentialExecutor};
+    use reqwest::tls::Version;
 
     #[test]
     #[should_panic]
@@ -711,6 +926,159 @@ mod test {
             assert_eq!(client.url().clone(), Url::parse(url).unwrap());
         }
     }
+
+    #[test]
+    fn cloud_environment_endpoint() {
+        let mut client = GraphClient::from(
+            GraphClientConfiguration::new()
+                .access_token("token")
+                .cloud_environment(CloudEnvironment::USGov),
+        );
+        assert_eq!("https://graph.microsoft.us/v1.0", client.url().as_str());
+        client.use_beta();
+        assert_eq!("https://graph.microsoft.us/beta", client.url().as_str());
+        client.use_v1();
+        assert_eq!("https://graph.microsoft.us/v1.0", client.url().as_str());
+    }
+
+    #[test]
+    #[should_panic]
+    fn cloud_environment_invalid_host() {
+        let mut client = GraphClient::from(
+            GraphClientConfiguration::new()
+                .access_token("token")
+                .cloud_environment(CloudEnvironment::USGov),
+        );
+        client.use_endpoint(&Url::parse("https://graph.microsoft.com/v1.0").unwrap());
+    }
+
+    #[test]
+    fn credential_cloud_environment() {
+        let confidential_client =
+            ConfidentialClientApplication::builder("6731de76-14a6-49ae-97bc-6eba6914391e")
+                .with_client_secret("secret")
+                .with_tenant("tenant-id")
+                .with_cloud_environment(CloudEnvironment::China)
+                .build();
+        assert_eq!(
+            AzureCloudInstance::AzureChina,
+            confidential_client.azure_cloud_instance()
+        );
+
+        let client = GraphClient::from(&confidential_client);
+        assert_eq!(
+            "https://microsoftgraph.chinacloudapi.cn/v1.0",
+            client.url().as_str()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "https://login.microsoftonline.us")]
+    fn cloud_environment_mismatch() {
+        let confidential_client =
+            ConfidentialClientApplication::builder("6731de76-14a6-49ae-97bc-6eba6914391e")
+                .with_client_secret("secret")
+                .with_tenant("tenant-id")
+                .build();
+        let _ = GraphClient::from(
+            GraphClientConfiguration::new()
+                .client_application(confidential_client)
+                .cloud_environment(CloudEnvironment::USGovDoD),
+        );
+    }
+
+    #[test]
+    fn try_from_config_cloud_environment_mismatch() {
+        let confidential_client =
+            ConfidentialClientApplication::builder("6731de76-14a6-49ae-97bc-6eba6914391e")
+                .with_client_secret("secret")
+                .with_tenant("tenant-id")
+                .build();
+        assert!(matches!(
+            GraphClient::try_from_config(
+                GraphClientConfiguration::new()
+                    .client_application(confidential_client)
+                    .cloud_environment(CloudEnvironment::USGovDoD),
+            ),
+            Err(ConfigurationError::CloudEnvironmentMismatch { .. })
+        ));
+
+        let client = GraphClient::try_from_config(
+            GraphClientConfiguration::new().cloud_environment(CloudEnvironment::USGov),
+        )
+        .unwrap();
+        assert_eq!("https://graph.microsoft.us/v1.0", client.url().as_str());
+    }
+
+    #[test]
+    fn try_use_endpoint_errors() {
+        let mut client = GraphClient::new("token");
+        assert!(matches!(
+            client.try_use_endpoint(&Url::parse("https://graph.microsoft.com/v1.0?a=b").unwrap()),
+            Err(ConfigurationError::EndpointQuery(_))
+        ));
+        assert!(matches!(
+            client.try_use_endpoint(&Url::parse("http://graph.microsoft.com/v1.0").unwrap()),
+            Err(ConfigurationError::InsecureEndpoint(_))
+        ));
+        assert!(matches!(
+            client.try_use_endpoint(&Url::parse("https://example.org/v1.0").unwrap()),
+            Err(ConfigurationError::HostNotAllowed(_))
+        ));
+        assert_eq!(client.url().as_str(), "https://graph.microsoft.com/v1.0");
+
+        client
+            .try_use_endpoint(&Url::parse("https://graph.microsoft.us/v1.0").unwrap())
+            .unwrap();
+        assert_eq!(client.url().as_str(), "https://graph.microsoft.us/v1.0");
+    }
+
+    #[test]
+    fn use_endpoint_keeps_previous_rules() {
+        let mut client = GraphClient::from(
+            GraphClientConfiguration::new()
+                .access_token("token")
+                .min_tls_version(Version::TLS_1_0),
+        );
+        client.use_endpoint(&Url::parse("http://graph.microsoft.com/v1.0").unwrap());
+        assert_eq!(client.url().as_str(), "http://graph.microsoft.com/v1.0");
+    }
+
+    #[test]
+    fn builder_configuration_errors() {
+        assert!(matches!(
+            GraphClient::builder().build(),
+            Err(ConfigurationError::MissingCredentials)
+        ));
+        assert!(matches!(
+            GraphClient::builder()
+                .config(GraphClientConfiguration::new().min_tls_version(Version::TLS_1_0))
+                .access_token("token")
+                .build(),
+            Err(ConfigurationError::TlsVersion(version)) if version == "TLS 1.0"
+        ));
+        assert!(matches!(
+            GraphClient::builder()
+                .access_token("token")
+                .endpoint("graph.microsoft.com")
+                .build(),
+            Err(ConfigurationError::InvalidEndpoint { .. })
+        ));
+        assert!(matches!(
+            GraphClient::builder()
+                .access_token("token")
+                .endpoint("https://example.org")
+                .build(),
+            Err(ConfigurationError::HostNotAllowed(_))
+        ));
+
+        let client = GraphClient::builder()
+            .access_token("token")
+            .endpoint("https://graph.microsoft.com/beta")
+            .build()
+            .unwrap();
+        assert_eq!(client.url().as_str(), "https://graph.microsoft.com/beta");
+    }
 }
 
 #[cfg(test)]
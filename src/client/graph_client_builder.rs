@@ -0,0 +1,86 @@
+use crate::client::GraphClient;
+use graph_core::identity::ClientApplication;
+use graph_error::ConfigurationError;
+use graph_http::api_impl::GraphClientConfiguration;
+use url::Url;
+
+/// Builds a [`GraphClient`] and checks the configuration of the client, returning a
+/// [`ConfigurationError`] instead of panicking when a setting is invalid.
+///
+/// The TLS version, proxy, and other settings of the [`GraphClientConfiguration`],
+/// the credentials, and the endpoint are checked when the client is built.
+///
+/// # Example
+/// ```rust
+/// use graph_rs_sdk::GraphClient;
+///
+/// let client = GraphClient::builder()
+///     .access_token("ACCESS_TOKEN")
+///     .endpoint("https://graph.microsoft.com/beta")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(client.url().as_str(), "https://graph.microsoft.com/beta");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GraphClientBuilder {
+    config: GraphClientConfiguration,
+    endpoint: Option<String>,
+}
+
+impl GraphClientBuilder {
+    pub fn new() -> GraphClientBuilder {
+        GraphClientBuilder::default()
+    }
+
+    /// The configuration of the http client. Credentials set on the configuration
+    /// are kept unless they are set again on the builder.
+    pub fn config(mut self, config: GraphClientConfiguration) -> GraphClientBuilder {
+        self.config = config;
+        self
+    }
+
+    pub fn access_token<AT: ToString>(mut self, access_token: AT) -> GraphClientBuilder {
+        self.config = self.config.access_token(access_token);
+        self
+    }
+
+    pub fn client_application<CA: ClientApplication + 'static>(
+        mut self,
+        client_app: CA,
+    ) -> GraphClientBuilder {
+        self.config = self.config.client_application(client_app);
+        self
+    }
+
+    /// Set a custom endpoint for the Microsoft Graph API. The endpoint is checked in the
+    /// same way as [`GraphClient::try_use_endpoint`] when the client is built.
+    pub fn endpoint(mut self, endpoint: impl AsRef<str>) -> GraphClientBuilder {
+        self.endpoint = Some(endpoint.as_ref().to_owned());
+        self
+    }
+
+    /// Build the client.
+    ///
+    /// # Errors
+    /// Returns a [`ConfigurationError`] if no access token or client application was given,
+    /// the http client cannot be built from the configuration, or the endpoint is not a valid
+    /// Microsoft Graph endpoint.
+    pub fn build(self) -> Result<GraphClient, ConfigurationError> {
+        let mut client = GraphClient::from_client(self.config.try_build()?);
+
+        if let Some(endpoint) = self.endpoint {
+            let url = Url::parse(&endpoint)
+                .map_err(|source| ConfigurationError::InvalidEndpoint { endpoint, source })?;
+            client.try_use_endpoint(&url)?;
+        }
+
+        Ok(client)
+    }
+}
+
+impl From<GraphClientConfiguration> for GraphClientBuilder {
+    fn from(config: GraphClientConfiguration) -> Self {
+        GraphClientBuilder::new().config(config)
+    }
+}
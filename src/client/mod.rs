@@ -1,8 +1,15 @@
+// Some of the macros and helpers are unused when only some of the api client features are enabled.
 #[macro_use]
+#[cfg_attr(not(feature = "full"), allow(unused_macros))]
 pub mod api_macros;
+#[cfg_attr(not(feature = "full"), allow(dead_code))]
 pub mod common;
 
 mod graph;
+mod graph_client_builder;
+mod tenant_pool;
 
 pub(crate) use common::*;
 pub use graph::*;
+pub use graph_client_builder::*;
+pub use tenant_pool::*;
@@ -0,0 +1,323 @@
+use crate::client::GraphClient;
+use graph_core::cache::TokenCache;
+use graph_error::{AuthorizationFailure, IdentityResult};
+use graph_http::api_impl::GraphClientConfiguration;
+use graph_oauth::{
+    ClientAssertionCredential, ClientAssertionCredentialBuilder, ClientCertificateCredential,
+    ClientCertificateCredentialBuilder, ClientCredentialAdminConsentResponse,
+    ClientCredentialsAuthorizationUrlParameters, ClientSecretCredential,
+    ClientSecretCredentialBuilder, ConfidentialClientApplication, SignOut, TokenCredentialExecutor,
+};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tower::ServiceExt;
+use url::Url;
+
+/// Credential builders that can be used as the template of a [`TenantClientPool`].
+///
+/// Implemented for the builders of the client credentials flow: client secret,
+/// client certificate, and client assertion.
+pub trait TenantCredentialBuilder: Clone + Send + Sync {
+    type Credential: Clone
+        + Debug
+        + Send
+        + Sync
+        + TokenCache
+        + TokenCredentialExecutor
+        + SignOut
+        + 'static;
+
+    /// Build a credential for the tenant using a clone of the builder.
+    fn build_for_tenant(
+        &self,
+        tenant_id: &str,
+        config: &GraphClientConfiguration,
+    ) -> ConfidentialClientApplication<Self::Credential>;
+}
+
+macro_rules! tenant_credential_builder {
+    ($builder:ty, $credential:ty) => {
+        impl TenantCredentialBuilder for $builder {
+            type Credential = $credential;
+
+            fn build_for_tenant(
+                &self,
+                tenant_id: &str,
+                config: &GraphClientConfiguration,
+            ) -> ConfidentialClientApplication<Self::Credential> {
+                self.clone()
+                    .with_tenant(tenant_id)
+                    .with_config(config)
+                    .build()
+            }
+        }
+    };
+}
+
+tenant_credential_builder!(ClientSecretCredentialBuilder, ClientSecretCredential);
+tenant_credential_builder!(
+    ClientCertificateCredentialBuilder,
+    ClientCertificateCredential
+);
+tenant_credential_builder!(ClientAssertionCredentialBuilder, ClientAssertionCredential);
+
+#[derive(Clone)]
+struct TenantClient<C: Clone> {
+    client: GraphClient,
+    credential: ConfidentialClientApplication<C>,
+    // Updated by every request sent with the client.
+    last_used: Arc<Mutex<Instant>>,
+}
+
+impl<C: Clone> TenantClient<C> {
+    fn touch(&self) {
+        *self.last_used.lock().unwrap_or_else(|err| err.into_inner()) = Instant::now();
+    }
+
+    fn idle_time(&self) -> Duration {
+        self.last_used
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .elapsed()
+    }
+}
+
+/// Creates and reuses a [`GraphClient`] for each tenant of a multi-tenant application.
+///
+/// The credential of each tenant is built from a clone of one template credential builder
+/// with the tenant id set, the first time a client for the tenant is requested. Every
+/// tenant shares one connection pool and one token cache, where tokens are cached per
+/// tenant. Clones of the pool share the same clients.
+///
+/// # Example
+/// ```rust,ignore
+/// use graph_rs_sdk::{identity::ConfidentialClientApplication, TenantClientPool};
+///
+/// let template = ConfidentialClientApplication::builder("CLIENT_ID")
+///     .with_client_secret("CLIENT_SECRET")
+///     .with_scope(vec!["https://graph.microsoft.com/.default"]);
+///
+/// let pool = TenantClientPool::new(template)
+///     .max_concurrent_requests_per_tenant(4)
+///     .idle_timeout(Duration::from_secs(30 * 60));
+///
+/// let response = pool.client("TENANT_ID").users().list_user().send().await?;
+/// ```
+#[derive(Clone)]
+pub struct TenantClientPool<B: TenantCredentialBuilder> {
+    template: B,
+    config: GraphClientConfiguration,
+    max_concurrent_requests: Option<usize>,
+    idle_timeout: Option<Duration>,
+    clients: Arc<Mutex<HashMap<String, TenantClient<B::Credential>>>>,
+}
+
+impl<B: TenantCredentialBuilder> TenantClientPool<B> {
+    pub fn new(template: B) -> TenantClientPool<B> {
+        TenantClientPool {
+            template,
+            config: GraphClientConfiguration::new().share_connection_pool(),
+            max_concurrent_requests: None,
+            idle_timeout: None,
+            clients: Default::default(),
+        }
+    }
+
+    /// The configuration used for the clients and credentials of every tenant. The
+    /// connection pool of the configuration is shared by all tenants.
+    ///
+    /// Clients that have already been created keep their configuration.
+    pub fn config(mut self, config: GraphClientConfiguration) -> TenantClientPool<B> {
+        self.config = config.share_connection_pool();
+        self
+    }
+
+    /// Limit the number of requests to the Graph Api that are in flight at the same time
+    /// for each tenant. Requests for tokens are not limited.
+    ///
+    /// Default is no limit.
+    pub fn max_concurrent_requests_per_tenant(
+        mut self,
+        max_concurrent_requests: usize,
+    ) -> TenantClientPool<B> {
+        self.max_concurrent_requests = Some(max_concurrent_requests);
+        self
+    }
+
+    /// Remove the client and cached tokens of tenants that have not been used within
+    /// the timeout. A tenant is used when its client is returned from the pool and when
+    /// a request is sent with its client, including clients that were cloned. Idle tenants are evicted when a client is requested from the pool
+    /// or when [`TenantClientPool::evict_idle`] is called.
+    ///
+    /// Default is to keep clients until they are removed.
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> TenantClientPool<B> {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Returns the client for the tenant, creating the client and credential of the
+    /// tenant if they do not exist.
+    pub fn client(&self, tenant_id: impl AsRef<str>) -> GraphClient {
+        let tenant_id = tenant_id.as_ref();
+        let mut clients = self.clients.lock().unwrap_or_else(|err| err.into_inner());
+        self.evict_idle_clients(&mut clients);
+
+        let tenant_client = clients
+            .entry(tenant_id.to_owned())
+            .or_insert_with(|| self.new_tenant_client(tenant_id));
+        tenant_client.touch();
+        tenant_client.client.clone()
+    }
+
+    /// Returns true if the pool has a client for the tenant.
+    pub fn contains_tenant(&self, tenant_id: impl AsRef<str>) -> bool {
+        self.clients
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .contains_key(tenant_id.as_ref())
+    }
+
+    /// The tenant ids that have a client in the pool.
+    pub fn tenants(&self) -> Vec<String> {
+        self.clients
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove the client and cached token of the tenant. Returns true if the pool had a
+    /// client for the tenant.
+    pub fn remove(&self, tenant_id: impl AsRef<str>) -> bool {
+        self.clients
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(tenant_id.as_ref())
+            .map(|mut tenant_client| tenant_client.credential.evict_token())
+            .is_some()
+    }
+
+    /// Remove the clients of tenants that have been idle for longer than the
+    /// [idle timeout](TenantClientPool::idle_timeout). Returns the number of tenants removed.
+    pub fn evict_idle(&self) -> usize {
+        let mut clients = self.clients.lock().unwrap_or_else(|err| err.into_inner());
+        self.evict_idle_clients(&mut clients)
+    }
+
+    /// The admin consent url that onboards a tenant. The `tenant` is the tenant id or
+    /// domain of the organization, or `organizations` to let the admin sign in to any
+    /// organization. Once consent is granted the admin is redirected to the redirect uri
+    /// with the `tenant` and `admin_consent` query parameters that can be given to
+    /// [`TenantClientPool::connect_admin_consent`].
+    pub fn admin_consent_url(
+        &self,
+        tenant: impl AsRef<str>,
+        redirect_uri: Url,
+        state: Option<&str>,
+    ) -> IdentityResult<Url> {
+        let credential = self
+            .template
+            .build_for_tenant(tenant.as_ref(), &self.config);
+        let mut builder = ClientCredentialsAuthorizationUrlParameters::builder(
+            credential.client_id().to_string(),
+        );
+        builder
+            .with_tenant(tenant.as_ref())
+            .with_redirect_uri(redirect_uri);
+        if let Some(state) = state {
+            builder.with_state(state);
+        }
+        builder
+            .build()
+            .url_with_host(&credential.azure_cloud_instance())
+    }
+
+    /// Returns the client of the tenant from the query of the admin consent redirect.
+    /// Returns an error if the admin did not grant consent.
+    pub fn connect_admin_consent(
+        &self,
+        response: &ClientCredentialAdminConsentResponse,
+    ) -> IdentityResult<GraphClient> {
+        if !response.admin_consent {
+            return AuthorizationFailure::msg_result(
+                "admin_consent",
+                "admin consent was not granted for the tenant",
+            );
+        }
+
+        if response.tenant.trim().is_empty() {
+            return AuthorizationFailure::result("tenant");
+        }
+
+        Ok(self.client(&response.tenant))
+    }
+
+    fn new_tenant_client(&self, tenant_id: &str) -> TenantClient<B::Credential> {
+        let credential = self.template.build_for_tenant(tenant_id, &self.config);
+        let last_used = Arc::new(Mutex::new(Instant::now()));
+        let request_last_used = last_used.clone();
+        let config = self
+            .config
+            .clone()
+            .shared_concurrency_limit(self.max_concurrent_requests)
+            .client_application(credential.clone())
+            .wrap_service(move |service| {
+                let last_used = request_last_used.clone();
+                service
+                    .map_request(move |request| {
+                        *last_used.lock().unwrap_or_else(|err| err.into_inner()) = Instant::now();
+                        request
+                    })
+                    .boxed_clone()
+            });
+        TenantClient {
+            client: GraphClient::from(config),
+            credential,
+            last_used,
+        }
+    }
+
+    fn evict_idle_clients(
+        &self,
+        clients: &mut HashMap<String, TenantClient<B::Credential>>,
+    ) -> usize {
+        let Some(idle_timeout) = self.idle_timeout else {
+            return 0;
+        };
+
+        let len = clients.len();
+        clients.retain(|_, tenant_client| {
+            if tenant_client.idle_time() < idle_timeout {
+                return true;
+            }
+            tenant_client.credential.evict_token();
+            false
+        });
+        len - clients.len()
+    }
+}
+
+impl<B: TenantCredentialBuilder> Debug for TenantClientPool<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantClientPool")
+            .field("config", &self.config)
+            .field("max_concurrent_requests", &self.max_concurrent_requests)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("tenants", &self.tenants())
+            .finish()
+    }
+}
@@ -21,8 +21,13 @@
 //! and [tao](https://github.com/tauri-apps/tao) crates for webview support. Supports Linux and Windows platforms. Currently, does not support MacOS - work for this is in progress.
 //! * `openssl`: Enables support for using certificates in Client Credentials and Authorization Code auth flows. Additionally, enables related types such as X509Certificate
 //!   for building/running certificate based auth flows.
-//! * `test-util`: Enables test only features. Currently, this just enables the ability to turn off https only in the http client in order to use mocking frameworks with the crate.
-//! Other test related features may be added in the future.
+//! * `full`: Enabled by default. Enables every Api client module. Each top level module, such as `users`, `drives`, or `device_management`,
+//!   has its own feature named after the module with `-` in place of `_`, for example `device-management`. Disable the default features and enable only
+//!   the modules that are used to reduce compile times. Features of modules that use the Api clients of other modules enable those modules as well.
+//! * `beta`: Enables the separate Api clients of the Graph beta Api, exported as `graph_rs_sdk::beta`. Only the users and identity protection clients are available.
+//! * `metrics`: Enables `GraphClientConfiguration::metrics` which records counters and histograms of requests through the [metrics](https://crates.io/crates/metrics) facade.
+//! * `test-util`: Enables test only features. Enables the ability to turn off https only in the http client in order to use mocking frameworks with the crate,
+//!   and the `graph_rs_sdk::vcr` module for recording requests and responses to a cassette and replaying them in tests without credentials.
 //! * `native-tls`: Enables feature native-tls in the reqwest http-client. See the [reqwest crate](https://crates.io/crates/reqwest) for more details.
 //! * `rustls-tls`: Enables feature rustls-tls in the reqwest http-client. See the [reqwest crate](https://crates.io/crates/reqwest) for more details.
 //! * `brotli`: Enables feature brotli in the reqwest http-client. See the [reqwest crate](https://crates.io/crates/reqwest) for more details.
@@ -192,92 +197,181 @@
 /// Main Graph client.
 #[macro_use]
 pub(crate) mod client;
+#[cfg(feature = "admin")]
 pub mod admin;
+#[cfg(feature = "agreement-acceptances")]
 pub mod agreement_acceptances;
+#[cfg(feature = "agreements")]
 pub mod agreements;
+#[cfg(feature = "app-catalogs")]
 pub mod app_catalogs;
+#[cfg(feature = "applications")]
 pub mod applications;
+#[cfg(feature = "audit-logs")]
 pub mod audit_logs;
+#[cfg(feature = "authentication-method-configurations")]
 pub mod authentication_method_configurations;
+#[cfg(feature = "authentication-methods-policy")]
 pub mod authentication_methods_policy;
+#[cfg(feature = "batch")]
 pub mod batch;
+#[cfg(feature = "beta")]
+pub mod beta;
+#[cfg(feature = "branding")]
 pub mod branding;
+#[cfg(feature = "certificate-based-auth-configuration")]
 pub mod certificate_based_auth_configuration;
+#[cfg(feature = "chats")]
 pub mod chats;
+#[cfg(feature = "communications")]
 pub mod communications;
+#[cfg(feature = "contracts")]
 pub mod contracts;
+#[cfg(feature = "data-policy-operations")]
 pub mod data_policy_operations;
+#[cfg(feature = "default-drive")]
 pub mod default_drive;
+#[cfg(feature = "device-app-management")]
 pub mod device_app_management;
+#[cfg(feature = "device-management")]
 pub mod device_management;
+#[cfg(feature = "devices")]
 pub mod devices;
+#[cfg(feature = "directory")]
 pub mod directory;
+#[cfg(feature = "directory-objects")]
 pub mod directory_objects;
+#[cfg(feature = "directory-role-templates")]
 pub mod directory_role_templates;
+#[cfg(feature = "directory-roles")]
 pub mod directory_roles;
+#[cfg(feature = "domain-dns-records")]
 pub mod domain_dns_records;
+#[cfg(feature = "domains")]
 pub mod domains;
+#[cfg(feature = "drives")]
 pub mod drives;
+#[cfg(feature = "education")]
 pub mod education;
+#[cfg(feature = "extended-properties")]
 pub mod extended_properties;
+#[cfg(feature = "group-lifecycle-policies")]
 pub mod group_lifecycle_policies;
+#[cfg(feature = "groups")]
 pub mod groups;
 /// The main identity APIs with starting path `identity/`
+#[cfg(feature = "identity-access")]
 pub mod identity_access;
+#[cfg(feature = "identity-governance")]
 pub mod identity_governance;
+#[cfg(feature = "identity-providers")]
 pub mod identity_providers;
+#[cfg(feature = "invitations")]
 pub mod invitations;
+#[cfg(feature = "me")]
 pub mod me;
+#[cfg(feature = "oauth2-permission-grants")]
 pub mod oauth2_permission_grants;
+#[cfg(feature = "organization")]
 pub mod organization;
+#[cfg(feature = "permission-grants")]
 pub mod permission_grants;
+#[cfg(feature = "places")]
 pub mod places;
+#[cfg(feature = "planner")]
 pub mod planner;
+#[cfg(feature = "policies")]
 pub mod policies;
+#[cfg(feature = "reports")]
 pub mod reports;
+#[cfg(feature = "schema-extensions")]
 pub mod schema_extensions;
+#[cfg(feature = "service-principals")]
 pub mod service_principals;
+#[cfg(feature = "sites")]
 pub mod sites;
+#[cfg(feature = "solutions")]
 pub mod solutions;
+#[cfg(feature = "subscribed-skus")]
 pub mod subscribed_skus;
+#[cfg(feature = "subscriptions")]
 pub mod subscriptions;
+#[cfg(feature = "teams")]
 pub mod teams;
+#[cfg(feature = "teams-templates")]
 pub mod teams_templates;
+#[cfg(feature = "teamwork")]
 pub mod teamwork;
+#[cfg(feature = "users")]
 pub mod users;
 
 pub static GRAPH_URL: &str = "https://graph.microsoft.com/v1.0";
 pub static GRAPH_URL_BETA: &str = "https://graph.microsoft.com/beta";
 
-pub use crate::client::{Graph, GraphClient};
+pub use crate::client::{
+    Graph, GraphClient, GraphClientBuilder, TenantClientPool, TenantCredentialBuilder,
+};
+pub use graph_core::identity::CloudEnvironment;
 pub use graph_error::{GraphFailure, GraphResult};
-pub use graph_http::api_impl::{GraphClientConfiguration, ODataQuery};
+pub use graph_http::api_impl::{GraphClientConfiguration, GraphService, HttpTransport, ODataQuery};
 
 /// Reexport of graph-oauth crate.
 pub mod identity {
-    pub use graph_core::identity::ClientApplication;
+    pub use graph_core::identity::{ClientApplication, CloudEnvironment};
     pub use graph_oauth::*;
 }
 
+/// Tracing spans and metrics of requests. See [`GraphClientConfiguration::telemetry`].
+pub mod telemetry {
+    pub use graph_http::api_impl::{
+        GRAPH_REQUESTS_TOTAL, GRAPH_REQUEST_DURATION_SECONDS, GRAPH_REQUEST_RETRIES_TOTAL,
+        GRAPH_THROTTLED_RESPONSES_TOTAL, GRAPH_THROTTLE_WAIT_SECONDS,
+    };
+}
+
+/// Client side rate limiting of requests. See [`GraphClientConfiguration::rate_limiter`].
+pub mod rate_limit {
+    pub use graph_http::api_impl::{RateLimit, RateLimitService, RateLimiter};
+}
+
+/// Caching of responses with ETags. See [`GraphClientConfiguration::response_cache`].
+pub mod cache {
+    pub use graph_http::api_impl::{CachedResponse, InMemoryCache, ResponseCache};
+}
+
 pub mod http {
     pub use graph_core::http::{HttpResponseBuilderExt, HttpResponseExt};
-    pub use graph_http::api_impl::{BodyRead, FileConfig, UploadSession};
+    pub use graph_http::api_impl::{
+        AsyncOperationState, AsyncOperationStatus, BodyRead, FileConfig, LongRunningOperation,
+        TypedRequestHandler, TypedResponse, TypedResult, UploadSession,
+    };
     pub use graph_http::traits::{
         AsyncIterator, ODataDeltaLink, ODataDownloadLink, ODataMetadataLink, ODataNextLink,
         ODataQuery, ResponseBlockingExt, ResponseExt, UploadSessionLink,
     };
 
     pub mod blocking {
-        pub use graph_http::api_impl::UploadSessionBlocking;
+        pub use graph_http::api_impl::{TypedBlockingRequestHandler, UploadSessionBlocking};
         pub use reqwest::blocking::Body;
     }
 
     pub use reqwest::tls::Version;
     pub use reqwest::{Body, Method};
-    pub use reqwest::{NoProxy, Proxy};
+    pub use reqwest::{Certificate, Identity, NoProxy, Proxy};
     pub use url::Url;
 }
 
+/// Typed expressions for the OData query parameters `$filter`, `$orderby`, and `$expand`.
+pub mod odata {
+    pub use graph_http::odata::*;
+}
+
+/// Record and replay requests and responses for tests.
+#[cfg(feature = "test-util")]
+pub mod vcr {
+    pub use graph_http::vcr::*;
+}
+
 /// Reexport of graph-error crate.
 pub mod error {
     pub use graph_error::*;
@@ -288,6 +382,7 @@ pub mod header {
     pub use reqwest::header::*;
 }
 
+#[cfg_attr(not(feature = "full"), allow(unused_imports))]
 pub(crate) mod api_default_imports {
     pub(crate) use handlebars::*;
     pub(crate) use reqwest::Method;
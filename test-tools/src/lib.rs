@@ -6,5 +6,7 @@ extern crate serde_json;
 extern crate lazy_static;
 
 pub mod common;
+pub mod mock_server;
 pub mod oauth_request;
 pub mod support;
+pub mod vcr;
@@ -0,0 +1,786 @@
+use crate::mock_server::entity::{self, Counters, EntityStore};
+use crate::mock_server::{GraphState, MockBody, MockRequest, MockResponse};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+const CONFLICT_BEHAVIOR: &str = "@microsoft.graph.conflictBehavior";
+
+/// Upload sessions of the mock server do not expire.
+const SESSION_EXPIRATION: &str = "2099-01-01T00:00:00Z";
+
+/// Identifies a drive by its owner, such as `users/{id}`, or by the id of the drive.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum DriveKey {
+    Owner(String),
+    Id(String),
+}
+
+/// Returns the rest of the path when the path starts with the segment.
+fn strip_segment<'a>(path: &'a str, segment: &str) -> Option<&'a str> {
+    let rest = path.strip_prefix(segment)?;
+    if rest.is_empty() || rest.starts_with('/') {
+        Some(rest.trim_start_matches('/'))
+    } else {
+        None
+    }
+}
+
+/// Splits a path such as `users/{id}/drive/root/children` into the drive and the rest of
+/// the path after the drive.
+pub(crate) fn drive_route<'a>(path: &'a str, me_id: &str) -> Option<(DriveKey, &'a str)> {
+    if let Some(rest) = strip_segment(path, "me/drive").or_else(|| strip_segment(path, "drive")) {
+        return Some((DriveKey::Owner(format!("users/{me_id}")), rest));
+    }
+
+    let mut segments = path.splitn(3, '/');
+    let resource = segments.next()?;
+    let id = segments.next()?;
+    let rest = segments.next().unwrap_or_default();
+    match resource {
+        "drives" => Some((DriveKey::Id(id.to_string()), rest)),
+        "users" | "groups" | "sites" => Some((
+            DriveKey::Owner(format!("{resource}/{id}")),
+            strip_segment(rest, "drive")?,
+        )),
+        _ => None,
+    }
+}
+
+/// The item a request is for: the root or an item id, an optional path relative to the
+/// item, and the action such as `children` or `content`.
+#[derive(Debug, Eq, PartialEq)]
+struct ItemRoute<'a> {
+    item_id: Option<&'a str>,
+    path: Option<&'a str>,
+    action: &'a str,
+}
+
+impl<'a> ItemRoute<'a> {
+    fn parse(rest: &'a str) -> Option<ItemRoute<'a>> {
+        let (item_id, rest) = if let Some(rest) = rest.strip_prefix("root") {
+            (None, rest)
+        } else if let Some(rest) = rest.strip_prefix("items/") {
+            let end = rest.find([':', '/']).unwrap_or(rest.len());
+            (Some(&rest[..end]), &rest[end..])
+        } else {
+            return None;
+        };
+
+        let (path, rest) = match rest.strip_prefix(':') {
+            Some(rest) => match rest.find(':') {
+                Some(end) => (Some(&rest[..end]), &rest[end + 1..]),
+                None => (Some(rest), ""),
+            },
+            None => (None, rest),
+        };
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+
+        Some(ItemRoute {
+            item_id,
+            path: path
+                .map(|path| path.trim_matches('/'))
+                .filter(|path| !path.is_empty()),
+            action: rest.trim_start_matches('/'),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Drive {
+    id: String,
+    root_id: String,
+    items: EntityStore,
+    content: HashMap<String, Vec<u8>>,
+}
+
+impl Drive {
+    fn new(id: String, counters: &mut Counters) -> Drive {
+        let root_id = counters.next_id();
+        let mut items = EntityStore::default();
+        let version = counters.next_version();
+        items.insert(
+            &root_id,
+            json!({
+                "name": "root",
+                "root": {},
+                "folder": {},
+                "size": 0,
+                "parentReference": { "driveId": id }
+            }),
+            version,
+        );
+
+        Drive {
+            id,
+            root_id,
+            items,
+            content: HashMap::new(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "driveType": "business",
+            "quota": { "used": self.content.values().map(|c| c.len()).sum::<usize>() }
+        })
+    }
+
+    fn children(&self, parent_id: &str) -> Vec<Value> {
+        self.items
+            .filter(|item| item["parentReference"]["id"].as_str() == Some(parent_id))
+    }
+
+    fn child_by_name(&self, parent_id: &str, name: &str) -> Option<String> {
+        self.children(parent_id)
+            .into_iter()
+            .find(|item| item["name"].as_str() == Some(name))
+            .and_then(|item| item["id"].as_str().map(|id| id.to_string()))
+    }
+
+    /// The id of the item at the path relative to the parent.
+    fn resolve(&self, parent_id: &str, path: Option<&str>) -> Option<String> {
+        match path {
+            Some(path) => path
+                .split('/')
+                .try_fold(parent_id.to_string(), |parent, name| {
+                    self.child_by_name(&parent, name)
+                }),
+            None => self
+                .items
+                .contains(parent_id)
+                .then(|| parent_id.to_string()),
+        }
+    }
+
+    /// The id of the folder at the path relative to the parent. Missing folders are created.
+    fn create_folders(
+        &mut self,
+        parent_id: &str,
+        path: &[&str],
+        counters: &mut Counters,
+    ) -> String {
+        let mut parent = parent_id.to_string();
+        for name in path {
+            parent = match self.child_by_name(&parent, name) {
+                Some(id) => id,
+                None => self.create_item(&parent, name, json!({ "folder": {} }), counters)["id"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+        }
+        parent
+    }
+
+    fn create_item(
+        &mut self,
+        parent_id: &str,
+        name: &str,
+        mut item: Value,
+        counters: &mut Counters,
+    ) -> Value {
+        let id = counters.next_id();
+        let version = counters.next_version();
+        item["name"] = json!(name);
+        item["parentReference"] = json!({ "driveId": self.id, "id": parent_id });
+        if item.get("size").is_none() {
+            item["size"] = json!(0);
+        }
+        self.items.insert(&id, item, version)
+    }
+
+    /// Creates or replaces the content of the file with the name in the parent folder.
+    /// Returns true when the file was created.
+    fn put_content(
+        &mut self,
+        parent_id: &str,
+        name: &str,
+        content: Vec<u8>,
+        counters: &mut Counters,
+    ) -> (bool, Value) {
+        let file = json!({
+            "file": { "mimeType": "application/octet-stream" },
+            "size": content.len()
+        });
+        let (created, item) = match self.child_by_name(parent_id, name) {
+            Some(id) => {
+                let version = counters.next_version();
+                (
+                    false,
+                    self.items.update(&id, &file, version).unwrap_or_default(),
+                )
+            }
+            None => (true, self.create_item(parent_id, name, file, counters)),
+        };
+        if let Some(id) = item["id"].as_str() {
+            self.content.insert(id.to_string(), content);
+        }
+        (created, item)
+    }
+
+    /// A name that is not used by another item in the folder, such as `file 1.txt`.
+    fn unique_name(&self, parent_id: &str, name: &str) -> String {
+        let (stem, extension) = match name.rfind('.') {
+            Some(index) if index > 0 => (&name[..index], &name[index..]),
+            _ => (name, ""),
+        };
+        let mut unique = name.to_string();
+        let mut count = 1;
+        while self.child_by_name(parent_id, &unique).is_some() {
+            unique = format!("{stem} {count}{extension}");
+            count += 1;
+        }
+        unique
+    }
+
+    fn remove(&mut self, id: &str, counters: &mut Counters) {
+        let children: Vec<String> = self
+            .children(id)
+            .iter()
+            .filter_map(|child| child["id"].as_str().map(|id| id.to_string()))
+            .collect();
+        for child in children {
+            self.remove(&child, counters);
+        }
+        let version = counters.next_version();
+        self.items.remove(id, version);
+        self.content.remove(id);
+    }
+
+    /// The item as returned by the Api with the child count of folders and the download
+    /// url of files.
+    fn view(&self, mut item: Value, base_url: &str) -> Value {
+        let id = item["id"].as_str().unwrap_or_default().to_string();
+        if item.get("folder").is_some() {
+            item["folder"] = json!({ "childCount": self.children(&id).len() });
+        }
+        if item.get("file").is_some() {
+            let download_url = format!("{base_url}/download/{}/{id}", self.id);
+            item["@microsoft.graph.downloadUrl"] = json!(download_url);
+            item["@content.downloadUrl"] = json!(download_url);
+        }
+        item
+    }
+}
+
+/// The drives of the mock server. Drives are created the first time they are used.
+#[derive(Debug, Default)]
+pub(crate) struct DriveStore {
+    owners: HashMap<String, String>,
+    drives: BTreeMap<String, Drive>,
+}
+
+impl DriveStore {
+    fn get_or_create(&mut self, key: &DriveKey, counters: &mut Counters) -> &mut Drive {
+        let id = match key {
+            DriveKey::Id(id) => id.clone(),
+            DriveKey::Owner(owner) => self
+                .owners
+                .entry(owner.clone())
+                .or_insert_with(|| counters.next_id())
+                .clone(),
+        };
+        self.drives
+            .entry(id.clone())
+            .or_insert_with(|| Drive::new(id, counters))
+    }
+
+    pub fn item(&self, id: &str) -> Option<Value> {
+        self.drives
+            .values()
+            .find_map(|drive| drive.items.get(id).cloned())
+    }
+
+    pub fn content(&self, id: &str) -> Option<Vec<u8>> {
+        self.drives
+            .values()
+            .find_map(|drive| drive.content.get(id).cloned())
+    }
+}
+
+/// An upload session created with `createUploadSession`.
+#[derive(Debug)]
+pub(crate) struct UploadSessionState {
+    drive_id: String,
+    parent_id: String,
+    name: String,
+    received: Vec<u8>,
+    total: Option<usize>,
+}
+
+/// A copy of a drive item. The copy is created right away and the monitor url reports
+/// the operation as in progress until it has been polled once.
+#[derive(Debug)]
+pub(crate) struct CopyOperation {
+    resource_id: String,
+    polled: bool,
+}
+
+fn item_not_found() -> MockResponse {
+    MockResponse::error(404, "itemNotFound", "The resource could not be found.")
+}
+
+fn name_conflict() -> MockResponse {
+    MockResponse::error(
+        409,
+        "nameAlreadyExists",
+        "The specified item name already exists.",
+    )
+}
+
+/// Handles the requests for a drive and its items.
+pub(crate) fn drive(
+    state: &mut GraphState,
+    request: &MockRequest,
+    key: &DriveKey,
+    rest: &str,
+    base_url: &str,
+) -> MockResponse {
+    let GraphState {
+        drives,
+        counters,
+        uploads,
+        operations,
+        ..
+    } = state;
+    let drive = drives.get_or_create(key, counters);
+
+    if rest.is_empty() {
+        return match request.method.as_str() {
+            "GET" => MockResponse::json(200, drive.to_json()),
+            _ => MockResponse::unsupported(request),
+        };
+    }
+
+    let route = match ItemRoute::parse(rest) {
+        Some(route) => route,
+        None => return MockResponse::unsupported(request),
+    };
+    let base_id = route.item_id.unwrap_or(drive.root_id.as_str()).to_string();
+    let item_id = drive.resolve(&base_id, route.path);
+
+    match (request.method.as_str(), route.action) {
+        ("GET", "") => match item_id.and_then(|id| drive.items.get(&id).cloned()) {
+            Some(item) => {
+                MockResponse::json(200, entity::select(drive.view(item, base_url), request))
+            }
+            None => item_not_found(),
+        },
+        ("PATCH", "") => {
+            let id = match item_id {
+                Some(id) => id,
+                None => return item_not_found(),
+            };
+            let patch = request.json().unwrap_or_default();
+            let version = counters.next_version();
+            match drive.items.update(&id, &patch, version) {
+                Some(item) => MockResponse::json(200, drive.view(item, base_url)),
+                None => item_not_found(),
+            }
+        }
+        ("DELETE", "") => match item_id {
+            Some(id) if id != drive.root_id => {
+                drive.remove(&id, counters);
+                MockResponse::no_content()
+            }
+            Some(_) => MockResponse::error(403, "accessDenied", "The root can not be deleted."),
+            None => item_not_found(),
+        },
+        ("GET", "children") => match item_id {
+            Some(id) => {
+                let children = drive
+                    .children(&id)
+                    .into_iter()
+                    .map(|child| drive.view(child, base_url))
+                    .collect();
+                MockResponse::json(200, entity::page(request, children, None, base_url))
+            }
+            None => item_not_found(),
+        },
+        ("POST", "children") => {
+            let parent_id = match item_id {
+                Some(id) => id,
+                None => return item_not_found(),
+            };
+            let mut item = match request.json() {
+                Some(item @ Value::Object(_)) => item,
+                _ => {
+                    return MockResponse::error(
+                        400,
+                        "invalidRequest",
+                        "The request body must be a json object.",
+                    )
+                }
+            };
+            let name = match item["name"].as_str() {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => return MockResponse::error(400, "invalidRequest", "Name is required."),
+            };
+            let behavior = item
+                .as_object_mut()
+                .and_then(|item| item.remove(CONFLICT_BEHAVIOR))
+                .and_then(|behavior| behavior.as_str().map(|b| b.to_string()))
+                .unwrap_or_else(|| "fail".into());
+
+            let name = match (drive.child_by_name(&parent_id, &name), behavior.as_str()) {
+                (None, _) => name,
+                (Some(_), "rename") => drive.unique_name(&parent_id, &name),
+                (Some(existing), "replace") => {
+                    drive.remove(&existing, counters);
+                    name
+                }
+                (Some(_), _) => return name_conflict(),
+            };
+            let item = drive.create_item(&parent_id, &name, item, counters);
+            MockResponse::json(201, drive.view(item, base_url))
+        }
+        ("GET", "content") => match item_id.and_then(|id| drive.content.get(&id)) {
+            Some(content) => MockResponse::bytes(200, content.clone()),
+            None => item_not_found(),
+        },
+        ("PUT", "content") => {
+            let (parent_id, name) = match target(drive, &base_id, route.path, counters) {
+                Some(target) => target,
+                None => return item_not_found(),
+            };
+            if request.query(CONFLICT_BEHAVIOR) == Some("fail")
+                && drive.child_by_name(&parent_id, &name).is_some()
+            {
+                return name_conflict();
+            }
+            let (created, item) =
+                drive.put_content(&parent_id, &name, request.body.clone(), counters);
+            MockResponse::json(if created { 201 } else { 200 }, drive.view(item, base_url))
+        }
+        ("POST", "createUploadSession") => {
+            let (parent_id, name) = match target(drive, &base_id, route.path, counters) {
+                Some(target) => target,
+                None => return item_not_found(),
+            };
+            let body = request.json().unwrap_or_default();
+            let behavior = body["item"][CONFLICT_BEHAVIOR]
+                .as_str()
+                .or_else(|| body[CONFLICT_BEHAVIOR].as_str())
+                .unwrap_or("replace");
+            let name = match (drive.child_by_name(&parent_id, &name), behavior) {
+                (Some(_), "fail") => return name_conflict(),
+                (Some(_), "rename") => drive.unique_name(&parent_id, &name),
+                _ => name,
+            };
+
+            let session_id = counters.next_id();
+            uploads.insert(
+                session_id.clone(),
+                UploadSessionState {
+                    drive_id: drive.id.clone(),
+                    parent_id,
+                    name,
+                    received: Vec::new(),
+                    total: None,
+                },
+            );
+            MockResponse::json(
+                200,
+                json!({
+                    "uploadUrl": format!("{base_url}/upload/{session_id}"),
+                    "expirationDateTime": SESSION_EXPIRATION,
+                    "nextExpectedRanges": ["0-"]
+                }),
+            )
+        }
+        ("POST", "copy") => {
+            let item = match item_id.and_then(|id| drive.items.get(&id).cloned()) {
+                Some(item) => item,
+                None => return item_not_found(),
+            };
+            let content = match item["id"].as_str().and_then(|id| drive.content.get(id)) {
+                Some(content) => content.clone(),
+                None => {
+                    return MockResponse::error(400, "notSupported", "Only files can be copied.")
+                }
+            };
+            let body = request.json().unwrap_or_default();
+            let parent_id = body["parentReference"]["id"]
+                .as_str()
+                .or_else(|| item["parentReference"]["id"].as_str())
+                .unwrap_or(drive.root_id.as_str())
+                .to_string();
+            let name = body["name"]
+                .as_str()
+                .or_else(|| item["name"].as_str())
+                .unwrap_or_default()
+                .to_string();
+            if drive.child_by_name(&parent_id, &name).is_some() {
+                return name_conflict();
+            }
+
+            let (_, copy) = drive.put_content(&parent_id, &name, content, counters);
+            let operation_id = counters.next_id();
+            operations.insert(
+                operation_id.clone(),
+                CopyOperation {
+                    resource_id: copy["id"].as_str().unwrap_or_default().to_string(),
+                    polled: false,
+                },
+            );
+            MockResponse {
+                status: 202,
+                headers: Vec::new(),
+                body: MockBody::Empty,
+            }
+            .header("location", &format!("{base_url}/monitor/{operation_id}"))
+        }
+        ("GET", "delta()") if route.item_id.is_none() && route.path.is_none() => {
+            let changes = match request.query("$deltatoken") {
+                Some(token) => match token.parse::<u64>() {
+                    Ok(version) => drive.items.changes_since(version),
+                    Err(_) => {
+                        return MockResponse::error(400, "invalidRequest", "Invalid delta token.")
+                    }
+                },
+                None => drive.items.list(),
+            };
+            let changes = changes
+                .into_iter()
+                .map(|item| drive.view(item, base_url))
+                .collect();
+            let delta_link = entity::link(
+                base_url,
+                &request.full_path,
+                &[("$deltatoken".to_string(), counters.version.to_string())],
+            );
+            MockResponse::json(
+                200,
+                entity::page(request, changes, Some(delta_link), base_url),
+            )
+        }
+        _ => MockResponse::unsupported(request),
+    }
+}
+
+/// The parent folder and name of the file a request writes to. Folders in the path are
+/// created when they do not exist.
+fn target(
+    drive: &mut Drive,
+    base_id: &str,
+    path: Option<&str>,
+    counters: &mut Counters,
+) -> Option<(String, String)> {
+    match path {
+        Some(path) => {
+            let segments: Vec<&str> = path.split('/').collect();
+            let (name, folders) = segments.split_last()?;
+            drive.resolve(base_id, None)?;
+            let parent_id = drive.create_folders(base_id, folders, counters);
+            Some((parent_id, name.to_string()))
+        }
+        None => {
+            let item = drive.items.get(base_id)?;
+            Some((
+                item["parentReference"]["id"].as_str()?.to_string(),
+                item["name"].as_str()?.to_string(),
+            ))
+        }
+    }
+}
+
+/// Parses a `Content-Range` header such as `bytes 0-99/1000`.
+fn content_range(value: &str) -> Option<(usize, usize, usize)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+    Some((start.parse().ok()?, end.parse().ok()?, total.parse().ok()?))
+}
+
+/// The status of a copy operation in the format of an `asyncJobStatus`. Monitor urls do
+/// not require an access token.
+pub(crate) fn monitor(state: &mut GraphState, operation_id: &str) -> MockResponse {
+    let operation = match state.operations.get_mut(operation_id) {
+        Some(operation) => operation,
+        None => return item_not_found(),
+    };
+    if !operation.polled {
+        operation.polled = true;
+        return MockResponse::json(
+            202,
+            json!({
+                "operation": "itemCopy",
+                "status": "inProgress",
+                "percentageComplete": 50.0
+            }),
+        );
+    }
+    MockResponse::json(
+        200,
+        json!({
+            "operation": "itemCopy",
+            "status": "completed",
+            "percentageComplete": 100.0,
+            "resourceId": operation.resource_id
+        }),
+    )
+}
+
+/// Handles the requests to the upload url of an upload session. Byte ranges must be
+/// uploaded in order and the size of the file can not change.
+pub(crate) fn upload(
+    state: &mut GraphState,
+    request: &MockRequest,
+    session_id: &str,
+    base_url: &str,
+) -> MockResponse {
+    let session = match state.uploads.get_mut(session_id) {
+        Some(session) => session,
+        None => return item_not_found(),
+    };
+
+    match request.method.as_str() {
+        "GET" => MockResponse::json(
+            200,
+            json!({
+                "expirationDateTime": SESSION_EXPIRATION,
+                "nextExpectedRanges": [format!("{}-", session.received.len())]
+            }),
+        ),
+        "DELETE" => {
+            state.uploads.remove(session_id);
+            MockResponse::no_content()
+        }
+        "PUT" => {
+            let (start, end, total) = match request.header("content-range").and_then(content_range)
+            {
+                Some(range) => range,
+                None => {
+                    return MockResponse::error(
+                        400,
+                        "invalidRequest",
+                        "The Content-Range header is missing or invalid.",
+                    )
+                }
+            };
+            if session.total.map(|t| t != total).unwrap_or_default() {
+                return MockResponse::error(
+                    400,
+                    "invalidRequest",
+                    "The total size of the file can not change during an upload.",
+                );
+            }
+            if start != session.received.len() || end < start || end >= total {
+                return MockResponse::error(
+                    416,
+                    "invalidRange",
+                    &format!(
+                        "The uploaded fragment overlaps with data that has already been received or is out of range. The next expected range is {}-.",
+                        session.received.len()
+                    ),
+                );
+            }
+            if request.body.len() != end - start + 1 {
+                return MockResponse::error(
+                    400,
+                    "invalidRequest",
+                    "The Content-Length does not match the Content-Range.",
+                );
+            }
+
+            session.total = Some(total);
+            session.received.extend_from_slice(&request.body);
+            if session.received.len() < total {
+                return MockResponse::json(
+                    202,
+                    json!({
+                        "expirationDateTime": SESSION_EXPIRATION,
+                        "nextExpectedRanges": [format!("{}-{}", session.received.len(), total - 1)]
+                    }),
+                );
+            }
+
+            let session = match state.uploads.remove(session_id) {
+                Some(session) => session,
+                None => return item_not_found(),
+            };
+            let GraphState {
+                drives, counters, ..
+            } = state;
+            let drive = drives.get_or_create(&DriveKey::Id(session.drive_id), counters);
+            let (created, item) = drive.put_content(
+                &session.parent_id,
+                &session.name,
+                session.received,
+                counters,
+            );
+            MockResponse::json(if created { 201 } else { 200 }, drive.view(item, base_url))
+        }
+        _ => MockResponse::unsupported(request),
+    }
+}
+
+/// The content of a file using its download url.
+pub(crate) fn download(state: &GraphState, drive_id: &str, item_id: &str) -> MockResponse {
+    match state
+        .drives
+        .drives
+        .get(drive_id)
+        .and_then(|drive| drive.content.get(item_id))
+    {
+        Some(content) => MockResponse::bytes(200, content.clone()),
+        None => item_not_found(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_drive_route() {
+        assert_eq!(
+            Some((
+                DriveKey::Owner("users/me".into()),
+                "root:/file.txt:/content"
+            )),
+            drive_route("me/drive/root:/file.txt:/content", "me")
+        );
+        assert_eq!(
+            Some((DriveKey::Id("1".into()), "items/2")),
+            drive_route("drives/1/items/2", "me")
+        );
+        assert_eq!(
+            Some((DriveKey::Owner("groups/1".into()), "")),
+            drive_route("groups/1/drive", "me")
+        );
+        assert_eq!(None, drive_route("groups/1/drives", "me"));
+        assert_eq!(None, drive_route("users/1", "me"));
+    }
+
+    #[test]
+    fn parse_item_route() {
+        assert_eq!(
+            Some(ItemRoute {
+                item_id: None,
+                path: Some("folder/file.txt"),
+                action: "createUploadSession"
+            }),
+            ItemRoute::parse("root:/folder/file.txt:/createUploadSession")
+        );
+        assert_eq!(
+            Some(ItemRoute {
+                item_id: Some("1"),
+                path: None,
+                action: "children"
+            }),
+            ItemRoute::parse("items/1/children")
+        );
+        assert_eq!(
+            Some(ItemRoute {
+                item_id: Some("1"),
+                path: Some("file.txt"),
+                action: ""
+            }),
+            ItemRoute::parse("items/1:/file.txt:")
+        );
+        assert_eq!(None, ItemRoute::parse("roots"));
+    }
+}
@@ -0,0 +1,294 @@
+use crate::mock_server::{MockRequest, MockResponse};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Page size used when a request does not set `$top`.
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Generates ids and versions. Every change to an entity gets a new version which is
+/// used as the delta token of delta queries.
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    pub version: u64,
+    pub next_id: u64,
+    pub requests: u64,
+}
+
+impl Counters {
+    pub fn next_version(&mut self) -> u64 {
+        self.version += 1;
+        self.version
+    }
+
+    pub fn next_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("00000000-0000-0000-0000-{:012}", self.next_id)
+    }
+
+    pub fn next_request_id(&mut self) -> String {
+        self.requests += 1;
+        format!("11111111-0000-0000-0000-{:012}", self.requests)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entity {
+    version: u64,
+    value: Value,
+}
+
+/// An in memory entity set such as users or groups.
+#[derive(Debug, Default)]
+pub(crate) struct EntityStore {
+    entities: BTreeMap<String, Entity>,
+    removed: BTreeMap<String, u64>,
+}
+
+impl EntityStore {
+    pub fn insert(&mut self, id: &str, mut value: Value, version: u64) -> Value {
+        value["id"] = Value::String(id.to_string());
+        self.removed.remove(id);
+        self.entities.insert(
+            id.to_string(),
+            Entity {
+                version,
+                value: value.clone(),
+            },
+        );
+        value
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Value> {
+        self.entities.get(id).map(|entity| &entity.value)
+    }
+
+    /// The weak ETag of the current version of the entity.
+    pub fn etag(&self, id: &str) -> Option<String> {
+        self.entities
+            .get(id)
+            .map(|entity| format!("W/\"{}\"", entity.version))
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.entities.contains_key(id)
+    }
+
+    /// Merges the top level properties of the patch into the entity.
+    pub fn update(&mut self, id: &str, patch: &Value, version: u64) -> Option<Value> {
+        let entity = self.entities.get_mut(id)?;
+        if let (Some(target), Some(patch)) = (entity.value.as_object_mut(), patch.as_object()) {
+            for (key, value) in patch.iter().filter(|(key, _)| key.as_str() != "id") {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+        entity.version = version;
+        Some(entity.value.clone())
+    }
+
+    pub fn remove(&mut self, id: &str, version: u64) -> Option<Value> {
+        let entity = self.entities.remove(id)?;
+        self.removed.insert(id.to_string(), version);
+        Some(entity.value)
+    }
+
+    pub fn list(&self) -> Vec<Value> {
+        self.entities
+            .values()
+            .map(|entity| entity.value.clone())
+            .collect()
+    }
+
+    pub fn filter<F: Fn(&Value) -> bool>(&self, f: F) -> Vec<Value> {
+        self.entities
+            .values()
+            .map(|entity| &entity.value)
+            .filter(|value| f(value))
+            .cloned()
+            .collect()
+    }
+
+    /// Entities changed after the version followed by the entities removed after the version.
+    pub fn changes_since(&self, version: u64) -> Vec<Value> {
+        let mut changes: Vec<Value> = self
+            .entities
+            .values()
+            .filter(|entity| entity.version > version)
+            .map(|entity| entity.value.clone())
+            .collect();
+        changes.extend(
+            self.removed
+                .iter()
+                .filter(|(_, removed)| **removed > version)
+                .map(|(id, _)| json!({ "id": id, "@removed": { "reason": "deleted" } })),
+        );
+        changes
+    }
+}
+
+/// Handles the requests of an entity set: list, create, delta, get, update, and delete.
+/// The segments are the path segments after the entity set.
+pub(crate) fn entity_set(
+    store: &mut EntityStore,
+    counters: &mut Counters,
+    request: &MockRequest,
+    segments: &[&str],
+    base_url: &str,
+) -> MockResponse {
+    match (request.method.as_str(), segments) {
+        ("GET", []) => MockResponse::json(200, page(request, store.list(), None, base_url)),
+        ("POST", []) => match request.json() {
+            Some(value @ Value::Object(_)) => {
+                let id = value["id"]
+                    .as_str()
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| counters.next_id());
+                if store.contains(&id) {
+                    return MockResponse::error(
+                        400,
+                        "Request_BadRequest",
+                        "Another object with the same value for property id already exists.",
+                    );
+                }
+                let version = counters.next_version();
+                MockResponse::json(201, store.insert(&id, value, version))
+            }
+            _ => MockResponse::error(400, "BadRequest", "The request body must be a json object."),
+        },
+        ("GET", ["delta()"]) => {
+            let changes = match request.query("$deltatoken") {
+                Some(token) => match token.parse::<u64>() {
+                    Ok(version) => store.changes_since(version),
+                    Err(_) => {
+                        return MockResponse::error(400, "badRequest", "Invalid delta token.")
+                    }
+                },
+                None => store.list(),
+            };
+            let delta_link = link(
+                base_url,
+                &request.full_path,
+                &[("$deltatoken".to_string(), counters.version.to_string())],
+            );
+            MockResponse::json(200, page(request, changes, Some(delta_link), base_url))
+        }
+        ("GET", [id]) => match (store.get(id), store.etag(id)) {
+            (Some(value), Some(etag)) => {
+                if request.header("if-none-match") == Some(etag.as_str()) {
+                    return MockResponse::not_modified().header("etag", &etag);
+                }
+                MockResponse::json(200, select(value.clone(), request)).header("etag", &etag)
+            }
+            _ => MockResponse::not_found(id),
+        },
+        ("PATCH" | "DELETE", [id]) if !if_match(store, request, id) => MockResponse::error(
+            412,
+            "PreconditionFailed",
+            "The If-Match header does not match the current ETag of the resource.",
+        ),
+        ("PATCH", [id]) => {
+            let patch = request.json().unwrap_or(Value::Null);
+            let version = counters.next_version();
+            match store.update(id, &patch, version) {
+                Some(_) => MockResponse::no_content(),
+                None => MockResponse::not_found(id),
+            }
+        }
+        ("DELETE", [id]) => {
+            let version = counters.next_version();
+            match store.remove(id, version) {
+                Some(_) => MockResponse::no_content(),
+                None => MockResponse::not_found(id),
+            }
+        }
+        _ => MockResponse::unsupported(request),
+    }
+}
+
+/// Requests without an `If-Match` header, or with `*`, always match.
+fn if_match(store: &EntityStore, request: &MockRequest, id: &str) -> bool {
+    match (request.header("if-match"), store.etag(id)) {
+        (None, _) | (Some("*"), Some(_)) => true,
+        (Some(if_match), Some(etag)) => if_match == etag,
+        (Some(_), None) => false,
+    }
+}
+
+/// A page of a collection using `$top` and `$skiptoken`. The `@odata.nextLink` keeps the
+/// other query parameters of the request. The delta link is added to the last page.
+pub(crate) fn page(
+    request: &MockRequest,
+    items: Vec<Value>,
+    delta_link: Option<String>,
+    base_url: &str,
+) -> Value {
+    let top = request
+        .query("$top")
+        .and_then(|top| top.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .max(1);
+    let skip = request
+        .query("$skiptoken")
+        .and_then(|skip| skip.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let total = items.len();
+    let value: Vec<Value> = items
+        .into_iter()
+        .skip(skip)
+        .take(top)
+        .map(|item| select(item, request))
+        .collect();
+
+    let mut body = Map::new();
+    body.insert("value".into(), Value::Array(value));
+    if skip + top < total {
+        let mut query: Vec<(String, String)> = request
+            .query
+            .iter()
+            .filter(|(key, _)| key != "$skiptoken")
+            .cloned()
+            .collect();
+        query.push(("$skiptoken".into(), (skip + top).to_string()));
+        body.insert(
+            "@odata.nextLink".into(),
+            Value::String(link(base_url, &request.full_path, &query)),
+        );
+    } else if let Some(delta_link) = delta_link {
+        body.insert("@odata.deltaLink".into(), Value::String(delta_link));
+    }
+    Value::Object(body)
+}
+
+/// Keeps only the properties in `$select`. The id is always returned.
+pub(crate) fn select(value: Value, request: &MockRequest) -> Value {
+    match (request.query("$select"), value) {
+        (Some(select), Value::Object(map)) => {
+            let properties: Vec<&str> = select.split(',').map(|p| p.trim()).collect();
+            Value::Object(
+                map.into_iter()
+                    .filter(|(key, _)| {
+                        key == "id" || key.starts_with('@') || properties.contains(&key.as_str())
+                    })
+                    .collect(),
+            )
+        }
+        (_, value) => value,
+    }
+}
+
+pub(crate) fn link(base_url: &str, path: &str, query: &[(String, String)]) -> String {
+    if query.is_empty() {
+        return format!("{base_url}{path}");
+    }
+
+    let query: Vec<String> = query
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{key}={}",
+                url::form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>()
+            )
+        })
+        .collect();
+    format!("{base_url}{path}?{}", query.join("&"))
+}
@@ -0,0 +1,731 @@
+//! An in memory Microsoft Graph server for tests.
+//!
+//! [`MockGraphServer`] binds a random port on localhost and emulates users, groups,
+//! messages, and drive items including paging with `$top`, delta queries, upload
+//! sessions, copies of drive items with a monitor url, `$batch`, throttling, claims
+//! challenges, ETags of users, groups, and messages, and the token endpoint of the
+//! identity platform.
+//!
+//! ```rust,ignore
+//! use test_tools::mock_server::MockGraphServer;
+//!
+//! let server = MockGraphServer::start().await;
+//! let client = server.graph_client();
+//!
+//! let response = client.users().create_user(&serde_json::json!({
+//!     "displayName": "Adele Vance"
+//! })).send().await?;
+//! assert_eq!(201, response.status().as_u16());
+//! ```
+//!
+//! Clients built from [`MockGraphServer::client_configuration`] send requests for the
+//! Graph and login hosts of the national clouds to the mock server, so credentials
+//! configured with it request tokens from the mock token endpoint.
+
+mod drive;
+mod entity;
+mod token;
+
+pub use entity::DEFAULT_PAGE_SIZE;
+
+use crate::mock_server::drive::{CopyOperation, DriveStore, UploadSessionState};
+use crate::mock_server::entity::{Counters, EntityStore};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use graph_rs_sdk::{GraphClient, GraphClientConfiguration, GraphService};
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+use url::Url;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+/// Hosts of the Graph Api and the identity platform that clients built from
+/// [`MockGraphServer::client_configuration`] send to the mock server instead.
+pub const MOCK_HOSTS: [&str; 10] = [
+    "graph.microsoft.com",
+    "canary.graph.microsoft.com",
+    "graph.microsoft.us",
+    "dod-graph.microsoft.us",
+    "graph.microsoft.de",
+    "microsoftgraph.chinacloudapi.cn",
+    "login.microsoftonline.com",
+    "login.microsoftonline.us",
+    "login.microsoftonline.de",
+    "login.chinacloudapi.cn",
+];
+
+/// Access token used by [`MockGraphServer::graph_client`]. The mock server accepts any
+/// bearer token.
+pub const MOCK_ACCESS_TOKEN: &str = "mock-access-token";
+
+/// Maximum number of requests in a `$batch` request.
+const MAX_BATCH_REQUESTS: usize = 20;
+
+/// A request received by the mock server.
+#[derive(Debug, Clone)]
+pub(crate) struct MockRequest {
+    pub method: String,
+    /// The percent encoded path including the version such as `/v1.0/users`.
+    pub full_path: String,
+    /// The decoded path without the version such as `/users`.
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Header names are lowercase.
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    fn new(method: &str, url: &Url, headers: HashMap<String, String>, body: Vec<u8>) -> Self {
+        let full_path = url.path().to_string();
+        let decoded = percent_encoding::percent_decode_str(&full_path)
+            .decode_utf8_lossy()
+            .to_string();
+        let path = ["/v1.0", "/beta"]
+            .iter()
+            .find_map(|version| decoded.strip_prefix(version))
+            .unwrap_or(decoded.as_str())
+            .to_string();
+
+        MockRequest {
+            method: method.to_uppercase(),
+            full_path,
+            path,
+            query: url.query_pairs().into_owned().collect(),
+            headers,
+            body,
+        }
+    }
+
+    pub fn query(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k.eq(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|v| v.as_str())
+    }
+
+    pub fn json(&self) -> Option<Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+
+    pub fn form(&self) -> HashMap<String, String> {
+        url::form_urlencoded::parse(&self.body)
+            .into_owned()
+            .collect()
+    }
+}
+
+impl From<&Request> for MockRequest {
+    fn from(request: &Request) -> Self {
+        let headers = request
+            .headers
+            .iter()
+            .map(|(name, values)| {
+                (
+                    name.as_str().to_lowercase(),
+                    values.last().as_str().to_string(),
+                )
+            })
+            .collect();
+        MockRequest::new(
+            request.method.as_ref(),
+            &request.url,
+            headers,
+            request.body.clone(),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum MockBody {
+    Empty,
+    Json(Value),
+    Bytes(Vec<u8>),
+}
+
+/// A response returned by the mock server.
+#[derive(Debug, Clone)]
+pub(crate) struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: MockBody,
+}
+
+impl MockResponse {
+    pub fn json(status: u16, body: Value) -> MockResponse {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body: MockBody::Json(body),
+        }
+    }
+
+    pub fn bytes(status: u16, body: Vec<u8>) -> MockResponse {
+        MockResponse {
+            status,
+            headers: vec![(
+                "content-type".to_string(),
+                "application/octet-stream".to_string(),
+            )],
+            body: MockBody::Bytes(body),
+        }
+    }
+
+    pub fn no_content() -> MockResponse {
+        MockResponse {
+            status: 204,
+            headers: Vec::new(),
+            body: MockBody::Empty,
+        }
+    }
+
+    pub fn not_modified() -> MockResponse {
+        MockResponse {
+            status: 304,
+            headers: Vec::new(),
+            body: MockBody::Empty,
+        }
+    }
+
+    /// An error in the format of the Graph Api.
+    pub fn error(status: u16, code: &str, message: &str) -> MockResponse {
+        MockResponse::json(
+            status,
+            json!({
+                "error": {
+                    "code": code,
+                    "message": message,
+                    "innerError": {
+                        "request-id": "00000000-0000-0000-0000-000000000000",
+                        "client-request-id": "00000000-0000-0000-0000-000000000000"
+                    }
+                }
+            }),
+        )
+    }
+
+    pub fn not_found(id: &str) -> MockResponse {
+        MockResponse::error(
+            404,
+            "Request_ResourceNotFound",
+            &format!("Resource '{id}' does not exist or one of its queried reference-property objects are not present."),
+        )
+    }
+
+    pub fn unsupported(request: &MockRequest) -> MockResponse {
+        MockResponse::error(
+            400,
+            "BadRequest",
+            &format!(
+                "Resource not found for the segment '{} {}'.",
+                request.method, request.path
+            ),
+        )
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> MockResponse {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn batch_response(&self, id: &Value) -> Value {
+        let headers: serde_json::Map<String, Value> = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        let mut response = json!({
+            "id": id,
+            "status": self.status,
+            "headers": headers,
+        });
+        match &self.body {
+            MockBody::Empty => {}
+            MockBody::Json(body) => response["body"] = body.clone(),
+            MockBody::Bytes(body) => {
+                response["body"] = Value::String(String::from_utf8_lossy(body).to_string())
+            }
+        }
+        response
+    }
+}
+
+impl From<MockResponse> for ResponseTemplate {
+    fn from(response: MockResponse) -> Self {
+        let mut template = ResponseTemplate::new(response.status);
+        for (name, value) in response.headers.iter() {
+            template = template.insert_header(name.as_str(), value.as_str());
+        }
+        match response.body {
+            MockBody::Empty => template,
+            MockBody::Json(body) => template.set_body_json(body),
+            MockBody::Bytes(body) => template.set_body_bytes(body),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Throttle {
+    remaining: usize,
+    retry_after: Duration,
+}
+
+#[derive(Debug)]
+struct ClaimsChallenge {
+    remaining: usize,
+    claims: String,
+}
+
+/// The in memory state of the mock server.
+#[derive(Debug)]
+pub(crate) struct GraphState {
+    pub counters: Counters,
+    pub users: EntityStore,
+    pub groups: EntityStore,
+    pub messages: BTreeMap<String, EntityStore>,
+    pub drives: DriveStore,
+    pub uploads: HashMap<String, UploadSessionState>,
+    pub operations: HashMap<String, CopyOperation>,
+    pub me_id: String,
+    pub tokens_issued: usize,
+    throttle: Option<Throttle>,
+    claims_challenge: Option<ClaimsChallenge>,
+}
+
+impl GraphState {
+    fn new() -> GraphState {
+        let mut counters = Counters::default();
+        let mut users = EntityStore::default();
+        let me_id = counters.next_id();
+        let version = counters.next_version();
+        users.insert(
+            &me_id,
+            json!({
+                "displayName": "Mock User",
+                "userPrincipalName": "mock.user@contoso.onmicrosoft.com",
+                "mail": "mock.user@contoso.onmicrosoft.com"
+            }),
+            version,
+        );
+
+        GraphState {
+            counters,
+            users,
+            groups: Default::default(),
+            messages: Default::default(),
+            drives: Default::default(),
+            uploads: Default::default(),
+            operations: Default::default(),
+            me_id,
+            tokens_issued: 0,
+            throttle: None,
+            claims_challenge: None,
+        }
+    }
+
+    fn handle(&mut self, request: &MockRequest, base_url: &str) -> MockResponse {
+        let path = request.path.trim_matches('/').to_string();
+        let segments: Vec<&str> = path.split('/').collect();
+
+        match segments.as_slice() {
+            [_tenant, "oauth2", "v2.0", "token"] => token::token(self, request),
+            [_tenant, "oauth2", "v2.0", "devicecode"] => token::device_code(request),
+            ["upload", session_id] => drive::upload(self, request, session_id, base_url),
+            ["monitor", operation_id] if request.method == "GET" => {
+                drive::monitor(self, operation_id)
+            }
+            ["download", drive_id, item_id] if request.method == "GET" => {
+                drive::download(self, drive_id, item_id)
+            }
+            _ => {
+                if let Some(response) = self.authorize(request) {
+                    return response;
+                }
+                self.graph(request, &path, base_url)
+            }
+        }
+    }
+
+    /// Requires an access token, returns 401 responses with a claims challenge and
+    /// returns 429 responses while throttling.
+    fn authorize(&mut self, request: &MockRequest) -> Option<MockResponse> {
+        let authorized = request
+            .header("authorization")
+            .map(|value| value.starts_with("Bearer ") || value.starts_with("PoP "))
+            .unwrap_or_default();
+        if !authorized {
+            return Some(MockResponse::error(
+                401,
+                "InvalidAuthenticationToken",
+                "Access token is empty.",
+            ));
+        }
+
+        if let Some(claims_challenge) = self.claims_challenge.as_mut() {
+            if claims_challenge.remaining > 0 {
+                claims_challenge.remaining -= 1;
+                let www_authenticate = format!(
+                    r#"Bearer realm="", error="insufficient_claims", claims="{}""#,
+                    STANDARD.encode(claims_challenge.claims.as_bytes())
+                );
+                return Some(
+                    MockResponse::error(
+                        401,
+                        "InvalidAuthenticationToken",
+                        "Continuous access evaluation resulted in claims challenge.",
+                    )
+                    .header("www-authenticate", &www_authenticate),
+                );
+            }
+            self.claims_challenge = None;
+        }
+
+        let throttle = self.throttle.as_mut()?;
+        if throttle.remaining == 0 {
+            self.throttle = None;
+            return None;
+        }
+        throttle.remaining -= 1;
+        Some(
+            MockResponse::error(
+                429,
+                "TooManyRequests",
+                "Too many requests. Please retry after the time in the Retry-After header.",
+            )
+            .header("retry-after", &throttle.retry_after.as_secs().to_string()),
+        )
+    }
+
+    fn graph(&mut self, request: &MockRequest, path: &str, base_url: &str) -> MockResponse {
+        if let Some((drive_key, rest)) = drive::drive_route(path, &self.me_id) {
+            return drive::drive(self, request, &drive_key, rest, base_url);
+        }
+
+        let me_id = self.me_id.clone();
+        let segments: Vec<&str> = path.split('/').collect();
+        match segments.as_slice() {
+            ["$batch"] if request.method == "POST" => self.batch(request, base_url),
+            ["me", "messages", rest @ ..] => self.messages(request, &me_id, rest, base_url),
+            ["users", user_id, "messages", rest @ ..] => {
+                self.messages(request, user_id, rest, base_url)
+            }
+            ["me"] => entity::entity_set(
+                &mut self.users,
+                &mut self.counters,
+                request,
+                &[me_id.as_str()],
+                base_url,
+            ),
+            ["users", rest @ ..] => {
+                let response = entity::entity_set(
+                    &mut self.users,
+                    &mut self.counters,
+                    request,
+                    rest,
+                    base_url,
+                );
+                if let ("DELETE", 204, [user_id]) = (request.method.as_str(), response.status, rest)
+                {
+                    self.messages.remove(*user_id);
+                }
+                response
+            }
+            ["groups", rest @ ..] => entity::entity_set(
+                &mut self.groups,
+                &mut self.counters,
+                request,
+                rest,
+                base_url,
+            ),
+            _ => MockResponse::unsupported(request),
+        }
+    }
+
+    fn messages(
+        &mut self,
+        request: &MockRequest,
+        user_id: &str,
+        segments: &[&str],
+        base_url: &str,
+    ) -> MockResponse {
+        if !self.users.contains(user_id) {
+            return MockResponse::not_found(user_id);
+        }
+
+        let store = self.messages.entry(user_id.to_string()).or_default();
+        let response = entity::entity_set(store, &mut self.counters, request, segments, base_url);
+        match (request.method.as_str(), response.status, segments) {
+            // Updating a message returns the message.
+            ("PATCH", 204, [id]) => match store.get(id) {
+                Some(message) => MockResponse::json(200, message.clone()),
+                None => response,
+            },
+            _ => response,
+        }
+    }
+
+    /// Runs each request of a json batch and returns the responses in the same order.
+    fn batch(&mut self, request: &MockRequest, base_url: &str) -> MockResponse {
+        let requests = match request.json() {
+            Some(Value::Object(mut body)) => match body.remove("requests") {
+                Some(Value::Array(requests)) => requests,
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        if requests.is_empty() || requests.len() > MAX_BATCH_REQUESTS {
+            return MockResponse::error(
+                400,
+                "BadRequest",
+                "A batch request must contain between 1 and 20 requests.",
+            );
+        }
+
+        let version = request.full_path.trim_end_matches("/$batch").to_string();
+        let responses: Vec<Value> = requests
+            .iter()
+            .map(|batch_request| {
+                let url = batch_request["url"].as_str().unwrap_or_default();
+                let url = format!("{base_url}{version}/{}", url.trim_start_matches('/'));
+                let url = match Url::parse(&url) {
+                    Ok(url) => url,
+                    Err(_) => {
+                        return MockResponse::error(400, "BadRequest", "Invalid request url.")
+                            .batch_response(&batch_request["id"])
+                    }
+                };
+
+                let mut headers: HashMap<String, String> = batch_request["headers"]
+                    .as_object()
+                    .map(|headers| {
+                        headers
+                            .iter()
+                            .map(|(name, value)| {
+                                (
+                                    name.to_lowercase(),
+                                    value.as_str().unwrap_or_default().to_string(),
+                                )
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if let Some(authorization) = request.header("authorization") {
+                    headers.insert("authorization".into(), authorization.to_string());
+                }
+                let body = match &batch_request["body"] {
+                    Value::Null => Vec::new(),
+                    body => body.to_string().into_bytes(),
+                };
+
+                let method = batch_request["method"].as_str().unwrap_or("GET");
+                let inner = MockRequest::new(method, &url, headers, body);
+                let path = inner.path.trim_matches('/').to_string();
+                let response = match self.authorize(&inner) {
+                    Some(response) => response,
+                    None => self.graph(&inner, &path, base_url),
+                };
+                response.batch_response(&batch_request["id"])
+            })
+            .collect();
+
+        MockResponse::json(200, json!({ "responses": responses }))
+    }
+}
+
+struct GraphResponder {
+    state: Arc<Mutex<GraphState>>,
+    base_url: String,
+}
+
+impl Respond for GraphResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let request = MockRequest::from(request);
+        let mut state = self.state.lock();
+        let request_id = state.counters.next_request_id();
+        let mut response = state
+            .handle(&request, &self.base_url)
+            .header("request-id", &request_id);
+        if let Some(client_request_id) = request.header("client-request-id") {
+            response = response.header("client-request-id", client_request_id);
+        }
+        ResponseTemplate::from(response)
+    }
+}
+
+/// An in memory Graph Api server bound to a random port on localhost. The server stops
+/// when it is dropped.
+pub struct MockGraphServer {
+    server: MockServer,
+    state: Arc<Mutex<GraphState>>,
+}
+
+impl MockGraphServer {
+    /// Start a server with a single user that is returned for `/me`.
+    pub async fn start() -> MockGraphServer {
+        let server = MockServer::start().await;
+        let state = Arc::new(Mutex::new(GraphState::new()));
+        Mock::given(any())
+            .respond_with(GraphResponder {
+                state: state.clone(),
+                base_url: server.uri(),
+            })
+            .mount(&server)
+            .await;
+
+        MockGraphServer { server, state }
+    }
+
+    /// The base url of the server such as `http://127.0.0.1:8080`.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// The v1.0 endpoint of the server.
+    pub fn endpoint(&self) -> Url {
+        Url::parse(&format!("{}/v1.0", self.server.uri())).unwrap()
+    }
+
+    /// The id of the user returned for `/me`.
+    pub fn me_id(&self) -> String {
+        self.state.lock().me_id.clone()
+    }
+
+    /// A configuration that allows http and sends the requests for the hosts in
+    /// [`MOCK_HOSTS`] to the mock server. Use it for the client and for credentials
+    /// so that tokens are requested from the mock token endpoint.
+    pub fn client_configuration(&self) -> GraphClientConfiguration {
+        let address = *self.server.address();
+        GraphClientConfiguration::new()
+            .https_only(false)
+            .wrap_service(move |service: GraphService| {
+                tower::service_fn(move |mut request: reqwest::Request| {
+                    let url = request.url_mut();
+                    if url
+                        .host_str()
+                        .map(|host| MOCK_HOSTS.contains(&host))
+                        .unwrap_or_default()
+                    {
+                        let _ = url.set_scheme("http");
+                        let _ = url.set_ip_host(address.ip());
+                        let _ = url.set_port(Some(address.port()));
+                    }
+                    service.clone().oneshot(request)
+                })
+                .boxed_clone()
+            })
+    }
+
+    /// A client with a static access token that uses the server as its endpoint.
+    pub fn graph_client(&self) -> GraphClient {
+        self.graph_client_with(self.client_configuration().access_token(MOCK_ACCESS_TOKEN))
+    }
+
+    /// A client built from the configuration that uses the server as its endpoint. The
+    /// configuration should start from [`MockGraphServer::client_configuration`].
+    pub fn graph_client_with(&self, config: GraphClientConfiguration) -> GraphClient {
+        let mut client = GraphClient::from(config);
+        client.use_test_endpoint(&self.endpoint());
+        client
+    }
+
+    /// Adds a user and returns the user with its id. An id is generated when the user
+    /// does not have one.
+    pub fn insert_user(&self, user: Value) -> Value {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        insert(&mut state.users, &mut state.counters, user)
+    }
+
+    /// Adds a group and returns the group with its id.
+    pub fn insert_group(&self, group: Value) -> Value {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        insert(&mut state.groups, &mut state.counters, group)
+    }
+
+    /// Adds a message to the mailbox of a user and returns the message with its id.
+    pub fn insert_message(&self, user_id: &str, message: Value) -> Value {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        let store = state.messages.entry(user_id.to_string()).or_default();
+        insert(store, &mut state.counters, message)
+    }
+
+    pub fn users(&self) -> Vec<Value> {
+        self.state.lock().users.list()
+    }
+
+    pub fn user(&self, id: &str) -> Option<Value> {
+        self.state.lock().users.get(id).cloned()
+    }
+
+    pub fn groups(&self) -> Vec<Value> {
+        self.state.lock().groups.list()
+    }
+
+    pub fn messages(&self, user_id: &str) -> Vec<Value> {
+        self.state
+            .lock()
+            .messages
+            .get(user_id)
+            .map(|store| store.list())
+            .unwrap_or_default()
+    }
+
+    /// A drive item in any drive.
+    pub fn drive_item(&self, id: &str) -> Option<Value> {
+        self.state.lock().drives.item(id)
+    }
+
+    /// The content of a file in any drive.
+    pub fn drive_item_content(&self, id: &str) -> Option<Vec<u8>> {
+        self.state.lock().drives.content(id)
+    }
+
+    /// Respond to the next `count` Graph Api requests with `429 Too Many Requests` and
+    /// a `Retry-After` header. Requests to the token endpoint and to upload urls are
+    /// not throttled.
+    pub fn throttle(&self, count: usize, retry_after: Duration) {
+        self.state.lock().throttle = Some(Throttle {
+            remaining: count,
+            retry_after,
+        });
+    }
+
+    /// Respond to the next `count` Graph Api requests with `401 Unauthorized` and a
+    /// `WWW-Authenticate` header with a claims challenge for `claims`, as done when
+    /// Continuous Access Evaluation (CAE) revokes a token.
+    pub fn claims_challenge(&self, count: usize, claims: &str) {
+        self.state.lock().claims_challenge = Some(ClaimsChallenge {
+            remaining: count,
+            claims: claims.to_string(),
+        });
+    }
+
+    /// The number of access tokens returned by the token endpoint.
+    pub fn tokens_issued(&self) -> usize {
+        self.state.lock().tokens_issued
+    }
+
+    /// Every request received by the server.
+    pub async fn received_requests(&self) -> Vec<Request> {
+        self.server.received_requests().await.unwrap_or_default()
+    }
+}
+
+fn insert(store: &mut EntityStore, counters: &mut Counters, value: Value) -> Value {
+    let id = value["id"]
+        .as_str()
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| counters.next_id());
+    let version = counters.next_version();
+    store.insert(&id, value, version)
+}
@@ -0,0 +1,104 @@
+use crate::mock_server::{GraphState, MockRequest, MockResponse};
+use serde_json::json;
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+const JWT_BEARER_GRANT: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+/// Seconds until tokens issued by the mock token endpoint expire.
+const EXPIRES_IN: u64 = 3599;
+
+pub(crate) const MOCK_DEVICE_CODE: &str = "mock-device-code";
+
+fn oauth_error(error: &str, description: &str) -> MockResponse {
+    MockResponse::json(
+        400,
+        json!({
+            "error": error,
+            "error_description": description,
+            "error_codes": [900144],
+            "trace_id": "00000000-0000-0000-0000-000000000000",
+            "correlation_id": "00000000-0000-0000-0000-000000000000"
+        }),
+    )
+}
+
+/// The token endpoint: `POST /{tenant}/oauth2/v2.0/token`.
+pub(crate) fn token(state: &mut GraphState, request: &MockRequest) -> MockResponse {
+    if request.method != "POST" {
+        return MockResponse::unsupported(request);
+    }
+
+    let form = request.form();
+    // Client secrets may be sent with basic authentication instead of in the body.
+    let basic_auth = request
+        .header("authorization")
+        .map(|value| value.starts_with("Basic "))
+        .unwrap_or_default();
+    if !form.contains_key("client_id") && !basic_auth {
+        return oauth_error(
+            "invalid_request",
+            "AADSTS900144: The request body must contain the following parameter: 'client_id'.",
+        );
+    }
+
+    let grant_type = form
+        .get("grant_type")
+        .map(|s| s.as_str())
+        .unwrap_or_default();
+    let (required, refresh_token) = match grant_type {
+        "client_credentials" => (vec![], false),
+        "password" => (vec!["username", "password"], true),
+        "authorization_code" => (vec!["code"], true),
+        "refresh_token" => (vec!["refresh_token"], true),
+        DEVICE_CODE_GRANT => (vec!["device_code"], true),
+        JWT_BEARER_GRANT => (vec!["assertion"], false),
+        _ => {
+            return oauth_error(
+                "unsupported_grant_type",
+                &format!(
+                    "AADSTS70003: The app requested an unsupported grant type '{grant_type}'."
+                ),
+            )
+        }
+    };
+    if let Some(missing) = required.iter().find(|param| !form.contains_key(**param)) {
+        return oauth_error(
+            "invalid_request",
+            &format!(
+                "AADSTS900144: The request body must contain the following parameter: '{missing}'."
+            ),
+        );
+    }
+
+    state.tokens_issued += 1;
+    let mut body = json!({
+        "token_type": "Bearer",
+        "scope": form.get("scope").cloned().unwrap_or_else(|| "https://graph.microsoft.com/.default".into()),
+        "expires_in": EXPIRES_IN,
+        "ext_expires_in": EXPIRES_IN,
+        "access_token": format!("mock-access-token-{}", state.tokens_issued),
+    });
+    if refresh_token {
+        body["refresh_token"] = json!(format!("mock-refresh-token-{}", state.tokens_issued));
+    }
+    MockResponse::json(200, body)
+}
+
+/// The device authorization endpoint: `POST /{tenant}/oauth2/v2.0/devicecode`.
+pub(crate) fn device_code(request: &MockRequest) -> MockResponse {
+    if request.method != "POST" {
+        return MockResponse::unsupported(request);
+    }
+
+    MockResponse::json(
+        200,
+        json!({
+            "device_code": MOCK_DEVICE_CODE,
+            "user_code": "MOCKCODE",
+            "verification_uri": "https://microsoft.com/devicelogin",
+            "expires_in": 900,
+            "interval": 1,
+            "message": "To sign in, use a web browser to open the page https://microsoft.com/devicelogin and enter the code MOCKCODE to authenticate."
+        }),
+    )
+}
@@ -0,0 +1,61 @@
+//! Record and replay the requests of integration tests so that they can run without
+//! credentials.
+//!
+//! Set `GRAPH_VCR_MODE=record` and the test app credentials to record a cassette to
+//! `tests/cassettes/<name>.json`. Otherwise, the cassette is replayed.
+use crate::oauth_request::OAuthTestClient;
+use graph_error::{GraphFailure, GraphResult};
+use graph_rs_sdk::vcr::{Redactor, Vcr, VcrMode, REDACTED};
+use graph_rs_sdk::{GraphClient, GraphClientConfiguration};
+use std::path::PathBuf;
+
+/// Directory of the cassettes relative to the root of the repository.
+pub const CASSETTE_DIR: &str = "./tests/cassettes";
+
+/// The id of the test user is replaced with this value in cassettes.
+pub const CASSETTE_USER_ID: &str = "USER_ID";
+
+pub struct VcrTestClient {
+    pub client: GraphClient,
+    pub user_id: String,
+    pub vcr: Vcr,
+}
+
+impl VcrTestClient {
+    pub fn new(name: &str) -> GraphResult<VcrTestClient> {
+        let vcr = Vcr::from_env(VcrTestClient::cassette_path(name))?;
+
+        match vcr.mode() {
+            VcrMode::Record => {
+                let (user_id, client_application) = OAuthTestClient::ClientCredentials
+                    .request_access_token_credential()
+                    .ok_or_else(|| {
+                        GraphFailure::not_found("Recording a cassette requires test credentials")
+                    })?;
+                let vcr = vcr.redactor(Redactor::new().replace(&user_id, CASSETTE_USER_ID));
+                let client = GraphClient::from(vcr.configure(
+                    GraphClientConfiguration::new().client_application(client_application),
+                ));
+                Ok(VcrTestClient {
+                    client,
+                    user_id,
+                    vcr,
+                })
+            }
+            VcrMode::Replay => {
+                let client = GraphClient::from(
+                    vcr.configure(GraphClientConfiguration::new().access_token(REDACTED)),
+                );
+                Ok(VcrTestClient {
+                    client,
+                    user_id: CASSETTE_USER_ID.to_string(),
+                    vcr,
+                })
+            }
+        }
+    }
+
+    pub fn cassette_path(name: &str) -> PathBuf {
+        PathBuf::from(CASSETTE_DIR).join(format!("{name}.json"))
+    }
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "beta")]
+
+use graph_rs_sdk::*;
+use url::Url;
+
+#[test]
+fn beta_client_url() {
+    let client = Graph::new("");
+    let beta = client.beta_client();
+
+    assert_eq!(beta.url().as_str(), GRAPH_URL_BETA);
+    assert_eq!(beta.users().list_user().url().path(), "/beta/users");
+    assert_eq!(
+        beta.user("user-id").list_activity_statistics().url().path(),
+        "/beta/users/user-id/analytics/activityStatistics"
+    );
+    assert_eq!(
+        beta.identity_protection()
+            .get_risky_users("risky-user-id")
+            .url()
+            .path(),
+        "/beta/identityProtection/riskyUsers/risky-user-id"
+    );
+
+    // The v1.0 client is not changed.
+    assert_eq!(client.users().list_user().url().path(), "/v1.0/users");
+}
+
+#[test]
+fn beta_client_uses_host_of_endpoint() {
+    let mut client = Graph::new("");
+    client.use_endpoint(&Url::parse("https://graph.microsoft.us/v1.0").unwrap());
+
+    assert_eq!(
+        client.beta_client().users().list_user().url().as_str(),
+        "https://graph.microsoft.us/beta/users"
+    );
+}
@@ -0,0 +1,35 @@
+use graph_rs_sdk::identity::ConfidentialClientApplication;
+use graph_rs_sdk::{CloudEnvironment, GraphClient};
+use test_tools::mock_server::MockGraphServer;
+
+#[tokio::test]
+async fn us_government_cloud_environment() {
+    let server = MockGraphServer::start().await;
+    let config = server
+        .client_configuration()
+        .cloud_environment(CloudEnvironment::USGov);
+
+    let confidential_client =
+        ConfidentialClientApplication::builder("6731de76-14a6-49ae-97bc-6eba6914391e")
+            .with_client_secret("secret")
+            .with_tenant("tenant-id")
+            .with_cloud_environment(CloudEnvironment::USGov)
+            .with_config(&config)
+            .build();
+    let client = GraphClient::from(config.client_application(confidential_client));
+    assert_eq!("https://graph.microsoft.us/v1.0", client.url().as_str());
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+
+    let requests = server.received_requests().await;
+    assert_eq!("/tenant-id/oauth2/v2.0/token", requests[0].url.path());
+    let body: Vec<(String, String)> = url::form_urlencoded::parse(&requests[0].body)
+        .into_owned()
+        .collect();
+    assert!(body.contains(&(
+        "scope".to_string(),
+        "https://graph.microsoft.us/.default".to_string()
+    )));
+    assert_eq!("/v1.0/me", requests[1].url.path());
+}
@@ -0,0 +1,57 @@
+use graph_rs_sdk::error::{GraphErrorCode, GraphFailure};
+use std::time::Duration;
+use test_tools::mock_server::MockGraphServer;
+
+#[tokio::test]
+async fn send_checked_returns_success_responses() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client.me().get_user().send_checked().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn send_checked_not_found() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let result = client
+        .user("missing-user")
+        .get_user()
+        .client_request_id("client-request-id")
+        .send_checked()
+        .await;
+
+    match result {
+        Err(GraphFailure::GraphApiError(error)) => {
+            assert_eq!(404, error.status().as_u16());
+            assert_eq!(Some(GraphErrorCode::RequestResourceNotFound), error.code());
+            assert!(error.has_code(&GraphErrorCode::RequestResourceNotFound));
+            assert!(error.message().unwrap().contains("missing-user"));
+            assert!(!error.is_retryable());
+            assert_eq!(None, error.retry_after());
+            assert_eq!(Some("client-request-id"), error.client_request_id());
+            assert!(error.request_id().is_some());
+        }
+        _ => panic!("expected GraphFailure::GraphApiError"),
+    }
+}
+
+#[tokio::test]
+async fn send_checked_throttled() {
+    let server = MockGraphServer::start().await;
+    server.throttle(1, Duration::from_secs(30));
+    let client = server.graph_client();
+
+    let failure = client.me().get_user().send_checked().await.unwrap_err();
+    assert!(failure.diagnostics().unwrap().request_id.is_some());
+    match failure {
+        GraphFailure::GraphApiError(error) => {
+            assert_eq!(429, error.status().as_u16());
+            assert!(error.is_retryable());
+            assert_eq!(Some(Duration::from_secs(30)), error.retry_after());
+        }
+        _ => panic!("expected GraphFailure::GraphApiError"),
+    }
+}
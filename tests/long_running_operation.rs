@@ -0,0 +1,123 @@
+use graph_rs_sdk::error::{AsyncOperationError, GraphFailure};
+use graph_rs_sdk::http::AsyncOperationState;
+use graph_rs_sdk::GraphClient;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use test_tools::mock_server::MockGraphServer;
+
+async fn upload_file(client: &GraphClient) -> String {
+    let response = client
+        .me()
+        .drive()
+        .item_by_path(":/file.txt:")
+        .update_items_content(reqwest::Body::from("file content"))
+        .send()
+        .await
+        .unwrap();
+    let file: serde_json::Value = response.json().await.unwrap();
+    file["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn copy_drive_item_completes() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+    let file_id = upload_file(&client).await;
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let progress_clone = progress.clone();
+    let status = client
+        .me()
+        .drive()
+        .item(&file_id)
+        .copy(&serde_json::json!({ "name": "copy.txt" }))
+        .send_long_running()
+        .await
+        .unwrap()
+        .poll_interval(Duration::from_millis(10))
+        .on_progress(move |status| {
+            progress_clone
+                .lock()
+                .unwrap()
+                .push(status.percentage_complete)
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(AsyncOperationState::Completed, status.state);
+    assert_eq!(
+        vec![Some(50.0), Some(100.0)],
+        progress.lock().unwrap().clone()
+    );
+    let copy_id = status.resource_id.unwrap();
+    assert_ne!(file_id, copy_id);
+    assert_eq!(
+        Some(b"file content".to_vec()),
+        server.drive_item_content(&copy_id)
+    );
+}
+
+#[tokio::test]
+async fn long_running_operation_timeout() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+    let file_id = upload_file(&client).await;
+
+    let result = client
+        .me()
+        .drive()
+        .item(&file_id)
+        .copy(&serde_json::json!({ "name": "copy.txt" }))
+        .send_long_running()
+        .await
+        .unwrap()
+        .poll_interval(Duration::from_secs(10))
+        .timeout(Duration::from_millis(100))
+        .await;
+
+    match result {
+        Err(GraphFailure::AsyncOperation(AsyncOperationError::TimedOut(timeout))) => {
+            assert_eq!(Duration::from_millis(100), timeout);
+        }
+        _ => panic!("expected AsyncOperationError::TimedOut"),
+    }
+}
+
+#[tokio::test]
+async fn long_running_operation_cancelled() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+    let file_id = upload_file(&client).await;
+
+    let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+    let operation = client
+        .me()
+        .drive()
+        .item(&file_id)
+        .copy(&serde_json::json!({ "name": "copy.txt" }))
+        .send_long_running()
+        .await
+        .unwrap()
+        .poll_interval(Duration::from_secs(10))
+        .cancel_on(async move {
+            let _ = receiver.await;
+        });
+    sender.send(()).unwrap();
+
+    match operation.await {
+        Err(GraphFailure::AsyncOperation(AsyncOperationError::Cancelled)) => {}
+        _ => panic!("expected AsyncOperationError::Cancelled"),
+    }
+}
+
+#[tokio::test]
+async fn missing_monitor_url() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let result = client.me().get_user().send_long_running().await;
+    match result {
+        Err(GraphFailure::AsyncOperation(AsyncOperationError::MissingMonitorUrl)) => {}
+        _ => panic!("expected AsyncOperationError::MissingMonitorUrl"),
+    }
+}
@@ -0,0 +1,483 @@
+use futures::StreamExt;
+use graph_rs_sdk::http::{AsyncIterator, ResponseExt};
+use graph_rs_sdk::identity::ConfidentialClientApplication;
+use graph_rs_sdk::*;
+use std::time::Duration;
+use test_tools::mock_server::MockGraphServer;
+
+static CLAIMS: &str = r#"{"access_token":{"nbf":{"essential":true,"value":"1604106651"}}}"#;
+
+#[tokio::test]
+async fn user_crud() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client
+        .users()
+        .create_user(&serde_json::json!({
+            "displayName": "Adele Vance",
+            "accountEnabled": true
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(201, response.status().as_u16());
+    let user: serde_json::Value = response.json().await.unwrap();
+    let id = user["id"].as_str().unwrap();
+
+    let response = client
+        .user(id)
+        .update_user(&serde_json::json!({ "displayName": "Adele" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(204, response.status().as_u16());
+    assert_eq!(
+        Some("Adele"),
+        server.user(id).unwrap()["displayName"].as_str()
+    );
+
+    let response = client.user(id).get_user().send().await.unwrap();
+    let user: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(Some("Adele"), user["displayName"].as_str());
+
+    let response = client.user(id).delete_user().send().await.unwrap();
+    assert_eq!(204, response.status().as_u16());
+
+    let response = client.user(id).get_user().send().await.unwrap();
+    assert_eq!(404, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn me_is_the_default_user() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client.me().get_user().send().await.unwrap();
+    let user: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(Some(server.me_id().as_str()), user["id"].as_str());
+}
+
+#[tokio::test]
+async fn requests_without_access_token_are_unauthorized() {
+    let server = MockGraphServer::start().await;
+    let response = reqwest::get(format!("{}/users", server.endpoint()))
+        .await
+        .unwrap();
+    assert_eq!(401, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn list_users_paging() {
+    let server = MockGraphServer::start().await;
+    for i in 0..4 {
+        server.insert_user(serde_json::json!({ "displayName": format!("User {i}") }));
+    }
+
+    let client = server.graph_client();
+    let deque = client
+        .users()
+        .list_user()
+        .top("2")
+        .select(&["displayName"])
+        .paging()
+        .json::<serde_json::Value>()
+        .await
+        .unwrap();
+    assert_eq!(3, deque.len());
+
+    let users: Vec<serde_json::Value> = deque
+        .into_iter()
+        .flat_map(|response| {
+            response.into_body().unwrap()["value"]
+                .as_array()
+                .cloned()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(5, users.len());
+    assert!(users.iter().all(|user| user.get("mail").is_none()));
+}
+
+#[tokio::test]
+async fn users_delta() {
+    let server = MockGraphServer::start().await;
+    let removed = server.insert_user(serde_json::json!({ "displayName": "Removed" }));
+    let client = server.graph_client();
+
+    let deque = client
+        .users()
+        .delta()
+        .paging()
+        .json::<serde_json::Value>()
+        .await
+        .unwrap();
+    let body = deque.back().unwrap().body().as_ref().unwrap().clone();
+    assert_eq!(2, body["value"].as_array().unwrap().len());
+    let delta_link = body["@odata.deltaLink"].as_str().unwrap().to_string();
+
+    let added = server.insert_user(serde_json::json!({ "displayName": "Added" }));
+    client
+        .user(removed["id"].as_str().unwrap())
+        .delete_user()
+        .send()
+        .await
+        .unwrap();
+
+    let response = reqwest::Client::new()
+        .get(delta_link)
+        .bearer_auth("token")
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    let changes = body["value"].as_array().unwrap();
+    assert_eq!(2, changes.len());
+    assert_eq!(added["id"], changes[0]["id"]);
+    assert_eq!(removed["id"], changes[1]["id"]);
+    assert!(changes[1].get("@removed").is_some());
+    assert!(body.get("@odata.deltaLink").is_some());
+}
+
+#[tokio::test]
+async fn drive_items() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client
+        .me()
+        .drive()
+        .item_by_path(":/folder/file.txt:")
+        .update_items_content(reqwest::Body::from("file content"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(201, response.status().as_u16());
+    let file: serde_json::Value = response.json().await.unwrap();
+    let file_id = file["id"].as_str().unwrap();
+    assert_eq!(
+        Some(b"file content".to_vec()),
+        server.drive_item_content(file_id)
+    );
+
+    let response = client
+        .me()
+        .drive()
+        .item(file_id)
+        .get_items_content()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(b"file content", response.bytes().await.unwrap().as_ref());
+
+    let response = client
+        .me()
+        .drive()
+        .item_by_path(":/folder:")
+        .create_children(&serde_json::json!({
+            "name": "file.txt",
+            "file": {}
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(409, response.status().as_u16());
+
+    let response = client
+        .me()
+        .drive()
+        .item_by_path(":/folder:")
+        .list_children()
+        .send()
+        .await
+        .unwrap();
+    let children: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(1, children["value"].as_array().unwrap().len());
+
+    let folder_id = file["parentReference"]["id"].as_str().unwrap();
+    let response = client
+        .me()
+        .drive()
+        .item(folder_id)
+        .delete_items()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(204, response.status().as_u16());
+    assert!(server.drive_item(file_id).is_none());
+}
+
+#[tokio::test]
+async fn upload_session() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client
+        .me()
+        .drive()
+        .item_by_path(":/upload.txt:")
+        .create_upload_session(&serde_json::json!({
+            "item": { "@microsoft.graph.conflictBehavior": "fail" }
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let content: Vec<u8> = (0..1_000_000).map(|i| (i % 251) as u8).collect();
+    let mut upload_session = response
+        .into_upload_session(content.as_slice())
+        .await
+        .unwrap();
+
+    let mut last = None;
+    while let Some(result) = upload_session.next().await {
+        let response = result.unwrap();
+        assert!(response.status().is_success());
+        last = Some(response);
+    }
+
+    let response = last.unwrap();
+    assert_eq!(201, response.status().as_u16());
+    let file: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        Some(content),
+        server.drive_item_content(file["id"].as_str().unwrap())
+    );
+}
+
+#[tokio::test]
+async fn upload_session_range_checks() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client
+        .me()
+        .drive()
+        .item_by_path(":/upload.txt:")
+        .create_upload_session(&serde_json::json!({}))
+        .send()
+        .await
+        .unwrap();
+    let session: serde_json::Value = response.json().await.unwrap();
+    let upload_url = session["uploadUrl"].as_str().unwrap();
+
+    let http_client = reqwest::Client::new();
+    let response = http_client
+        .put(upload_url)
+        .header("Content-Range", "bytes 5-9/10")
+        .body(vec![0; 5])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(416, response.status().as_u16());
+
+    let response = http_client
+        .put(upload_url)
+        .header("Content-Range", "bytes 0-4/10")
+        .body(vec![0; 4])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(400, response.status().as_u16());
+
+    let response = http_client
+        .put(upload_url)
+        .header("Content-Range", "bytes 0-4/10")
+        .body(vec![0; 5])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(202, response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("5-9", body["nextExpectedRanges"][0]);
+}
+
+#[tokio::test]
+async fn batch() {
+    let server = MockGraphServer::start().await;
+    let user = server.insert_user(serde_json::json!({ "displayName": "Adele Vance" }));
+    let client = server.graph_client();
+
+    let response = client
+        .batch(&serde_json::json!({
+            "requests": [
+                {
+                    "id": "1",
+                    "method": "GET",
+                    "url": format!("/users/{}", user["id"].as_str().unwrap())
+                },
+                {
+                    "id": "2",
+                    "method": "POST",
+                    "url": "/groups",
+                    "headers": { "Content-Type": "application/json" },
+                    "body": { "displayName": "Group" }
+                },
+                {
+                    "id": "3",
+                    "method": "GET",
+                    "url": "/users/missing"
+                }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    let responses = body["responses"].as_array().unwrap();
+    assert_eq!(200, responses[0]["status"]);
+    assert_eq!("Adele Vance", responses[0]["body"]["displayName"]);
+    assert_eq!(201, responses[1]["status"]);
+    assert_eq!(404, responses[2]["status"]);
+    assert_eq!(1, server.groups().len());
+}
+
+#[tokio::test]
+async fn throttling() {
+    let server = MockGraphServer::start().await;
+    server.throttle(1, Duration::from_secs(1));
+
+    let client = server.graph_client();
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(429, response.status().as_u16());
+    assert_eq!(
+        Some("1"),
+        response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok())
+    );
+
+    server.throttle(2, Duration::from_secs(1));
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .wait_for_retry_after_headers(true),
+    );
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(4, server.received_requests().await.len());
+}
+
+#[tokio::test]
+async fn client_credentials_token() {
+    let server = MockGraphServer::start().await;
+    let config = server.client_configuration();
+
+    let confidential_client =
+        ConfidentialClientApplication::builder("6731de76-14a6-49ae-97bc-6eba6914391e")
+            .with_client_secret("secret")
+            .with_tenant("tenant-id")
+            .with_config(&config)
+            .build();
+    let client = server.graph_client_with(config.client_application(confidential_client));
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(1, server.tokens_issued());
+
+    let requests = server.received_requests().await;
+    assert_eq!("/tenant-id/oauth2/v2.0/token", requests[0].url.path());
+    assert_eq!(
+        Some("Bearer mock-access-token-1"),
+        requests[1]
+            .headers
+            .get(&"authorization".parse().unwrap())
+            .map(|value| value.last().as_str())
+    );
+}
+
+fn token_request_claims(requests: &[wiremock::Request]) -> Vec<Option<serde_json::Value>> {
+    requests
+        .iter()
+        .filter(|request| request.url.path().ends_with("/oauth2/v2.0/token"))
+        .map(|request| {
+            url::form_urlencoded::parse(&request.body)
+                .find(|(key, _)| key == "claims")
+                .and_then(|(_, claims)| serde_json::from_str(&claims).ok())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn claims_challenge() {
+    let server = MockGraphServer::start().await;
+    for i in 0..4 {
+        server.insert_user(serde_json::json!({ "displayName": format!("User {i}") }));
+    }
+    let config = server.client_configuration();
+    let confidential_client =
+        ConfidentialClientApplication::builder("6731de76-14a6-49ae-97bc-6eba6914391e")
+            .with_client_secret("secret")
+            .with_tenant("tenant-id")
+            .with_config(&config)
+            .build();
+    let client = server.graph_client_with(config.client_application(confidential_client));
+
+    server.claims_challenge(1, CLAIMS);
+    let response = client.me().get_user().send_checked().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(2, server.tokens_issued());
+
+    let mut stream = client
+        .users()
+        .list_user()
+        .top("2")
+        .paging()
+        .stream::<serde_json::Value>()
+        .unwrap();
+    let response = stream.next().await.unwrap().unwrap();
+    assert_eq!(200, response.status().as_u16());
+
+    server.claims_challenge(1, CLAIMS);
+    let mut pages = 1;
+    while let Some(result) = stream.next().await {
+        assert_eq!(200, result.unwrap().status().as_u16());
+        pages += 1;
+    }
+    assert_eq!(3, pages);
+    assert_eq!(3, server.tokens_issued());
+
+    let claims = token_request_claims(&server.received_requests().await);
+    assert_eq!(3, claims.len());
+    for claims in claims.iter().skip(1) {
+        assert_eq!(
+            Some("1604106651"),
+            claims.as_ref().unwrap()["access_token"]["nbf"]["value"].as_str()
+        );
+    }
+}
+
+#[tokio::test]
+async fn messages() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client
+        .me()
+        .messages()
+        .create_messages(&serde_json::json!({
+            "subject": "Hello",
+            "isRead": false
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(201, response.status().as_u16());
+    let message: serde_json::Value = response.json().await.unwrap();
+
+    let response = client
+        .me()
+        .message(message["id"].as_str().unwrap())
+        .update_messages(&serde_json::json!({ "isRead": true }))
+        .send()
+        .await
+        .unwrap();
+    let message: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(true, message["isRead"]);
+    assert_eq!(1, server.messages(&server.me_id()).len());
+}
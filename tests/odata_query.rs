@@ -1,3 +1,5 @@
+use graph_rs_sdk::header::HeaderValue;
+use graph_rs_sdk::odata::{Expand, Filter, OrderBy};
 use graph_rs_sdk::*;
 use test_tools::oauth_request::OAuthTestClient;
 
@@ -67,6 +69,252 @@ fn expand_filter_query() {
     );
 }
 
+#[test]
+fn multiple_filter_query() {
+    let client = Graph::new("");
+
+    let url = client
+        .users()
+        .list_user()
+        .filter(&[
+            "userType eq 'Member'",
+            "accountEnabled eq true or city eq 'Seattle'",
+        ])
+        .url();
+
+    assert_eq!(
+        Some("(userType eq 'Member') and (accountEnabled eq true or city eq 'Seattle')".into()),
+        url.query_pairs()
+            .find(|(key, _)| key.eq("$filter"))
+            .map(|(_, value)| value.to_string())
+    );
+}
+
+#[test]
+fn typed_filter_query() {
+    let client = Graph::new("");
+
+    let mut request = client.users().list_user().filter_by(
+        Filter::eq("userType", "Member").and(Filter::starts_with("displayName", "O'Neil")),
+    );
+
+    assert_eq!(
+        Some("userType eq 'Member' and startswith(displayName,'O''Neil')".into()),
+        request
+            .url()
+            .query_pairs()
+            .find(|(key, _)| key.eq("$filter"))
+            .map(|(_, value)| value.to_string())
+    );
+    assert!(request.headers_mut().get("ConsistencyLevel").is_none());
+}
+
+#[test]
+fn advanced_query_consistency_level() {
+    let client = Graph::new("");
+
+    let mut request = client
+        .users()
+        .list_user()
+        .filter_by(Filter::ends_with("mail", "@contoso.com"))
+        .order_by(&[OrderBy::asc("userPrincipalName")]);
+    assert_eq!(
+        Some(&HeaderValue::from_static("eventual")),
+        request.headers_mut().get("ConsistencyLevel")
+    );
+
+    let mut request = client.groups().list_group().count("true");
+    assert_eq!(
+        Some(&HeaderValue::from_static("eventual")),
+        request.headers_mut().get("ConsistencyLevel")
+    );
+}
+
+#[test]
+fn consistency_level_only_for_directory_objects() {
+    let client = Graph::new("");
+
+    let mut request = client.sites().list_site().search("contoso").count("true");
+    assert!(request.headers_mut().get("ConsistencyLevel").is_none());
+
+    let mut request = client
+        .drives()
+        .list_drive()
+        .filter_by(Filter::ends_with("name", "docs"));
+    assert!(request.headers_mut().get("ConsistencyLevel").is_none());
+
+    let mut request = client.users().list_user().search("\"displayName:adele\"");
+    assert_eq!(
+        Some(&HeaderValue::from_static("eventual")),
+        request.headers_mut().get("ConsistencyLevel")
+    );
+}
+
+#[tokio::test]
+async fn advanced_query_adds_consistency_level_before_send() {
+    let client = Graph::new("token");
+
+    let request = client
+        .users()
+        .list_user()
+        .filter(&["endswith(mail,'@contoso.com')"])
+        .build()
+        .await
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        Some(&HeaderValue::from_static("eventual")),
+        request.headers().get("ConsistencyLevel")
+    );
+    assert!(request
+        .url()
+        .query_pairs()
+        .any(|(key, value)| key.eq("$count") && value.eq("true")));
+}
+
+#[tokio::test]
+async fn advanced_query_typed_filter_before_send() {
+    let client = Graph::new("token");
+
+    let request = client
+        .users()
+        .list_user()
+        .filter_by(Filter::ends_with("mail", "@contoso.com"))
+        .build()
+        .await
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(
+        Some(&HeaderValue::from_static("eventual")),
+        request.headers().get("ConsistencyLevel")
+    );
+    assert!(request
+        .url()
+        .query_pairs()
+        .any(|(key, value)| key.eq("$count") && value.eq("true")));
+
+    let request = client
+        .users()
+        .list_user()
+        .filter_by(Filter::eq("displayName", "not ne endswith"))
+        .build()
+        .await
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(request.headers().get("ConsistencyLevel").is_none());
+    assert!(!request.url().query_pairs().any(|(key, _)| key.eq("$count")));
+}
+
+#[tokio::test]
+async fn advanced_query_strict_mode_preflight_error() {
+    let client = Graph::from(
+        GraphClientConfiguration::new()
+            .access_token("token")
+            .strict_advanced_queries(true),
+    );
+
+    let result = client
+        .groups()
+        .list_group()
+        .filter(&["not(groupTypes/any(c:c eq 'Unified'))"])
+        .build()
+        .await;
+    assert!(matches!(result, Err(GraphFailure::PreFlightError { .. })));
+
+    let result = client
+        .groups()
+        .list_group()
+        .filter(&["startswith(displayName,'a')"])
+        .build()
+        .await;
+    assert!(result.is_ok());
+
+    let result = client
+        .users()
+        .list_user()
+        .search("\"displayName:adele\"")
+        .count("true")
+        .build()
+        .await;
+    assert!(matches!(result, Err(GraphFailure::PreFlightError { .. })));
+
+    let result = client
+        .users()
+        .list_user()
+        .search("\"displayName:adele\"")
+        .count("true")
+        .consistency_level_eventual()
+        .build()
+        .await;
+    assert!(result.is_ok());
+}
+
+#[test]
+fn typed_order_by_and_expand_query() {
+    let client = Graph::new("");
+
+    let url = client
+        .groups()
+        .list_group()
+        .order_by(&[
+            OrderBy::asc("displayName"),
+            OrderBy::desc("createdDateTime"),
+        ])
+        .expand(&[Expand::new("members").select(&["id", "displayName"])])
+        .url();
+
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                "$orderby".to_string(),
+                "displayName asc,createdDateTime desc".to_string()
+            ),
+            (
+                "$expand".to_string(),
+                "members($select=id,displayName)".to_string()
+            ),
+        ],
+        query
+    );
+}
+
+#[test]
+fn order_by_direction_values() {
+    let client = Graph::new("");
+
+    let url = client
+        .users()
+        .list_user()
+        .order_by(&["displayName", "desc"])
+        .url();
+    assert_eq!(
+        Some("displayName desc".to_string()),
+        url.query_pairs()
+            .find(|(key, _)| key == "$orderby")
+            .map(|(_, value)| value.to_string())
+    );
+
+    let url = client
+        .users()
+        .list_user()
+        .order_by(&["displayName desc", "createdDateTime", "ASC"])
+        .url();
+    assert_eq!(
+        Some("displayName desc,createdDateTime ASC".to_string()),
+        url.query_pairs()
+            .find(|(key, _)| key == "$orderby")
+            .map(|(_, value)| value.to_string())
+    );
+}
+
 async fn filter_request(client: &Graph) -> GraphResult<reqwest::Response> {
     client
         .users()
@@ -1,18 +1,16 @@
 use futures::StreamExt;
+use graph_rs_sdk::*;
 use std::collections::VecDeque;
-use test_tools::oauth_request::{Environment, DEFAULT_CLIENT_CREDENTIALS_MUTEX4};
+use test_tools::vcr::VcrTestClient;
 
 #[tokio::test]
 async fn paging_all() {
-    if Environment::is_appveyor() {
-        return;
-    }
-
-    let test_client = DEFAULT_CLIENT_CREDENTIALS_MUTEX4.lock().await;
+    let test_client = VcrTestClient::new("paging_all").unwrap();
     let mut vec = test_client
         .client
         .users()
         .delta()
+        .top("2")
         .paging()
         .json::<serde_json::Value>()
         .await
@@ -30,35 +28,34 @@ async fn paging_all() {
 
 #[tokio::test]
 async fn paging_stream() {
-    if Environment::is_local() {
-        let test_client = DEFAULT_CLIENT_CREDENTIALS_MUTEX4.lock().await;
-        let mut stream = test_client
-            .client
-            .users()
-            .delta()
-            .paging()
-            .stream::<serde_json::Value>()
-            .unwrap();
+    let test_client = VcrTestClient::new("paging_stream").unwrap();
+    let mut stream = test_client
+        .client
+        .users()
+        .delta()
+        .top("2")
+        .paging()
+        .stream::<serde_json::Value>()
+        .unwrap();
 
-        let mut deque = VecDeque::new();
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(response) => {
-                    assert!(response.status().is_success());
-                    let body = response.into_body().unwrap();
-                    deque.push_back(body);
-                }
-                Err(err) => panic!("Error on stream users delta\n{err:#?}"),
+    let mut deque = VecDeque::new();
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(response) => {
+                assert!(response.status().is_success());
+                let body = response.into_body().unwrap();
+                deque.push_back(body);
             }
+            Err(err) => panic!("Error on stream users delta\n{err:#?}"),
         }
+    }
 
-        assert!(deque.len() >= 2);
-        let last = deque.pop_back().unwrap();
-        assert!(last["@odata.deltaLink"].as_str().is_some());
+    assert!(deque.len() >= 2);
+    let last = deque.pop_back().unwrap();
+    assert!(last["@odata.deltaLink"].as_str().is_some());
 
-        for body in deque.iter() {
-            assert!(body["@odata.nextLink"].as_str().is_some());
-            assert!(body["@odata.deltaLink"].as_str().is_none());
-        }
+    for body in deque.iter() {
+        assert!(body["@odata.nextLink"].as_str().is_some());
+        assert!(body["@odata.deltaLink"].as_str().is_none());
     }
 }
@@ -0,0 +1,71 @@
+use graph_rs_sdk::rate_limit::{RateLimit, RateLimitService, RateLimiter};
+use std::time::{Duration, Instant};
+use test_tools::mock_server::MockGraphServer;
+
+#[tokio::test]
+async fn rate_limit_spaces_requests() {
+    let server = MockGraphServer::start().await;
+    let rate_limiter = RateLimiter::new().limit(
+        RateLimitService::Identity,
+        RateLimit::new(1, Duration::from_millis(300)),
+    );
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .rate_limiter(rate_limiter),
+    );
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        let response = client.me().get_user().send().await.unwrap();
+        assert_eq!(200, response.status().as_u16());
+    }
+    assert!(start.elapsed() >= Duration::from_millis(550));
+}
+
+#[tokio::test]
+async fn rate_limit_waits_after_throttled_response() {
+    let server = MockGraphServer::start().await;
+    server.throttle(1, Duration::from_secs(1));
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .rate_limiter(RateLimiter::new()),
+    );
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(429, response.status().as_u16());
+
+    let start = Instant::now();
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert!(start.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn rate_limit_buckets_are_per_tenant() {
+    let server = MockGraphServer::start().await;
+    server.throttle(1, Duration::from_secs(5));
+    let rate_limiter = RateLimiter::new();
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .rate_limiter(rate_limiter.for_tenant("tenant-a")),
+    );
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(429, response.status().as_u16());
+
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .rate_limiter(rate_limiter.for_tenant("tenant-b")),
+    );
+    let start = Instant::now();
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
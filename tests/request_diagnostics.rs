@@ -0,0 +1,109 @@
+use graph_rs_sdk::error::{GraphFailure, ResponseDiagnostics};
+use graph_rs_sdk::http::ResponseExt;
+use std::time::Duration;
+use test_tools::mock_server::MockGraphServer;
+
+fn client_request_ids(requests: &[wiremock::Request]) -> Vec<String> {
+    requests
+        .iter()
+        .filter_map(|request| {
+            request
+                .headers
+                .get(&"client-request-id".parse().unwrap())
+                .map(|value| value.last().as_str().to_string())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn client_request_id_is_generated_per_request() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client.me().get_user().send().await.unwrap();
+    let diagnostics = response.diagnostics();
+    let client_request_id = diagnostics.client_request_id.unwrap();
+    assert_eq!(36, client_request_id.len());
+    assert!(diagnostics.request_id.is_some());
+
+    client.me().get_user().send().await.unwrap();
+    let ids = client_request_ids(&server.received_requests().await);
+    assert_eq!(2, ids.len());
+    assert_eq!(client_request_id, ids[0]);
+    assert_ne!(ids[0], ids[1]);
+}
+
+#[tokio::test]
+async fn client_request_id_is_reused_across_retries() {
+    let server = MockGraphServer::start().await;
+    server.throttle(1, Duration::from_secs(0));
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .wait_for_retry_after_headers(true),
+    );
+
+    let response = client
+        .me()
+        .get_user()
+        .client_request_id("0b1c8a52-8f4f-4e0e-9a55-3b8a4fd0f2a1")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        Some("0b1c8a52-8f4f-4e0e-9a55-3b8a4fd0f2a1"),
+        response.diagnostics().client_request_id.as_deref()
+    );
+    assert_eq!(
+        vec!["0b1c8a52-8f4f-4e0e-9a55-3b8a4fd0f2a1"; 2],
+        client_request_ids(&server.received_requests().await)
+    );
+}
+
+#[tokio::test]
+async fn error_message_diagnostics() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client
+        .user("missing-user")
+        .get_user()
+        .client_request_id("client-request-id")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(404, response.status().as_u16());
+    let request_id = response.diagnostics().request_id;
+    assert!(request_id.is_some());
+
+    let error_message = response.into_graph_error_message().await.unwrap();
+    let failure = GraphFailure::from(error_message);
+    let diagnostics = failure.diagnostics().unwrap();
+    assert_eq!(request_id, diagnostics.request_id);
+    assert_eq!(
+        Some("client-request-id"),
+        diagnostics.client_request_id.as_deref()
+    );
+}
+
+#[tokio::test]
+async fn paging_error_message_diagnostics() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let mut responses = client
+        .user("missing-user")
+        .get_user()
+        .paging()
+        .json::<Vec<serde_json::Value>>()
+        .await
+        .unwrap();
+    let response = responses.pop_front().unwrap();
+    let error_message = response.body().as_ref().unwrap_err();
+    assert_eq!(
+        ResponseDiagnostics::from(response.headers()),
+        error_message.diagnostics().unwrap()
+    );
+}
@@ -0,0 +1,115 @@
+use graph_rs_sdk::cache::InMemoryCache;
+use graph_rs_sdk::error::GraphFailure;
+use std::sync::Arc;
+use test_tools::mock_server::MockGraphServer;
+
+#[tokio::test]
+async fn response_cache_revalidates_with_if_none_match() {
+    let server = MockGraphServer::start().await;
+    let cache = Arc::new(InMemoryCache::new());
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .response_cache(cache.clone()),
+    );
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+    let user: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(1, cache.len());
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(user, response.json::<serde_json::Value>().await.unwrap());
+
+    let requests = server.received_requests().await;
+    let last_request = requests.last().unwrap();
+    let if_none_match = last_request
+        .headers
+        .get(&"if-none-match".parse().unwrap())
+        .map(|value| value.last().as_str().to_string());
+    assert_eq!(Some(etag), if_none_match);
+}
+
+#[tokio::test]
+async fn response_cache_returns_updated_resource() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .response_cache(InMemoryCache::new()),
+    );
+    let user = server.insert_user(serde_json::json!({ "displayName": "Adele Vance" }));
+    let id = user["id"].as_str().unwrap();
+
+    let response = client.user(id).get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+
+    let response = client
+        .user(id)
+        .update_user(&serde_json::json!({ "displayName": "Adele" }))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let response = client.user(id).get_user().send().await.unwrap();
+    let user: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("Adele", user["displayName"].as_str().unwrap());
+}
+
+#[tokio::test]
+async fn if_none_match_not_modified() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+
+    let response = client.me().get_user().send().await.unwrap();
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+
+    let response = client
+        .me()
+        .get_user()
+        .if_none_match(&etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(304, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn if_match_precondition_failed() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+    let user = server.insert_user(serde_json::json!({ "displayName": "Adele Vance" }));
+    let id = user["id"].as_str().unwrap();
+
+    let response = client.user(id).get_user().send().await.unwrap();
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+
+    let response = client
+        .user(id)
+        .update_user(&serde_json::json!({ "displayName": "Adele" }))
+        .if_match(&etag)
+        .send_checked()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let result = client
+        .user(id)
+        .delete_user()
+        .if_match(&etag)
+        .send_checked()
+        .await;
+    match result {
+        Err(GraphFailure::PreconditionFailed(error)) => {
+            assert_eq!(412, error.status().as_u16());
+            assert!(error.is_precondition_failed());
+        }
+        _ => panic!("expected GraphFailure::PreconditionFailed"),
+    }
+    assert!(server.user(id).is_some());
+}
@@ -0,0 +1,19 @@
+use graph_rs_sdk::*;
+
+#[test]
+fn subscribed_skus_url() {
+    let client = Graph::new("");
+
+    assert_eq!(
+        "/v1.0/subscribedSkus".to_string(),
+        client.subscribed_skus().list_subscribed_sku().url().path()
+    );
+    assert_eq!(
+        "/v1.0/subscribedSkus/sku-id".to_string(),
+        client
+            .subscribed_sku("sku-id")
+            .get_subscribed_sku()
+            .url()
+            .path()
+    );
+}
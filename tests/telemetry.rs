@@ -0,0 +1,217 @@
+use graph_rs_sdk::header::{HeaderName, HeaderValue};
+#[cfg(feature = "metrics")]
+use graph_rs_sdk::telemetry::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "metrics")]
+use std::time::Duration;
+use test_tools::mock_server::MockGraphServer;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+type Fields = HashMap<String, String>;
+
+struct SpanFields<'a>(&'a mut Fields);
+
+impl Visit for SpanFields<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+/// Collects the fields of `graph.request` spans.
+#[derive(Clone, Default)]
+struct SpanCollector {
+    spans: Arc<Mutex<Vec<Fields>>>,
+}
+
+impl Subscriber for SpanCollector {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.spans.lock().unwrap();
+        let mut fields = Fields::new();
+        fields.insert("name".into(), span.metadata().name().into());
+        span.record(&mut SpanFields(&mut fields));
+        spans.push(fields);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let fields = &mut spans[span.into_u64() as usize - 1];
+        values.record(&mut SpanFields(fields));
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+impl SpanCollector {
+    fn graph_requests(&self) -> Vec<Fields> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|fields| fields["name"] == "graph.request")
+            .cloned()
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn telemetry_span_records_request() {
+    let collector = SpanCollector::default();
+    let _guard = tracing::subscriber::set_default(collector.clone());
+
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .telemetry(true),
+    );
+
+    let response = client
+        .user(server.me_id())
+        .get_user()
+        .header(
+            HeaderName::from_static("client-request-id"),
+            HeaderValue::from_static("b9e9f1c4-0d6c-4b8e-8d5e-6f0e3d2a7c11"),
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+
+    let spans = collector.graph_requests();
+    assert_eq!(1, spans.len());
+    let span = &spans[0];
+    assert_eq!("GET", span["http.request.method"]);
+    assert_eq!("/users/{{RID}}", span["url.template"]);
+    assert_eq!("GET /users/{{RID}}", span["otel.name"]);
+    assert_eq!("users", span["graph.resource_identity"]);
+    assert_eq!("200", span["http.response.status_code"]);
+    assert_eq!(
+        response.headers()["request-id"].to_str().unwrap(),
+        span["graph.request_id"]
+    );
+    assert_eq!(
+        "b9e9f1c4-0d6c-4b8e-8d5e-6f0e3d2a7c11",
+        span["graph.client_request_id"]
+    );
+    assert_eq!("0", span["graph.retry_count"]);
+    assert_eq!("0", span["graph.throttle_wait_ms"]);
+    assert!(span.contains_key("graph.latency_ms"));
+}
+
+#[tokio::test]
+async fn telemetry_disabled_by_default() {
+    let collector = SpanCollector::default();
+    let _guard = tracing::subscriber::set_default(collector.clone());
+
+    let server = MockGraphServer::start().await;
+    let response = server.graph_client().me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert!(collector.graph_requests().is_empty());
+}
+
+#[cfg(feature = "metrics")]
+type Metrics = Vec<(
+    metrics_util::CompositeKey,
+    Option<metrics::Unit>,
+    Option<metrics::SharedString>,
+    metrics_util::debugging::DebugValue,
+)>;
+
+/// Returns the value and labels of the metric with the given name.
+#[cfg(feature = "metrics")]
+fn metric(metrics: &Metrics, name: &str) -> Option<(f64, Vec<(String, String)>)> {
+    use metrics_util::debugging::DebugValue;
+
+    metrics
+        .iter()
+        .find(|(key, ..)| key.key().name() == name)
+        .map(|(key, _, _, value)| {
+            let value = match value {
+                DebugValue::Counter(value) => *value as f64,
+                DebugValue::Gauge(value) => value.into_inner(),
+                DebugValue::Histogram(values) => values.iter().map(|v| v.into_inner()).sum(),
+            };
+            let labels = key
+                .key()
+                .labels()
+                .map(|label| (label.key().to_string(), label.value().to_string()))
+                .collect();
+            (value, labels)
+        })
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test(flavor = "current_thread")]
+async fn metrics_record_throttled_requests() {
+    let recorder = metrics_util::debugging::DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let _guard = metrics::set_default_local_recorder(&recorder);
+
+    let server = MockGraphServer::start().await;
+    let message = server.insert_message(&server.me_id(), serde_json::json!({ "subject": "Hello" }));
+    server.throttle(1, Duration::from_secs(1));
+
+    let client = server.graph_client_with(
+        server
+            .client_configuration()
+            .access_token("token")
+            .wait_for_retry_after_headers(true)
+            .metrics(true),
+    );
+
+    let response = client
+        .user(server.me_id())
+        .message(message["id"].as_str().unwrap())
+        .get_messages()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+
+    let labels: Vec<(String, String)> = [
+        ("method", "GET"),
+        ("path_template", "/users/{{RID}}/messages/{{RID}}"),
+        ("resource", "messages"),
+        ("status", "200"),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value.to_string()))
+    .collect();
+    let metrics = snapshotter.snapshot().into_vec();
+    let (requests, request_labels) = metric(&metrics, GRAPH_REQUESTS_TOTAL).unwrap();
+    assert_eq!(1.0, requests);
+    assert_eq!(labels, request_labels);
+    assert_eq!(
+        1.0,
+        metric(&metrics, GRAPH_REQUEST_RETRIES_TOTAL).unwrap().0
+    );
+    assert_eq!(
+        1.0,
+        metric(&metrics, GRAPH_THROTTLED_RESPONSES_TOTAL).unwrap().0
+    );
+    assert_eq!(
+        1.0,
+        metric(&metrics, GRAPH_THROTTLE_WAIT_SECONDS).unwrap().0
+    );
+    assert!(metric(&metrics, GRAPH_REQUEST_DURATION_SECONDS).unwrap().0 >= 1.0);
+}
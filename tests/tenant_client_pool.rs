@@ -0,0 +1,179 @@
+use graph_rs_sdk::http::Url;
+use graph_rs_sdk::identity::{ClientCredentialAdminConsentResponse, ConfidentialClientApplication};
+use graph_rs_sdk::TenantClientPool;
+use std::time::Duration;
+use test_tools::mock_server::MockGraphServer;
+
+const CLIENT_ID: &str = "6731de76-14a6-49ae-97bc-6eba6914391e";
+
+fn bearer_tokens(requests: &[wiremock::Request]) -> Vec<String> {
+    requests
+        .iter()
+        .filter_map(|request| {
+            request
+                .headers
+                .get(&"authorization".parse().unwrap())
+                .map(|value| value.last().as_str().to_string())
+        })
+        .filter(|value| value.starts_with("Bearer"))
+        .collect()
+}
+
+#[tokio::test]
+async fn client_per_tenant() {
+    let server = MockGraphServer::start().await;
+    let template = ConfidentialClientApplication::builder(CLIENT_ID).with_client_secret("secret");
+    let pool = TenantClientPool::new(template).config(server.client_configuration());
+
+    let response = pool
+        .client("tenant-a")
+        .me()
+        .get_user()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+    let response = pool
+        .client("tenant-b")
+        .me()
+        .get_user()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+    let response = pool
+        .client("tenant-a")
+        .me()
+        .get_user()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+
+    assert_eq!(2, pool.len());
+    assert!(pool.contains_tenant("tenant-a"));
+    assert!(pool.contains_tenant("tenant-b"));
+    assert_eq!(2, server.tokens_issued());
+
+    let requests = server.received_requests().await;
+    assert_eq!("/tenant-a/oauth2/v2.0/token", requests[0].url.path());
+    assert_eq!("/tenant-b/oauth2/v2.0/token", requests[2].url.path());
+    assert_eq!(
+        vec![
+            "Bearer mock-access-token-1",
+            "Bearer mock-access-token-2",
+            "Bearer mock-access-token-1"
+        ],
+        bearer_tokens(&requests)
+    );
+}
+
+#[tokio::test]
+async fn evict_idle_tenants() {
+    let server = MockGraphServer::start().await;
+    let template = ConfidentialClientApplication::builder(CLIENT_ID).with_client_secret("secret");
+    let pool = TenantClientPool::new(template)
+        .config(server.client_configuration())
+        .idle_timeout(Duration::from_millis(50));
+
+    let response = pool
+        .client("tenant-a")
+        .me()
+        .get_user()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(0, pool.evict_idle());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(1, pool.evict_idle());
+    assert!(pool.is_empty());
+
+    // The cached token of the tenant is removed with its client.
+    let response = pool
+        .client("tenant-a")
+        .me()
+        .get_user()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(2, server.tokens_issued());
+}
+
+#[tokio::test]
+async fn requests_keep_tenant_from_idle_eviction() {
+    let server = MockGraphServer::start().await;
+    let template = ConfidentialClientApplication::builder(CLIENT_ID).with_client_secret("secret");
+    let pool = TenantClientPool::new(template)
+        .config(server.client_configuration())
+        .idle_timeout(Duration::from_millis(150));
+
+    let client = pool.client("tenant-a");
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(75)).await;
+        let response = client.me().get_user().send().await.unwrap();
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!(0, pool.evict_idle());
+    }
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(1, pool.evict_idle());
+}
+
+#[tokio::test]
+async fn max_concurrent_requests_per_tenant() {
+    let server = MockGraphServer::start().await;
+    let template = ConfidentialClientApplication::builder(CLIENT_ID).with_client_secret("secret");
+    let pool = TenantClientPool::new(template)
+        .config(server.client_configuration())
+        .max_concurrent_requests_per_tenant(1);
+
+    let client = pool.client("tenant-a");
+    let (first, second) = tokio::join!(
+        client.me().get_user().send(),
+        pool.client("tenant-a").me().get_user().send()
+    );
+    assert_eq!(200, first.unwrap().status().as_u16());
+    assert_eq!(200, second.unwrap().status().as_u16());
+}
+
+#[test]
+fn admin_consent() {
+    let template = ConfidentialClientApplication::builder(CLIENT_ID).with_client_secret("secret");
+    let pool = TenantClientPool::new(template);
+
+    let url = pool
+        .admin_consent_url(
+            "organizations",
+            Url::parse("http://localhost:8000/redirect").unwrap(),
+            Some("state"),
+        )
+        .unwrap();
+    assert_eq!(
+        "https://login.microsoftonline.com/organizations/adminconsent",
+        format!("{}{}", url.origin().ascii_serialization(), url.path())
+    );
+    let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    assert!(query.contains(&("client_id".into(), CLIENT_ID.into())));
+    assert!(query.contains(&("state".into(), "state".into())));
+    assert!(query.contains(&(
+        "redirect_uri".into(),
+        "http://localhost:8000/redirect".into()
+    )));
+
+    let response = ClientCredentialAdminConsentResponse {
+        admin_consent: false,
+        tenant: "tenant-a".into(),
+    };
+    assert!(pool.connect_admin_consent(&response).is_err());
+    assert!(pool.is_empty());
+
+    let response = ClientCredentialAdminConsentResponse {
+        admin_consent: true,
+        tenant: "tenant-a".into(),
+    };
+    assert!(pool.connect_admin_consent(&response).is_ok());
+    assert!(pool.contains_tenant("tenant-a"));
+}
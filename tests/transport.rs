@@ -0,0 +1,132 @@
+use graph_rs_sdk::identity::ConfidentialClientApplication;
+use graph_rs_sdk::*;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+type Requests = Arc<Mutex<Vec<::http::Request<reqwest::Body>>>>;
+
+/// A transport that records each request and responds with a json body for the path.
+fn test_transport(
+    requests: Requests,
+) -> impl tower::Service<
+    ::http::Request<reqwest::Body>,
+    Response = ::http::Response<String>,
+    Error = Infallible,
+    Future = impl Send,
+> + Clone {
+    tower::service_fn(move |request: ::http::Request<reqwest::Body>| {
+        let body = match request.uri().path() {
+            path if path.ends_with("/oauth2/v2.0/token") => serde_json::json!({
+                "token_type": "Bearer",
+                "expires_in": 3599,
+                "access_token": "transport-token"
+            }),
+            "/v1.0/users" if request.uri().query().is_none() => serde_json::json!({
+                "value": [{ "id": "1" }],
+                "@odata.nextLink": "https://graph.microsoft.com/v1.0/users?$skiptoken=1"
+            }),
+            "/v1.0/users" => serde_json::json!({ "value": [{ "id": "2" }] }),
+            _ => serde_json::json!({ "id": "me" }),
+        };
+        requests.lock().unwrap().push(request);
+
+        async move {
+            Ok::<_, Infallible>(
+                ::http::Response::builder()
+                    .status(200)
+                    .header("content-type", "application/json")
+                    .body(body.to_string())
+                    .unwrap(),
+            )
+        }
+    })
+}
+
+#[tokio::test]
+async fn requests_use_transport() {
+    let requests = Requests::default();
+    let client = GraphClient::from(
+        GraphClientConfiguration::new()
+            .access_token("ACCESS_TOKEN")
+            .transport(test_transport(requests.clone())),
+    );
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(
+        "https://graph.microsoft.com/v1.0/me",
+        response.url().as_str()
+    );
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!("me", body["id"]);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(1, requests.len());
+    assert_eq!(
+        "Bearer ACCESS_TOKEN",
+        requests[0].headers().get("authorization").unwrap()
+    );
+    // Default headers of the configuration are added by the client.
+    assert_eq!("*/*", requests[0].headers().get("accept").unwrap());
+}
+
+#[tokio::test]
+async fn paging_uses_transport() {
+    let requests = Requests::default();
+    let client = GraphClient::from(
+        GraphClientConfiguration::new()
+            .access_token("ACCESS_TOKEN")
+            .transport(test_transport(requests.clone())),
+    );
+
+    let deque = client
+        .users()
+        .list_user()
+        .paging()
+        .json::<serde_json::Value>()
+        .await
+        .unwrap();
+    assert_eq!(2, deque.len());
+    assert_eq!(2, requests.lock().unwrap().len());
+}
+
+#[tokio::test]
+async fn transport_with_tower_layers() {
+    let requests = Requests::default();
+    let transport = tower::ServiceBuilder::new()
+        .concurrency_limit(1)
+        .service(test_transport(requests.clone()));
+    let client = GraphClient::from(
+        GraphClientConfiguration::new()
+            .access_token("ACCESS_TOKEN")
+            .transport(transport),
+    );
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(1, requests.lock().unwrap().len());
+}
+
+#[tokio::test]
+async fn token_requests_share_transport() {
+    let requests = Requests::default();
+    let config = GraphClientConfiguration::new().transport(test_transport(requests.clone()));
+
+    let confidential_client =
+        ConfidentialClientApplication::builder("6731de76-14a6-49ae-97bc-6eba6914391e")
+            .with_client_secret("secret")
+            .with_tenant("tenant-id")
+            .with_config(&config)
+            .build();
+    let client = GraphClient::from(config.client_application(confidential_client));
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert!(response.status().is_success());
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(2, requests.len());
+    assert_eq!("/tenant-id/oauth2/v2.0/token", requests[0].uri().path());
+    assert_eq!(
+        "Bearer transport-token",
+        requests[1].headers().get("authorization").unwrap()
+    );
+}
@@ -0,0 +1,50 @@
+use graph_rs_sdk::error::ConfigurationError;
+use graph_rs_sdk::http::Url;
+use graph_rs_sdk::{GraphClient, GraphService};
+use test_tools::mock_server::MockGraphServer;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn trusted_graph_host_endpoint() {
+    let server = MockGraphServer::start().await;
+    let endpoint = server.endpoint();
+    let config = server
+        .client_configuration()
+        .trust_graph_host(Url::parse("https://graph.corp.internal").unwrap())
+        .access_token("token")
+        .wrap_service(move |service: GraphService| {
+            let endpoint = endpoint.clone();
+            tower::service_fn(move |mut request: reqwest::Request| {
+                let url = request.url_mut();
+                if url.host_str() == Some("graph.corp.internal") {
+                    let _ = url.set_scheme(endpoint.scheme());
+                    let _ = url.set_host(endpoint.host_str());
+                    let _ = url.set_port(endpoint.port());
+                }
+                service.clone().oneshot(request)
+            })
+            .boxed_clone()
+        });
+
+    let client = GraphClient::builder()
+        .config(config)
+        .endpoint("https://graph.corp.internal/v1.0")
+        .build()
+        .unwrap();
+    assert_eq!("https://graph.corp.internal/v1.0", client.url().as_str());
+
+    let response = client.me().get_user().send().await.unwrap();
+    assert_eq!(200, response.status().as_u16());
+
+    let requests = server.received_requests().await;
+    assert_eq!("/v1.0/me", requests[0].url.path());
+}
+
+#[test]
+fn untrusted_graph_host_endpoint() {
+    let result = GraphClient::builder()
+        .access_token("token")
+        .endpoint("https://graph.corp.internal/v1.0")
+        .build();
+    assert!(matches!(result, Err(ConfigurationError::HostNotAllowed(_))));
+}
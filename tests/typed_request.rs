@@ -0,0 +1,79 @@
+use graph_rs_sdk::http::TypedRequestHandler;
+use graph_rs_sdk::*;
+use serde::{Deserialize, Serialize};
+use test_tools::mock_server::MockGraphServer;
+
+static USER_ID: &str = "b!CbtYWrofwUGBJWnaJkNwoNrBLp_kC3RKklSXPwrdeP3yH8_qmH9xT5Y6RODPNfYI";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Users {
+    value: Vec<User>,
+}
+
+#[test]
+fn typed_request_url() {
+    let client = Graph::new("");
+
+    let request: TypedRequestHandler<Users> =
+        TypedRequestHandler::new(client.users().list_user()).select(&["id", "displayName"]);
+    assert_eq!(
+        request.url().to_string(),
+        "https://graph.microsoft.com/v1.0/users?%24select=id%2CdisplayName"
+    );
+
+    let request: TypedRequestHandler<User> =
+        TypedRequestHandler::new(client.user(USER_ID).get_user());
+    assert_eq!(request.url().path(), format!("/v1.0/users/{USER_ID}"));
+}
+
+#[test]
+fn typed_request_body() {
+    let client = Graph::new("");
+    let user = User {
+        display_name: Some("Adele Vance".into()),
+        ..Default::default()
+    };
+
+    let request: TypedRequestHandler<User> =
+        TypedRequestHandler::new(client.users().create_user(&user));
+    assert!(!request.is_err());
+    assert_eq!(request.url().path(), "/v1.0/users");
+
+    let request: TypedRequestHandler<()> =
+        TypedRequestHandler::new(client.user(USER_ID).update_user(&user));
+    let request = request.into_blocking();
+    assert_eq!(request.url().path(), format!("/v1.0/users/{USER_ID}"));
+}
+
+#[tokio::test]
+async fn typed_response_body() {
+    let server = MockGraphServer::start().await;
+    let client = server.graph_client();
+    let adele = server.insert_user(serde_json::json!({ "displayName": "Adele Vance" }));
+
+    let request: TypedRequestHandler<Users> = TypedRequestHandler::new(client.users().list_user());
+    let users = request.send().await.unwrap().into_body().unwrap();
+    let user = users
+        .value
+        .iter()
+        .find(|user| user.id.as_deref() == adele["id"].as_str())
+        .unwrap();
+    assert_eq!(user.display_name.as_deref(), Some("Adele Vance"));
+
+    let request: TypedRequestHandler<User> =
+        TypedRequestHandler::new(client.user("unknown").get_user());
+    let error = request.send().await.unwrap().into_body().unwrap_err();
+    assert_eq!(
+        error.code_property().as_deref(),
+        Some("Request_ResourceNotFound")
+    );
+}
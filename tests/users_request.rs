@@ -0,0 +1,55 @@
+use graph_rs_sdk::*;
+use test_tools::vcr::VcrTestClient;
+
+#[tokio::test]
+async fn list_and_get_user() {
+    let test_client = VcrTestClient::new("users_request").unwrap();
+
+    let response = test_client
+        .client
+        .users()
+        .list_user()
+        .select(&["id", "displayName", "mail"])
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    let users = body["value"].as_array().unwrap();
+    assert!(users
+        .iter()
+        .any(|user| user["id"].as_str() == Some(test_client.user_id.as_str())));
+    assert!(users
+        .iter()
+        .all(|user| user.get("userPrincipalName").is_none()));
+
+    let response = test_client
+        .client
+        .user(test_client.user_id.as_str())
+        .get_user()
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(Some(test_client.user_id.as_str()), body["id"].as_str());
+}
+
+#[tokio::test]
+async fn get_missing_user() {
+    let test_client = VcrTestClient::new("users_request").unwrap();
+
+    let response = test_client
+        .client
+        .user("00000000-0000-0000-0000-000000000000")
+        .get_user()
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(404, response.status().as_u16());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        Some("Request_ResourceNotFound"),
+        body["error"]["code"].as_str()
+    );
+}
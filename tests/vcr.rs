@@ -0,0 +1,114 @@
+use graph_rs_sdk::http::Url;
+use graph_rs_sdk::vcr::{Redactor, Vcr, REDACTED};
+use graph_rs_sdk::*;
+use test_tools::vcr::VcrTestClient;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn replay_list_users_paging() {
+    let test_client = VcrTestClient::new("list_users_paging").unwrap();
+
+    let deque = test_client
+        .client
+        .users()
+        .list_user()
+        .top("2")
+        .paging()
+        .json::<serde_json::Value>()
+        .await
+        .unwrap();
+    assert_eq!(2, deque.len());
+
+    let ids: Vec<String> = deque
+        .into_iter()
+        .flat_map(|response| {
+            response.into_body().unwrap()["value"]
+                .as_array()
+                .cloned()
+                .unwrap()
+        })
+        .map(|user| user["id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(4, ids.len());
+    assert!(ids.contains(&test_client.user_id));
+
+    let response = test_client
+        .client
+        .user(test_client.user_id.as_str())
+        .get_user()
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(Some(test_client.user_id.as_str()), body["id"].as_str());
+}
+
+#[tokio::test]
+async fn replay_missing_interaction_is_an_error() {
+    let test_client = VcrTestClient::new("list_users_paging").unwrap();
+
+    let result = test_client.client.groups().list_group().send().await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn record_then_replay() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/users/1234"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "1234",
+            "displayName": "Adele Vance",
+            "mail": "adele@contoso.com"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let cassette_path = std::env::temp_dir().join("graph-rs-sdk-vcr-record-then-replay.json");
+    let vcr = Vcr::record(&cassette_path).redactor(
+        Redactor::new()
+            .replace(mock_server.uri(), "https://graph.microsoft.com/v1.0")
+            .replace("1234", "USER_ID"),
+    );
+    let mut client = GraphClient::from(
+        vcr.configure(
+            GraphClientConfiguration::new()
+                .access_token("token")
+                .https_only(false),
+        ),
+    );
+    client.use_test_endpoint(&Url::parse(mock_server.uri().as_str()).unwrap());
+
+    let response = client.user("1234").get_user().send().await.unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(Some("Adele Vance"), body["displayName"].as_str());
+
+    let cassette = vcr.cassette();
+    assert_eq!(1, cassette.interactions.len());
+    let interaction = &cassette.interactions[0];
+    assert_eq!(
+        "https://graph.microsoft.com/v1.0/users/USER_ID",
+        interaction.request.url
+    );
+    assert_eq!(
+        Some(&REDACTED.to_string()),
+        interaction.request.headers.get("authorization")
+    );
+    vcr.save().unwrap();
+    drop(vcr);
+
+    let vcr = Vcr::replay(&cassette_path)
+        .unwrap()
+        .redactor(Redactor::new().replace("1234", "USER_ID"));
+    let client =
+        GraphClient::from(vcr.configure(GraphClientConfiguration::new().access_token(REDACTED)));
+    let response = client.user("1234").get_user().send().await.unwrap();
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(Some("USER_ID"), body["id"].as_str());
+    assert_eq!(Some(REDACTED), body["displayName"].as_str());
+    assert_eq!(Some(REDACTED), body["mail"].as_str());
+
+    std::fs::remove_file(cassette_path).unwrap();
+}